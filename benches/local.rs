@@ -0,0 +1,158 @@
+//! Benchmarks for local utilities which run without the game, such as
+//! `LocalCostMatrix`, `Position` math and `LocalPathFinder`.
+//!
+//! Run with `cargo bench`. The path search benchmarks also print how many
+//! tiles each search mode expands.
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use screeps::{
+    local::{
+        path_search::{LocalPathFinder, LocalSearchMode},
+        LocalRoomTerrain, TerrainCache,
+    },
+    pathfinder::{LocalCostMatrix, SearchGoal},
+    Position, RoomName,
+};
+
+fn room() -> RoomName {
+    RoomName::new("W5N5").unwrap()
+}
+
+fn cost_matrix(c: &mut Criterion) {
+    let mut matrix = LocalCostMatrix::new();
+    for x in 0..50 {
+        for y in 0..50 {
+            matrix.set(x, y, x ^ y);
+        }
+    }
+
+    c.bench_function("LocalCostMatrix::set all", |b| {
+        b.iter(|| {
+            let mut matrix = LocalCostMatrix::new();
+            for x in 0..50 {
+                for y in 0..50 {
+                    matrix.set(x, y, black_box(x ^ y));
+                }
+            }
+            matrix
+        })
+    });
+    c.bench_function("LocalCostMatrix::get all", |b| {
+        b.iter(|| {
+            let mut sum = 0u32;
+            for x in 0..50 {
+                for y in 0..50 {
+                    sum += u32::from(black_box(&matrix).get(x, y));
+                }
+            }
+            sum
+        })
+    });
+    c.bench_function("LocalCostMatrix::clone", |b| {
+        b.iter(|| black_box(&matrix).clone())
+    });
+}
+
+fn position_math(c: &mut Criterion) {
+    let positions: Vec<Position> = (0..50)
+        .flat_map(|x| (0..50).map(move |y| Position::new(x, y, room())))
+        .collect();
+    let target = Position::new(49, 0, RoomName::new("E3S2").unwrap());
+
+    c.bench_function("Position packed round trip", |b| {
+        b.iter(|| {
+            positions
+                .iter()
+                .map(|pos| Position::from_packed(black_box(pos.packed_repr())))
+                .filter(|pos| pos.x() == 0)
+                .count()
+        })
+    });
+    c.bench_function("Position::get_range_to", |b| {
+        b.iter(|| {
+            positions
+                .iter()
+                .map(|pos| pos.get_range_to(black_box(&target)))
+                .sum::<u32>()
+        })
+    });
+    c.bench_function("Position::get_direction_to", |b| {
+        b.iter(|| {
+            positions
+                .iter()
+                .filter_map(|pos| pos.get_direction_to(black_box(&target)))
+                .count()
+        })
+    });
+    c.bench_function("Position::world_coords round trip", |b| {
+        b.iter(|| {
+            positions
+                .iter()
+                .map(|pos| {
+                    let (x, y) = black_box(pos.world_coords());
+                    Position::from_world_coords(x, y)
+                })
+                .filter(|pos| pos.y() == 0)
+                .count()
+        })
+    });
+}
+
+/// Five rooms in a row, from `W4N1` to `W0N1`, with `last` as the terrain
+/// of `W0N1` and the rest plains.
+fn row_of_rooms(last: LocalRoomTerrain) -> TerrainCache {
+    let mut terrain = TerrainCache::new();
+    for i in 1..5 {
+        terrain.insert(
+            RoomName::new(&format!("W{}N1", i)).unwrap(),
+            LocalRoomTerrain::new_from_bits(Box::new([0; 2500])),
+        );
+    }
+    terrain.insert(RoomName::new("W0N1").unwrap(), last);
+    terrain
+}
+
+fn path_search(c: &mut Criterion) {
+    let highway = row_of_rooms(LocalRoomTerrain::new_from_bits(Box::new([0; 2500])));
+    // a wall around the east side of the middle of W0N1, open to the east,
+    // which A* floods the whole area in front of before going around
+    let mut bits = Box::new([0; 2500]);
+    for y in 5..45 {
+        bits[y * 50 + 20] = 1;
+    }
+    for x in 20..45 {
+        bits[5 * 50 + x] = 1;
+        bits[44 * 50 + x] = 1;
+    }
+    let pocket = row_of_rooms(LocalRoomTerrain::new_from_bits(bits));
+
+    let from = Position::new(10, 20, RoomName::new("W4N1").unwrap());
+    let goal = SearchGoal::new(Position::new(30, 25, RoomName::new("W0N1").unwrap()), 1);
+    let modes = [
+        LocalSearchMode::AStar,
+        LocalSearchMode::JumpPoint,
+        LocalSearchMode::Bidirectional,
+    ];
+
+    for &(scenario, terrain) in &[("highway", &highway), ("pocket", &pocket)] {
+        for &mode in &modes {
+            let search = || {
+                LocalPathFinder::new(terrain)
+                    .max_ops(100_000)
+                    .mode(mode)
+                    .search(from, goal)
+            };
+            let results = search();
+            println!(
+                "LocalPathFinder {} {:?}: {} ops, cost {}",
+                scenario, mode, results.ops, results.cost
+            );
+            c.bench_function(&format!("LocalPathFinder {} {:?}", scenario, mode), |b| {
+                b.iter(|| black_box(search()))
+            });
+        }
+    }
+}
+
+criterion_group!(benches, cost_matrix, position_math, path_search);
+criterion_main!(benches);
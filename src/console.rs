@@ -0,0 +1,74 @@
+//! Writing to the Screeps console.
+//!
+//! The game console only has `console.log`, so [`warn`] and [`error`] color
+//! their output the same way as [`screeps_logging`] does for those levels.
+//! `log`, [`warn`] and [`console_error`] have matching macros taking format
+//! arguments, like [`console_log!`].
+//!
+//! [`screeps_logging`]: crate::screeps_logging
+use log::Level;
+
+use crate::screeps_logging::level_color;
+
+pub fn log(message: &str) {
+    js! { @(no_return)
+        console.log(@{message});
+    }
+}
+
+/// Writes `message` in the warning color.
+pub fn warn(message: &str) {
+    log_colored(Level::Warn, message);
+}
+
+/// Writes `message` in the error color.
+pub fn error(message: &str) {
+    log_colored(Level::Error, message);
+}
+
+/// Writes `message` with `console.log`, and also sends it with `Game.notify`.
+///
+/// This calls the `console_error` function from `javascript/console_error.js`,
+/// which is the same function used to report errors by the build pipeline.
+pub fn console_error(message: &str) {
+    js! { @(no_return)
+        console_error(@{message});
+    }
+}
+
+fn log_colored(level: Level, message: &str) {
+    js! { @(no_return)
+        console.log("<font color=\"" + @{level_color(level)} + "\">" + @{message} + "</font>");
+    }
+}
+
+/// Formats a message and writes it with [`console::log`].
+///
+/// [`console::log`]: crate::console::log
+#[macro_export]
+macro_rules! console_log {
+    ($($arg:tt)*) => {
+        $crate::console::log(&format!($($arg)*))
+    };
+}
+
+/// Formats a message and writes it with [`console::warn`].
+///
+/// [`console::warn`]: crate::console::warn
+#[macro_export]
+macro_rules! console_warn {
+    ($($arg:tt)*) => {
+        $crate::console::warn(&format!($($arg)*))
+    };
+}
+
+/// Formats a message and writes it with [`console::console_error`], which
+/// also sends it with `Game.notify`.
+///
+/// [`console::console_error`]: crate::console::console_error
+#[macro_export]
+macro_rules! console_error {
+    ($($arg:tt)*) => {
+        $crate::console::console_error(&format!($($arg)*))
+    };
+}
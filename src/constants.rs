@@ -4,10 +4,10 @@
 //! <https://github.com/screeps/common/commits/master/lib/constants.js>.
 //!
 //! Currently missing:
-//! - OBSTACLE_OBJECT_TYPES
+//! - OBSTACLE_OBJECT_TYPES (structure types covered by
+//!   [`StructureType::is_obstacle`])
 //! - WORLD_WIDTH / WORLD_HEIGHT (deprecated in Screeps)
-//! - BODYPARTS_ALL, RESOURCES_ALL, COLORS_ALL
-//! - POWER_INFO
+//! - COLORS_ALL
 //!
 //! # Notes on Deserialization
 //!
@@ -70,7 +70,7 @@ pub use self::{
     find::FindConstant,
     look::{Look, LookConstant},
     numbers::*,
-    recipes::FactoryRecipe,
+    recipes::{CommodityChain, FactoryRecipe},
     small_enums::*,
     types::*,
 };
@@ -160,7 +160,7 @@ pub mod power {
             POWER_SPAWN_ENERGY_RATIO, POWER_SPAWN_POWER_CAPACITY,
         },
         small_enums::PowerClass,
-        types::PowerType,
+        types::{PowerInfo, PowerType},
     };
 }
 
@@ -219,8 +219,8 @@ pub mod tower {
 /// [`Nuke`]: crate::objects::Nuke
 pub mod nuke {
     pub use super::numbers::{
-        NUKER_COOLDOWN, NUKER_ENERGY_CAPACITY, NUKER_GHODIUM_CAPACITY, NUKE_DAMAGE_RANGE_0,
-        NUKE_DAMAGE_RANGE_2, NUKE_LAND_TIME, NUKE_RANGE,
+        nuke_damage, NUKER_COOLDOWN, NUKER_ENERGY_CAPACITY, NUKER_GHODIUM_CAPACITY,
+        NUKE_DAMAGE_RANGE_0, NUKE_DAMAGE_RANGE_2, NUKE_LAND_TIME, NUKE_RANGE,
     };
 }
 
@@ -23,6 +23,7 @@ use num_derive::FromPrimitive;
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use stdweb::Reference;
 
+use super::{Direction, ExitDirection};
 use crate::{
     local::Position,
     objects::{
@@ -92,6 +93,10 @@ unsafe impl FindConstant for RoomObject {
     }
 }
 
+/// Finds exit tiles, either on one edge of the room or on all of them.
+///
+/// The single-edge variants convert to and from [`ExitDirection`] and
+/// [`Direction`]; [`Exit::All`] has no direction.
 #[derive(
     Copy, Clone, Debug, FromPrimitive, Deserialize_repr, Serialize_repr, PartialEq, Eq, Hash,
 )]
@@ -129,6 +134,25 @@ impl Exit {
     pub fn all() -> Self {
         Exit::All
     }
+
+    /// The edge of the room this finds exits on, or `None` for [`Exit::All`].
+    #[inline]
+    pub fn direction(self) -> Option<ExitDirection> {
+        match self {
+            Exit::Top => Some(ExitDirection::Top),
+            Exit::Right => Some(ExitDirection::Right),
+            Exit::Bottom => Some(ExitDirection::Bottom),
+            Exit::Left => Some(ExitDirection::Left),
+            Exit::All => None,
+        }
+    }
+
+    /// Finds exits on the edge of the room in the given direction, or `None`
+    /// for diagonal directions.
+    #[inline]
+    pub fn from_direction(dir: Direction) -> Option<Self> {
+        ExitDirection::from_direction(dir).map(Into::into)
+    }
 }
 
 unsafe impl FindConstant for Exit {
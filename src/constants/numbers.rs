@@ -9,7 +9,7 @@ use super::types::{ResourceType, StructureType};
 
 // LOOK_* defined in `look.rs`
 
-// OBSTACLE_OBJECT_TYPES not yet implemented
+// OBSTACLE_OBJECT_TYPES partially implemented by `StructureType::is_obstacle` in `types.rs`
 
 // body parts and their costs defined in `small_enums.rs`
 
@@ -627,6 +627,20 @@ pub const NUKE_DAMAGE_RANGE_0: u32 = 10_000_000;
 /// Damage in hits done by nukes within range 2.
 pub const NUKE_DAMAGE_RANGE_2: u32 = 5_000_000;
 
+/// Damage in hits a nuke does to structures at a given range from where it
+/// lands: [`NUKE_DAMAGE_RANGE_0`] at the point of impact,
+/// [`NUKE_DAMAGE_RANGE_2`] within range 2, and nothing further out.
+///
+/// Creeps anywhere in the room are killed regardless of range.
+#[inline]
+pub fn nuke_damage(range: u32) -> u32 {
+    match range {
+        0 => NUKE_DAMAGE_RANGE_0,
+        1 | 2 => NUKE_DAMAGE_RANGE_2,
+        _ => 0,
+    }
+}
+
 /// Initial hits for factory structures; consider using the
 /// [`StructureType::initial_hits`] function.
 pub const FACTORY_HITS: u32 = 1000;
@@ -827,7 +841,9 @@ pub fn stronghold_rampart_hits(core_level: u32) -> Option<u32> {
 /// [source]: https://github.com/screeps/engine/blob/b2ac4720abe399837b0ba38712aaadfd4a9e9a7e/src/processor/intents/invader-core/stronghold/stronghold.js#L27
 pub const STRONGHOLD_DECAY_TICKS: u32 = 75_000;
 
-// POWER_INFO not yet implemented
-// BODYPARTS_ALL, RESOURCES_ALL, COLORS_ALL not yet implemented
+// POWER_INFO defined by `PowerType::info` in `types.rs`
+// BODYPARTS_ALL defined by `Part::iter_values` in `small_enums.rs`
+// RESOURCES_ALL defined by `ResourceType::iter_values` in `types.rs`
+// COLORS_ALL not yet implemented
 // INTERSHARD_RESOURCES defined in `types.rs`
 // COMMODITIES defined in `recipes.rs`
@@ -15,6 +15,22 @@ pub struct FactoryRecipe {
     pub level: Option<u32>,
 }
 
+/// The production chains factory commodities belong to, named after the
+/// deposit each one starts from.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CommodityChain {
+    /// Commodities made from [`ResourceType::Metal`].
+    Mechanical,
+    /// Commodities made from [`ResourceType::Biomass`].
+    Biological,
+    /// Commodities made from [`ResourceType::Silicon`].
+    Electronical,
+    /// Commodities made from [`ResourceType::Mist`].
+    Mystical,
+    /// Compressed commodities used as components by every chain.
+    Common,
+}
+
 impl ResourceType {
     /// Translates the `REACTIONS` constant.
     #[inline]
@@ -95,6 +111,91 @@ impl ResourceType {
         Some(components)
     }
 
+    /// Translates the `REACTIONS` constant in the other direction, finding the
+    /// compound produced by a lab reaction between two resources.
+    ///
+    /// The order of the two inputs doesn't matter.
+    #[inline]
+    pub fn reaction_product(self, other: ResourceType) -> Option<ResourceType> {
+        use ResourceType::*;
+        let product = match (self, other) {
+            // OH: O + H
+            (Oxygen, Hydrogen) | (Hydrogen, Oxygen) => Hydroxide,
+            // ZK: Z + K
+            (Zynthium, Keanium) | (Keanium, Zynthium) => ZynthiumKeanite,
+            // UL: U + L
+            (Utrium, Lemergium) | (Lemergium, Utrium) => UtriumLemergite,
+            // G: UL + ZK
+            (UtriumLemergite, ZynthiumKeanite) | (ZynthiumKeanite, UtriumLemergite) => Ghodium,
+            // UH: U + H
+            (Utrium, Hydrogen) | (Hydrogen, Utrium) => UtriumHydride,
+            // UH2O: UH + OH
+            (UtriumHydride, Hydroxide) | (Hydroxide, UtriumHydride) => UtriumAcid,
+            // XUH2O: UH2O + X
+            (UtriumAcid, Catalyst) | (Catalyst, UtriumAcid) => CatalyzedUtriumAcid,
+            // UO: U + O
+            (Utrium, Oxygen) | (Oxygen, Utrium) => UtriumOxide,
+            // UHO2: UO + OH
+            (UtriumOxide, Hydroxide) | (Hydroxide, UtriumOxide) => UtriumAlkalide,
+            // XUHO2: UHO2 + X
+            (UtriumAlkalide, Catalyst) | (Catalyst, UtriumAlkalide) => CatalyzedUtriumAlkalide,
+            // KH: K + H
+            (Keanium, Hydrogen) | (Hydrogen, Keanium) => KeaniumHydride,
+            // KH2O: KH + OH
+            (KeaniumHydride, Hydroxide) | (Hydroxide, KeaniumHydride) => KeaniumAcid,
+            // XKH2O: KH2O + X
+            (KeaniumAcid, Catalyst) | (Catalyst, KeaniumAcid) => CatalyzedKeaniumAcid,
+            // KO: K + O
+            (Keanium, Oxygen) | (Oxygen, Keanium) => KeaniumOxide,
+            // KHO2: KO + OH
+            (KeaniumOxide, Hydroxide) | (Hydroxide, KeaniumOxide) => KeaniumAlkalide,
+            // XKHO2: KHO2 + X
+            (KeaniumAlkalide, Catalyst) | (Catalyst, KeaniumAlkalide) => CatalyzedKeaniumAlkalide,
+            // LH: L + H
+            (Lemergium, Hydrogen) | (Hydrogen, Lemergium) => LemergiumHydride,
+            // LH2O: LH + OH
+            (LemergiumHydride, Hydroxide) | (Hydroxide, LemergiumHydride) => LemergiumAcid,
+            // XLH2O: LH2O + X
+            (LemergiumAcid, Catalyst) | (Catalyst, LemergiumAcid) => CatalyzedLemergiumAcid,
+            // LO: L + O
+            (Lemergium, Oxygen) | (Oxygen, Lemergium) => LemergiumOxide,
+            // LHO2: LO + OH
+            (LemergiumOxide, Hydroxide) | (Hydroxide, LemergiumOxide) => LemergiumAlkalide,
+            // XLHO2: LHO2 + X
+            (LemergiumAlkalide, Catalyst) | (Catalyst, LemergiumAlkalide) => {
+                CatalyzedLemergiumAlkalide
+            }
+            // ZH: Z + H
+            (Zynthium, Hydrogen) | (Hydrogen, Zynthium) => ZynthiumHydride,
+            // ZH2O: ZH + OH
+            (ZynthiumHydride, Hydroxide) | (Hydroxide, ZynthiumHydride) => ZynthiumAcid,
+            // XZH2O: ZH2O + X
+            (ZynthiumAcid, Catalyst) | (Catalyst, ZynthiumAcid) => CatalyzedZynthiumAcid,
+            // ZO: Z + O
+            (Zynthium, Oxygen) | (Oxygen, Zynthium) => ZynthiumOxide,
+            // ZHO2: ZO + OH
+            (ZynthiumOxide, Hydroxide) | (Hydroxide, ZynthiumOxide) => ZynthiumAlkalide,
+            // XZHO2: ZHO2 + X
+            (ZynthiumAlkalide, Catalyst) | (Catalyst, ZynthiumAlkalide) => {
+                CatalyzedZynthiumAlkalide
+            }
+            // GH: G + H
+            (Ghodium, Hydrogen) | (Hydrogen, Ghodium) => GhodiumHydride,
+            // GH2O: GH + OH
+            (GhodiumHydride, Hydroxide) | (Hydroxide, GhodiumHydride) => GhodiumAcid,
+            // XGH2O: GH2O + X
+            (GhodiumAcid, Catalyst) | (Catalyst, GhodiumAcid) => CatalyzedGhodiumAcid,
+            // GO: G + O
+            (Ghodium, Oxygen) | (Oxygen, Ghodium) => GhodiumOxide,
+            // GHO2: GO + OH
+            (GhodiumOxide, Hydroxide) | (Hydroxide, GhodiumOxide) => GhodiumAlkalide,
+            // XGHO2: GHO2 + X
+            (GhodiumAlkalide, Catalyst) | (Catalyst, GhodiumAlkalide) => CatalyzedGhodiumAlkalide,
+            _ => return None,
+        };
+        Some(product)
+    }
+
     /// Translates the `REACTION_TIME` constant.
     #[inline]
     pub fn reaction_time(self) -> Option<u32> {
@@ -738,4 +839,143 @@ impl ResourceType {
         };
         Some(recipe)
     }
+
+    /// The factory level of a commodity in one of the [`CommodityChain`]s.
+    ///
+    /// This is `0` for the first commodity of each deposit's chain, which any
+    /// factory can produce, and the level from `COMMODITIES` for the rest.
+    /// Deposits themselves, and resources outside the chains, have no level.
+    pub fn commodity_level(self) -> Option<u8> {
+        use ResourceType::*;
+        let level = match self {
+            Wire | Cell | Alloy | Condensate => 0,
+            Composite | Switch | Phlegm | Tube | Concentrate => 1,
+            Crystal | Transistor | Tissue | Fixtures | Extract => 2,
+            Liquid | Microchip | Muscle | Frame | Spirit => 3,
+            Circuit | Organoid | Hydraulics | Emanation => 4,
+            Device | Organism | Machine | Essence => 5,
+            _ => return None,
+        };
+        Some(level)
+    }
+
+    /// The production chain a commodity, or the deposit it's made from,
+    /// belongs to.
+    pub fn commodity_chain(self) -> Option<CommodityChain> {
+        use ResourceType::*;
+        let chain = match self {
+            Metal | Alloy | Tube | Fixtures | Frame | Hydraulics | Machine => {
+                CommodityChain::Mechanical
+            }
+            Biomass | Cell | Phlegm | Tissue | Muscle | Organoid | Organism => {
+                CommodityChain::Biological
+            }
+            Silicon | Wire | Switch | Transistor | Microchip | Circuit | Device => {
+                CommodityChain::Electronical
+            }
+            Mist | Condensate | Concentrate | Extract | Spirit | Emanation | Essence => {
+                CommodityChain::Mystical
+            }
+            Composite | Crystal | Liquid => CommodityChain::Common,
+            _ => return None,
+        };
+        Some(chain)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CommodityChain, ResourceType};
+
+    #[test]
+    fn reaction_components_is_inverse_of_reaction_product() {
+        for product in ResourceType::iter_values() {
+            if let Some([a, b]) = product.reaction_components() {
+                assert_eq!(a.reaction_product(b), Some(product), "{} + {}", a, b);
+                assert_eq!(b.reaction_product(a), Some(product), "{} + {}", b, a);
+            }
+        }
+        for a in ResourceType::iter_values() {
+            for b in ResourceType::iter_values() {
+                if let Some(product) = a.reaction_product(b) {
+                    let components = product.reaction_components().unwrap();
+                    assert!(
+                        components == [a, b] || components == [b, a],
+                        "{} + {} = {}",
+                        a,
+                        b,
+                        product
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn commodity_level_and_chain_table() {
+        use CommodityChain::*;
+        use ResourceType::*;
+        let expected = [
+            (Metal, None, Some(Mechanical)),
+            (Alloy, Some(0), Some(Mechanical)),
+            (Tube, Some(1), Some(Mechanical)),
+            (Fixtures, Some(2), Some(Mechanical)),
+            (Frame, Some(3), Some(Mechanical)),
+            (Hydraulics, Some(4), Some(Mechanical)),
+            (Machine, Some(5), Some(Mechanical)),
+            (Biomass, None, Some(Biological)),
+            (Cell, Some(0), Some(Biological)),
+            (Phlegm, Some(1), Some(Biological)),
+            (Tissue, Some(2), Some(Biological)),
+            (Muscle, Some(3), Some(Biological)),
+            (Organoid, Some(4), Some(Biological)),
+            (Organism, Some(5), Some(Biological)),
+            (Silicon, None, Some(Electronical)),
+            (Wire, Some(0), Some(Electronical)),
+            (Switch, Some(1), Some(Electronical)),
+            (Transistor, Some(2), Some(Electronical)),
+            (Microchip, Some(3), Some(Electronical)),
+            (Circuit, Some(4), Some(Electronical)),
+            (Device, Some(5), Some(Electronical)),
+            (Mist, None, Some(Mystical)),
+            (Condensate, Some(0), Some(Mystical)),
+            (Concentrate, Some(1), Some(Mystical)),
+            (Extract, Some(2), Some(Mystical)),
+            (Spirit, Some(3), Some(Mystical)),
+            (Emanation, Some(4), Some(Mystical)),
+            (Essence, Some(5), Some(Mystical)),
+            (Composite, Some(1), Some(Common)),
+            (Crystal, Some(2), Some(Common)),
+            (Liquid, Some(3), Some(Common)),
+            (Energy, None, None),
+            (Battery, None, None),
+            (UtriumBar, None, None),
+            (Ghodium, None, None),
+            (Ops, None, None),
+        ];
+        for &(resource, level, chain) in &expected {
+            assert_eq!(resource.commodity_level(), level, "{}", resource);
+            assert_eq!(resource.commodity_chain(), chain, "{}", resource);
+        }
+    }
+
+    #[test]
+    fn commodity_level_matches_factory_recipe_level() {
+        for resource in ResourceType::iter_values() {
+            let recipe_level = resource.commodity_recipe().and_then(|recipe| recipe.level);
+            match resource.commodity_level() {
+                Some(0) | None => assert_eq!(recipe_level, None, "{}", resource),
+                Some(level) => assert_eq!(recipe_level, Some(u32::from(level)), "{}", resource),
+            }
+        }
+    }
+
+    #[test]
+    fn every_leveled_commodity_belongs_to_a_chain() {
+        for resource in ResourceType::iter_values() {
+            if resource.commodity_level().is_some() {
+                assert!(resource.commodity_chain().is_some(), "{}", resource);
+            }
+        }
+    }
 }
@@ -1,9 +1,14 @@
 //! Various constants translated as small enums.
-use std::{borrow::Cow, fmt, str::FromStr};
+use std::{
+    borrow::Cow,
+    error, fmt,
+    ops::{BitOr, BitOrAssign},
+    str::FromStr,
+};
 
 use enum_iterator::IntoEnumIterator;
 use num_derive::FromPrimitive;
-use parse_display::FromStr;
+use parse_display::{Display, FromStr};
 use serde::{
     de::{Deserializer, Error as _, Unexpected},
     Deserialize, Serialize,
@@ -12,13 +17,20 @@ use serde_repr::{Deserialize_repr, Serialize_repr};
 
 use super::{
     find,
-    numbers::{TERRAIN_MASK_SWAMP, TERRAIN_MASK_WALL},
+    numbers::{TERRAIN_MASK_LAVA, TERRAIN_MASK_SWAMP, TERRAIN_MASK_WALL},
 };
 
+/// The result of calling a game method which can fail.
+///
+/// Most actions only take effect at the end of the tick, and a return code
+/// other than `Ok` means the action was never scheduled. This type is
+/// `#[must_use]` so that failed actions aren't silently ignored; use
+/// [`ReturnCode::as_result`] to handle them with `?`.
 #[derive(
     Debug, PartialEq, Eq, Clone, Copy, FromPrimitive, Hash, Deserialize_repr, Serialize_repr,
 )]
 #[repr(i16)]
+#[must_use = "this `ReturnCode` may be an error, which should be handled"]
 pub enum ReturnCode {
     Ok = 0,
     NotOwner = -1,
@@ -42,6 +54,18 @@ impl ReturnCode {
     ///
     /// `ReturnCode::Ok` is turned into `Result::Ok`, all other codes are turned
     /// into `Result::Err(code)`
+    ///
+    /// ```no_run
+    /// use screeps::{game, ReturnCode, SharedCreepProperties};
+    ///
+    /// fn say_hello() -> Result<(), ReturnCode> {
+    ///     for creep in game::creeps::values() {
+    ///         creep.say("hello", true).as_result()?;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// # let _ = say_hello();
+    /// ```
     #[inline]
     pub fn as_result(self) -> Result<(), Self> {
         match self {
@@ -51,6 +75,31 @@ impl ReturnCode {
     }
 }
 
+impl fmt::Display for ReturnCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            ReturnCode::Ok => "the operation has been scheduled successfully",
+            ReturnCode::NotOwner => "you are not the owner of this object",
+            ReturnCode::NoPath => "no path to the target could be found",
+            ReturnCode::NameExists => "an object with the same name already exists",
+            ReturnCode::Busy => "the object is busy (still spawning or otherwise unavailable)",
+            ReturnCode::NotFound => "the requested object or resource was not found",
+            ReturnCode::NotEnough => "not enough energy or resources to perform this action",
+            ReturnCode::InvalidTarget => "the target is not valid for this action",
+            ReturnCode::Full => "the target cannot receive any more",
+            ReturnCode::NotInRange => "the target is too far away",
+            ReturnCode::InvalidArgs => "the arguments provided are invalid",
+            ReturnCode::Tired => "the object is fatigued or its cooldown hasn't expired",
+            ReturnCode::NoBodypart => "there are no active body parts for this action",
+            ReturnCode::RclNotEnough => "the room controller level is insufficient",
+            ReturnCode::GclNotEnough => "your global control level is insufficient",
+        };
+        write!(f, "{} ({})", msg, *self as i16)
+    }
+}
+
+impl error::Error for ReturnCode {}
+
 js_deserializable!(ReturnCode);
 
 #[derive(
@@ -139,6 +188,57 @@ pub enum ExitDirection {
     Left = Direction::Left as u8,
 }
 
+impl ExitDirection {
+    /// Converts a direction, returning `None` for diagonals.
+    #[inline]
+    pub fn from_direction(dir: Direction) -> Option<Self> {
+        match dir {
+            Direction::Top => Some(ExitDirection::Top),
+            Direction::Right => Some(ExitDirection::Right),
+            Direction::Bottom => Some(ExitDirection::Bottom),
+            Direction::Left => Some(ExitDirection::Left),
+            _ => None,
+        }
+    }
+
+    /// Finds which edge of a room the given room coordinates are on, if any.
+    ///
+    /// Corner tiles are on two edges at once and are always walls, so they
+    /// return `None`.
+    ///
+    /// ```
+    /// use screeps::ExitDirection;
+    ///
+    /// assert_eq!(ExitDirection::from_edge(20, 0), Some(ExitDirection::Top));
+    /// assert_eq!(ExitDirection::from_edge(0, 20), Some(ExitDirection::Left));
+    /// assert_eq!(ExitDirection::from_edge(20, 20), None);
+    /// assert_eq!(ExitDirection::from_edge(49, 49), None);
+    /// ```
+    #[inline]
+    pub fn from_edge(x: u32, y: u32) -> Option<Self> {
+        match (x, y) {
+            (0, 0) | (0, 49) | (49, 0) | (49, 49) => None,
+            (_, 0) => Some(ExitDirection::Top),
+            (49, _) => Some(ExitDirection::Right),
+            (_, 49) => Some(ExitDirection::Bottom),
+            (0, _) => Some(ExitDirection::Left),
+            _ => None,
+        }
+    }
+
+    /// Whether the given room coordinates are on this edge of the room, like
+    /// `y == 0` for [`ExitDirection::Top`].
+    #[inline]
+    pub fn is_on_edge(self, x: u32, y: u32) -> bool {
+        match self {
+            ExitDirection::Top => y == 0,
+            ExitDirection::Right => x == 49,
+            ExitDirection::Bottom => y == 49,
+            ExitDirection::Left => x == 0,
+        }
+    }
+}
+
 impl From<ExitDirection> for find::Exit {
     #[inline]
     fn from(dir: ExitDirection) -> Self {
@@ -223,10 +323,102 @@ impl Terrain {
             D::Error::invalid_value(Unexpected::Str(&s), &r#""plain", "wall" or "swamp""#)
         })
     }
+
+    /// Whether creeps can move onto this terrain.
+    pub fn is_walkable(self) -> bool {
+        self != Terrain::Wall
+    }
+
+    pub fn is_swamp(self) -> bool {
+        self == Terrain::Swamp
+    }
 }
 
 js_deserializable!(Terrain);
 
+/// The `TERRAIN_MASK_*` bits of a single tile, as stored in
+/// [`RoomTerrain::get_raw_buffer`] and returned by `Room.Terrain.get`.
+///
+/// A tile can have more than one bit set; walls take priority when converting
+/// to [`Terrain`], followed by swamps.
+///
+/// [`RoomTerrain::get_raw_buffer`]: crate::objects::RoomTerrain::get_raw_buffer
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TerrainMask(u8);
+
+impl TerrainMask {
+    pub const WALL: TerrainMask = TerrainMask(TERRAIN_MASK_WALL);
+    pub const SWAMP: TerrainMask = TerrainMask(TERRAIN_MASK_SWAMP);
+    /// Not implemented in game.
+    pub const LAVA: TerrainMask = TerrainMask(TERRAIN_MASK_LAVA);
+
+    /// A mask with no bits set, meaning plain terrain.
+    pub const fn empty() -> TerrainMask {
+        TerrainMask(0)
+    }
+
+    /// Creates a mask from raw bits, dropping any which aren't a
+    /// `TERRAIN_MASK_*` value.
+    pub const fn from_bits_truncate(bits: u8) -> TerrainMask {
+        TerrainMask(bits & (TERRAIN_MASK_WALL | TERRAIN_MASK_SWAMP | TERRAIN_MASK_LAVA))
+    }
+
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Whether every bit set in `other` is also set in `self`.
+    pub const fn contains(self, other: TerrainMask) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Whether creeps can move onto this tile.
+    pub const fn is_walkable(self) -> bool {
+        !self.contains(TerrainMask::WALL)
+    }
+
+    /// Whether this tile is a swamp which creeps can move onto.
+    pub const fn is_swamp(self) -> bool {
+        self.is_walkable() && self.contains(TerrainMask::SWAMP)
+    }
+}
+
+impl BitOr for TerrainMask {
+    type Output = TerrainMask;
+
+    fn bitor(self, other: TerrainMask) -> TerrainMask {
+        TerrainMask(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for TerrainMask {
+    fn bitor_assign(&mut self, other: TerrainMask) {
+        self.0 |= other.0;
+    }
+}
+
+impl From<TerrainMask> for Terrain {
+    fn from(mask: TerrainMask) -> Terrain {
+        if mask.contains(TerrainMask::WALL) {
+            Terrain::Wall
+        } else if mask.contains(TerrainMask::SWAMP) {
+            Terrain::Swamp
+        } else {
+            Terrain::Plain
+        }
+    }
+}
+
+impl From<Terrain> for TerrainMask {
+    fn from(terrain: Terrain) -> TerrainMask {
+        TerrainMask(terrain as u8)
+    }
+}
+
 /// Creep part types.
 ///
 /// *Note:* This constant's `TryFrom<Value>`, `Serialize` and `Deserialize`
@@ -235,8 +427,24 @@ js_deserializable!(Terrain);
 /// `__part_str_to_num` JavaScript functions, [`FromStr`][std::str::FromStr] or
 /// [`Part::deserialize_from_str`].
 ///
+/// `Part`'s `FromStr`, `Display` and `ToString` representations accurately
+/// represent the strings the game constant uses.
+///
 /// See the [module-level documentation][crate::constants] for more details.
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Serialize_repr, Deserialize_repr, FromStr)]
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+    Hash,
+    FromPrimitive,
+    Serialize_repr,
+    Deserialize_repr,
+    Display,
+    FromStr,
+    IntoEnumIterator,
+)]
 #[repr(u8)]
 #[display(style = "snake_case")]
 pub enum Part {
@@ -277,6 +485,39 @@ impl Part {
             )
         })
     }
+
+    /// Translates the `BODYPARTS_ALL` constant.
+    pub fn iter_values() -> impl Iterator<Item = Part> {
+        <Part as enum_iterator::IntoEnumIterator>::into_enum_iter()
+    }
+
+    /// Position of this part in the order used by [`Part::sort_for_damage`],
+    /// lowest first.
+    #[inline]
+    pub fn damage_order(self) -> u8 {
+        match self {
+            Part::Tough => 0,
+            Part::Work => 1,
+            Part::Carry => 2,
+            Part::Claim => 3,
+            Part::Attack => 4,
+            Part::RangedAttack => 5,
+            Part::Heal => 6,
+            Part::Move => 7,
+        }
+    }
+
+    /// Sorts a body so that the parts a creep can most afford to lose come
+    /// first.
+    ///
+    /// The engine applies damage to a creep's parts in body order, so parts at
+    /// the start of the body are destroyed first. This puts `TOUGH` parts in
+    /// front, followed by utility and combat parts, keeping `HEAL` and `MOVE`
+    /// alive for as long as possible. The sort is stable, so parts of the same
+    /// type keep their relative order.
+    pub fn sort_for_damage(body: &mut [Part]) {
+        body.sort_by_key(|p| p.damage_order());
+    }
 }
 
 js_deserializable!(Part);
@@ -1,6 +1,7 @@
 //! `*Type` constants.
 use std::{borrow::Cow, str::FromStr};
 
+use enum_iterator::IntoEnumIterator;
 use num_derive::FromPrimitive;
 use parse_display::{Display, FromStr};
 use serde::{
@@ -9,6 +10,8 @@ use serde::{
 };
 use serde_repr::{Deserialize_repr, Serialize_repr};
 
+use super::Part;
+
 /// Translates `STRUCTURE_*` constants.
 ///
 /// *Note:* This constant's `TryFrom<Value>`, `Serialize` and `Deserialize`
@@ -177,9 +180,9 @@ impl StructureType {
             Observer => OBSERVER_HITS,
             PowerBank => POWER_BANK_HITS,
             PowerSpawn => POWER_SPAWN_HITS,
-            Extractor => EXTENSION_HITS,
+            Extractor => EXTRACTOR_HITS,
             Lab => LAB_HITS,
-            Terminal => TOWER_HITS,
+            Terminal => TERMINAL_HITS,
             Container => CONTAINER_HITS,
             Nuker => NUKER_HITS,
             Factory => FACTORY_HITS,
@@ -189,6 +192,22 @@ impl StructureType {
         Some(hits)
     }
 
+    /// Translates the structure types in the `OBSTACLE_OBJECT_TYPES`
+    /// constant; whether creeps are unable to move onto this structure.
+    ///
+    /// Ramparts aren't included, as whether they block movement depends on
+    /// their owner and whether they're public.
+    #[inline]
+    pub fn is_obstacle(self) -> bool {
+        use self::StructureType::*;
+
+        match self {
+            Spawn | Extension | Wall | Link | Storage | Tower | Observer | PowerBank
+            | PowerSpawn | Lab | Terminal | Nuker | Factory | InvaderCore | Controller => true,
+            Road | Rampart | KeeperLair | Portal | Extractor | Container => false,
+        }
+    }
+
     /// Helper function for deserializing from a string rather than a fake
     /// integer value.
     pub fn deserialize_from_str<'de, D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
@@ -256,7 +275,17 @@ js_deserializable!(IntershardResourceType);
 ///
 /// See the [module-level documentation][crate::constants] for more details.
 #[derive(
-    Copy, Clone, Debug, Display, PartialEq, Eq, Hash, Serialize_repr, Deserialize_repr, FromStr,
+    Copy,
+    Clone,
+    Debug,
+    Display,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize_repr,
+    Deserialize_repr,
+    FromStr,
+    IntoEnumIterator,
 )]
 #[repr(u16)]
 pub enum ResourceType {
@@ -532,7 +561,30 @@ pub enum Boost {
     Tough(f64),
 }
 
+impl Boost {
+    /// The body part this boost applies to.
+    pub fn part(self) -> Part {
+        match self {
+            Boost::Harvest(_)
+            | Boost::BuildAndRepair(_)
+            | Boost::Dismantle(_)
+            | Boost::UpgradeController(_) => Part::Work,
+            Boost::Attack(_) => Part::Attack,
+            Boost::RangedAttack(_) => Part::RangedAttack,
+            Boost::Heal(_) => Part::Heal,
+            Boost::Carry(_) => Part::Carry,
+            Boost::Move(_) => Part::Move,
+            Boost::Tough(_) => Part::Tough,
+        }
+    }
+}
+
 impl ResourceType {
+    /// Translates the `RESOURCES_ALL` constant.
+    pub fn iter_values() -> impl Iterator<Item = ResourceType> {
+        <ResourceType as enum_iterator::IntoEnumIterator>::into_enum_iter()
+    }
+
     /// Translates the `BOOSTS` constant.
     #[inline]
     pub fn boost(self) -> Option<Boost> {
@@ -584,7 +636,7 @@ impl ResourceType {
             //     rangedAttack: 3,
             //     rangedMassAttack: 3
             // },
-            KeaniumAlkalide => Boost::RangedAttack(4.0),
+            KeaniumAlkalide => Boost::RangedAttack(3.0),
             // XKHO2: {
             //     rangedAttack: 4,
             //     rangedMassAttack: 4
@@ -674,6 +726,41 @@ impl ResourceType {
         Some(boost)
     }
 
+    /// The tier of a boost compound: `1` for the first compounds made from
+    /// base minerals, `2` for the acids and alkalides made from those, and `3`
+    /// for the catalyzed compounds.
+    ///
+    /// Higher tiers of the same boost are always stronger.
+    pub fn boost_tier(self) -> Option<u8> {
+        use ResourceType::*;
+        let tier = match self {
+            UtriumHydride | UtriumOxide | KeaniumHydride | KeaniumOxide | LemergiumHydride
+            | LemergiumOxide | ZynthiumHydride | ZynthiumOxide | GhodiumHydride | GhodiumOxide => 1,
+            UtriumAcid | UtriumAlkalide | KeaniumAcid | KeaniumAlkalide | LemergiumAcid
+            | LemergiumAlkalide | ZynthiumAcid | ZynthiumAlkalide | GhodiumAcid
+            | GhodiumAlkalide => 2,
+            CatalyzedUtriumAcid
+            | CatalyzedUtriumAlkalide
+            | CatalyzedKeaniumAcid
+            | CatalyzedKeaniumAlkalide
+            | CatalyzedLemergiumAcid
+            | CatalyzedLemergiumAlkalide
+            | CatalyzedZynthiumAcid
+            | CatalyzedZynthiumAlkalide
+            | CatalyzedGhodiumAcid
+            | CatalyzedGhodiumAlkalide => 3,
+            // non-boost resources
+            _ => return None,
+        };
+        Some(tier)
+    }
+
+    /// The body part a boost compound applies to.
+    #[inline]
+    pub fn boosted_part(self) -> Option<Part> {
+        self.boost().map(Boost::part)
+    }
+
     /// Helper function for deserializing from a string rather than a fake
     /// integer value.
     pub fn deserialize_from_str<'de, D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
@@ -868,6 +955,7 @@ pub enum PowerType {
     OperateTerminal = 8,
     DisruptSpawn = 9,
     DisruptTower = 10,
+    DisruptSource = 11,
     Shield = 12,
     RegenSource = 13,
     RegenMineral = 14,
@@ -880,6 +968,211 @@ pub enum PowerType {
 
 js_deserializable!(PowerType);
 
+/// One power's entry in the `POWER_INFO` constant.
+///
+/// Values which vary with the power's level are stored as arrays indexed by
+/// `level - 1`; values which don't are repeated for every level.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PowerInfo {
+    /// The class of power creep which can learn this power.
+    pub class: PowerCreepClass,
+    /// The power creep level needed to learn each level of this power.
+    pub level: [u32; 5],
+    /// Ticks before the power can be used again.
+    pub cooldown: u32,
+    /// Ticks the power's effect lasts, if it has a lasting effect.
+    pub duration: Option<[u32; 5]>,
+    /// Range to the target, if the power has a target.
+    pub range: Option<u32>,
+    /// Ops consumed by using the power, if any.
+    pub ops: Option<[u32; 5]>,
+    /// Energy consumed by using the power, if any.
+    pub energy: Option<u32>,
+    /// Ticks between each time the effect applies, for effects which apply
+    /// periodically.
+    pub period: Option<u32>,
+    /// The strength of the power's effect; what this means depends on the
+    /// power.
+    pub effect: Option<[f64; 5]>,
+}
+
+impl PowerType {
+    /// Translates the `POWER_INFO` constant.
+    pub fn info(self) -> PowerInfo {
+        use PowerType::*;
+
+        const STANDARD_LEVELS: [u32; 5] = [0, 2, 7, 14, 22];
+        const LATE_LEVELS: [u32; 5] = [10, 11, 12, 14, 22];
+        const LAST_LEVELS: [u32; 5] = [20, 21, 22, 23, 24];
+
+        let base = PowerInfo {
+            class: PowerCreepClass::Operator,
+            level: STANDARD_LEVELS,
+            cooldown: 0,
+            duration: None,
+            range: None,
+            ops: None,
+            energy: None,
+            period: None,
+            effect: None,
+        };
+        match self {
+            GenerateOps => PowerInfo {
+                cooldown: 50,
+                effect: Some([1.0, 2.0, 4.0, 6.0, 8.0]),
+                ..base
+            },
+            OperateSpawn => PowerInfo {
+                cooldown: 300,
+                duration: Some([1000; 5]),
+                range: Some(3),
+                ops: Some([100; 5]),
+                effect: Some([0.9, 0.7, 0.5, 0.35, 0.2]),
+                ..base
+            },
+            OperateTower => PowerInfo {
+                cooldown: 10,
+                duration: Some([100; 5]),
+                range: Some(3),
+                ops: Some([10; 5]),
+                effect: Some([1.1, 1.2, 1.3, 1.4, 1.5]),
+                ..base
+            },
+            OperateStorage => PowerInfo {
+                cooldown: 800,
+                duration: Some([1000; 5]),
+                range: Some(3),
+                ops: Some([100; 5]),
+                effect: Some([
+                    500_000.0,
+                    1_000_000.0,
+                    2_000_000.0,
+                    4_000_000.0,
+                    7_000_000.0,
+                ]),
+                ..base
+            },
+            OperateLab => PowerInfo {
+                cooldown: 50,
+                duration: Some([1000; 5]),
+                range: Some(3),
+                ops: Some([10; 5]),
+                effect: Some([2.0, 4.0, 6.0, 8.0, 10.0]),
+                ..base
+            },
+            OperateExtension => PowerInfo {
+                cooldown: 50,
+                range: Some(3),
+                ops: Some([2; 5]),
+                effect: Some([0.2, 0.4, 0.6, 0.8, 1.0]),
+                ..base
+            },
+            OperateObserver => PowerInfo {
+                cooldown: 400,
+                duration: Some([200, 400, 600, 800, 1000]),
+                range: Some(3),
+                ops: Some([10; 5]),
+                ..base
+            },
+            OperateTerminal => PowerInfo {
+                cooldown: 500,
+                duration: Some([1000; 5]),
+                range: Some(3),
+                ops: Some([100; 5]),
+                effect: Some([0.9, 0.8, 0.7, 0.6, 0.5]),
+                ..base
+            },
+            DisruptSpawn => PowerInfo {
+                cooldown: 5,
+                duration: Some([1, 2, 3, 4, 5]),
+                range: Some(20),
+                ops: Some([10; 5]),
+                ..base
+            },
+            DisruptTower => PowerInfo {
+                cooldown: 0,
+                duration: Some([5; 5]),
+                range: Some(50),
+                ops: Some([10; 5]),
+                effect: Some([0.9, 0.8, 0.7, 0.6, 0.5]),
+                ..base
+            },
+            DisruptSource => PowerInfo {
+                cooldown: 100,
+                duration: Some([100, 200, 300, 400, 500]),
+                range: Some(3),
+                ops: Some([100; 5]),
+                ..base
+            },
+            Shield => PowerInfo {
+                cooldown: 20,
+                duration: Some([50; 5]),
+                energy: Some(100),
+                effect: Some([5000.0, 10_000.0, 15_000.0, 20_000.0, 25_000.0]),
+                ..base
+            },
+            RegenSource => PowerInfo {
+                level: LATE_LEVELS,
+                cooldown: 100,
+                duration: Some([300; 5]),
+                range: Some(3),
+                period: Some(15),
+                effect: Some([50.0, 100.0, 150.0, 200.0, 250.0]),
+                ..base
+            },
+            RegenMineral => PowerInfo {
+                level: LATE_LEVELS,
+                cooldown: 100,
+                duration: Some([100; 5]),
+                range: Some(3),
+                period: Some(10),
+                effect: Some([2.0, 4.0, 6.0, 8.0, 10.0]),
+                ..base
+            },
+            DisruptTerminal => PowerInfo {
+                level: LAST_LEVELS,
+                cooldown: 8,
+                duration: Some([10; 5]),
+                range: Some(50),
+                ops: Some([50, 40, 30, 20, 10]),
+                ..base
+            },
+            OperatePower => PowerInfo {
+                level: LATE_LEVELS,
+                cooldown: 800,
+                duration: Some([1000; 5]),
+                range: Some(3),
+                ops: Some([200; 5]),
+                effect: Some([1.0, 2.0, 3.0, 4.0, 5.0]),
+                ..base
+            },
+            Fortify => PowerInfo {
+                cooldown: 5,
+                duration: Some([1, 2, 3, 4, 5]),
+                range: Some(3),
+                ops: Some([5; 5]),
+                ..base
+            },
+            OperateController => PowerInfo {
+                level: LAST_LEVELS,
+                cooldown: 800,
+                duration: Some([1000; 5]),
+                range: Some(3),
+                ops: Some([200; 5]),
+                effect: Some([10.0, 20.0, 30.0, 40.0, 50.0]),
+                ..base
+            },
+            OperateFactory => PowerInfo {
+                cooldown: 800,
+                duration: Some([1000; 5]),
+                range: Some(3),
+                ops: Some([100; 5]),
+                ..base
+            },
+        }
+    }
+}
+
 /// Translates the `EFFECT_*` constants, which are natural effect types
 #[derive(
     Copy, Clone, Debug, PartialEq, Eq, Hash, FromPrimitive, Serialize_repr, Deserialize_repr,
@@ -917,6 +1210,7 @@ impl<'de> Deserialize<'de> for EffectType {
             8 => EffectType::PowerEffect(PowerType::OperateTerminal),
             9 => EffectType::PowerEffect(PowerType::DisruptSpawn),
             10 => EffectType::PowerEffect(PowerType::DisruptTower),
+            11 => EffectType::PowerEffect(PowerType::DisruptSource),
             12 => EffectType::PowerEffect(PowerType::Shield),
             13 => EffectType::PowerEffect(PowerType::RegenSource),
             14 => EffectType::PowerEffect(PowerType::RegenMineral),
@@ -938,3 +1232,110 @@ impl<'de> Deserialize<'de> for EffectType {
         Ok(effect_type)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::mem;
+
+    use super::{Boost, Part, ResourceType};
+
+    #[test]
+    fn boost_tier_and_boosted_part_table() {
+        use ResourceType::*;
+        let expected = [
+            (UtriumHydride, 1, Part::Attack),
+            (UtriumAcid, 2, Part::Attack),
+            (CatalyzedUtriumAcid, 3, Part::Attack),
+            (UtriumOxide, 1, Part::Work),
+            (UtriumAlkalide, 2, Part::Work),
+            (CatalyzedUtriumAlkalide, 3, Part::Work),
+            (KeaniumHydride, 1, Part::Carry),
+            (KeaniumAcid, 2, Part::Carry),
+            (CatalyzedKeaniumAcid, 3, Part::Carry),
+            (KeaniumOxide, 1, Part::RangedAttack),
+            (KeaniumAlkalide, 2, Part::RangedAttack),
+            (CatalyzedKeaniumAlkalide, 3, Part::RangedAttack),
+            (LemergiumHydride, 1, Part::Work),
+            (LemergiumAcid, 2, Part::Work),
+            (CatalyzedLemergiumAcid, 3, Part::Work),
+            (LemergiumOxide, 1, Part::Heal),
+            (LemergiumAlkalide, 2, Part::Heal),
+            (CatalyzedLemergiumAlkalide, 3, Part::Heal),
+            (ZynthiumHydride, 1, Part::Work),
+            (ZynthiumAcid, 2, Part::Work),
+            (CatalyzedZynthiumAcid, 3, Part::Work),
+            (ZynthiumOxide, 1, Part::Move),
+            (ZynthiumAlkalide, 2, Part::Move),
+            (CatalyzedZynthiumAlkalide, 3, Part::Move),
+            (GhodiumHydride, 1, Part::Work),
+            (GhodiumAcid, 2, Part::Work),
+            (CatalyzedGhodiumAcid, 3, Part::Work),
+            (GhodiumOxide, 1, Part::Tough),
+            (GhodiumAlkalide, 2, Part::Tough),
+            (CatalyzedGhodiumAlkalide, 3, Part::Tough),
+        ];
+        for &(resource, tier, part) in &expected {
+            assert_eq!(resource.boost_tier(), Some(tier), "{}", resource);
+            assert_eq!(resource.boosted_part(), Some(part), "{}", resource);
+        }
+        for &resource in &[Energy, Hydrogen, Catalyst, Hydroxide, Ghodium, Ops, Metal] {
+            assert_eq!(resource.boost_tier(), None, "{}", resource);
+            assert_eq!(resource.boosted_part(), None, "{}", resource);
+        }
+    }
+
+    #[test]
+    fn boost_tier_matches_reaction_components() {
+        for resource in ResourceType::iter_values() {
+            assert_eq!(
+                resource.boost_tier().is_some(),
+                resource.boost().is_some(),
+                "{}",
+                resource
+            );
+            let tier = match resource.boost_tier() {
+                Some(tier) => tier,
+                None => continue,
+            };
+            let components = resource.reaction_components().unwrap();
+            let expected = if components.contains(&ResourceType::Catalyst) {
+                3
+            } else if components.contains(&ResourceType::Hydroxide) {
+                2
+            } else {
+                1
+            };
+            assert_eq!(tier, expected, "{}", resource);
+        }
+    }
+
+    #[test]
+    fn higher_boost_tiers_are_stronger() {
+        fn strength(boost: Boost) -> f64 {
+            match boost {
+                // tough boosts multiply damage taken, so smaller is stronger
+                Boost::Tough(multiplier) => -multiplier,
+                Boost::Harvest(multiplier)
+                | Boost::BuildAndRepair(multiplier)
+                | Boost::Dismantle(multiplier)
+                | Boost::UpgradeController(multiplier)
+                | Boost::Attack(multiplier)
+                | Boost::RangedAttack(multiplier)
+                | Boost::Heal(multiplier)
+                | Boost::Carry(multiplier)
+                | Boost::Move(multiplier) => multiplier,
+            }
+        }
+
+        let boosts: Vec<_> = ResourceType::iter_values()
+            .filter_map(|resource| Some((resource.boost()?, resource.boost_tier()?)))
+            .collect();
+        for &(boost, tier) in &boosts {
+            for &(other, other_tier) in &boosts {
+                if mem::discriminant(&boost) == mem::discriminant(&other) && tier < other_tier {
+                    assert!(strength(boost) < strength(other), "{:?} {:?}", boost, other);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,370 @@
+//! Placing construction sites from a prioritized list.
+//!
+//! A [`ConstructionQueue`] holds the structures a room should eventually
+//! have, most important first. Each call to [`ConstructionQueue::place`]
+//! places sites for as many of them as the room's controller level and the
+//! construction site limits allow, and reports which couldn't be placed and
+//! why.
+//!
+//! ```no_run
+//! use screeps::{construction::ConstructionQueue, game, Position, RoomName, StructureType};
+//!
+//! let room_name = RoomName::new("W1N1").unwrap();
+//! let room = game::rooms::get(room_name).unwrap();
+//!
+//! let report = ConstructionQueue::new()
+//!     .with(Position::new(25, 25, room_name), StructureType::Spawn)
+//!     .with(Position::new(25, 27, room_name), StructureType::Extension)
+//!     .max_sites_per_room(5)
+//!     .max_sites_per_tick(2)
+//!     .place(&room);
+//!
+//! for (pos, ty, reason) in &report.blocked {
+//!     log::debug!("couldn't place {:?} at {}: {:?}", ty, pos, reason);
+//! }
+//! ```
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    constants::{find, ReturnCode, StructureType, MAX_CONSTRUCTION_SITES},
+    game,
+    local::{Position, RoomName},
+    objects::{HasPosition, Room, StructureProperties},
+};
+
+/// Why a structure in a [`ConstructionQueue`] wasn't placed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Blocked {
+    /// The room already has as many of this type, including construction
+    /// sites, as `CONTROLLER_STRUCTURES` allows at its controller level.
+    ControllerLevel,
+    /// The limit on construction sites was reached, either
+    /// `MAX_CONSTRUCTION_SITES` across all rooms,
+    /// [`ConstructionQueue::max_sites_per_room`] or
+    /// [`ConstructionQueue::max_sites_per_tick`].
+    SiteLimit,
+    /// `Room.createConstructionSite` returned an error, such as
+    /// `ReturnCode::InvalidTarget` for a tile which already has a structure.
+    Failed(ReturnCode),
+}
+
+/// What a call to [`ConstructionQueue::place`] did with each structure in the
+/// queue which isn't already built or placed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConstructionReport {
+    /// Structures construction sites were placed for this tick, in queue
+    /// order.
+    pub placed: Vec<(Position, StructureType)>,
+    /// Structures which couldn't be placed, in queue order.
+    pub blocked: Vec<(Position, StructureType, Blocked)>,
+}
+
+/// Structures to place construction sites for, in priority order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConstructionQueue {
+    items: Vec<(Position, StructureType)>,
+    max_sites_per_room: Option<u32>,
+    max_sites_per_tick: Option<u32>,
+}
+
+impl ConstructionQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a structure to the end of the queue.
+    pub fn with(mut self, pos: Position, structure_type: StructureType) -> Self {
+        self.items.push((pos, structure_type));
+        self
+    }
+
+    /// Adds structures to the end of the queue, in order.
+    pub fn extend<I>(mut self, items: I) -> Self
+    where
+        I: IntoIterator<Item = (Position, StructureType)>,
+    {
+        self.items.extend(items);
+        self
+    }
+
+    /// Sets the most construction sites of yours a room may have at once,
+    /// counting ones placed earlier - default is no limit beyond
+    /// `MAX_CONSTRUCTION_SITES`.
+    ///
+    /// Keeping this low stops builders from spreading their energy across
+    /// many sites.
+    pub fn max_sites_per_room(mut self, max: u32) -> Self {
+        self.max_sites_per_room = Some(max);
+        self
+    }
+
+    /// Sets the most construction sites placed by one call to
+    /// [`ConstructionQueue::place`] - default is no limit.
+    ///
+    /// Each site placed is an intent costing CPU, so this spreads placing a
+    /// freshly planned room over several ticks.
+    pub fn max_sites_per_tick(mut self, max: u32) -> Self {
+        self.max_sites_per_tick = Some(max);
+        self
+    }
+
+    pub fn items(&self) -> &[(Position, StructureType)] {
+        &self.items
+    }
+
+    /// Places construction sites in `room` for the queued structures in it,
+    /// in order.
+    ///
+    /// Structures which are already built, or already have a construction
+    /// site, are skipped and left out of the report. The rest are placed
+    /// unless that would go over `CONTROLLER_STRUCTURES` for the room's
+    /// controller level or a construction site limit.
+    pub fn place(&self, room: &Room) -> ConstructionReport {
+        let room_name = room.name();
+        let rcl = room.controller().map(|c| c.level()).unwrap_or(0);
+
+        let mut existing = HashSet::new();
+        let mut counts: HashMap<StructureType, u32> = HashMap::new();
+        for structure in room.find(find::STRUCTURES) {
+            let counted = match structure.as_owned() {
+                Some(owned) => owned.my(),
+                None => true,
+            };
+            if counted {
+                let ty = structure.structure_type();
+                existing.insert((structure.pos(), ty));
+                *counts.entry(ty).or_insert(0) += 1;
+            }
+        }
+        let sites = room.find(find::MY_CONSTRUCTION_SITES);
+        let room_sites = sites.len() as u32;
+        for site in sites {
+            let ty = site.structure_type();
+            existing.insert((site.pos(), ty));
+            *counts.entry(ty).or_insert(0) += 1;
+        }
+
+        let mut sites_left =
+            MAX_CONSTRUCTION_SITES.saturating_sub(game::construction_sites::keys().len() as u32);
+        if let Some(max) = self.max_sites_per_room {
+            sites_left = sites_left.min(max.saturating_sub(room_sites));
+        }
+
+        let mut room_state = RoomState {
+            rcl,
+            existing,
+            counts,
+            sites_left,
+        };
+        self.place_with(room_name, &mut room_state, |pos, ty| {
+            room.create_construction_site(&pos, ty)
+        })
+    }
+
+    /// Decides which queued structures in `room_name` to place, given what's
+    /// already there, calling `create` for each one which fits within the
+    /// limits.
+    fn place_with<F>(
+        &self,
+        room_name: RoomName,
+        room: &mut RoomState,
+        mut create: F,
+    ) -> ConstructionReport
+    where
+        F: FnMut(Position, StructureType) -> ReturnCode,
+    {
+        let mut tick_left = self.max_sites_per_tick.unwrap_or(u32::MAX);
+
+        let mut report = ConstructionReport::default();
+        for &(pos, ty) in &self.items {
+            if pos.room_name() != room_name || room.existing.contains(&(pos, ty)) {
+                continue;
+            }
+            let blocked = match room.limit(ty) {
+                Some(reason) => Some(reason),
+                None if tick_left == 0 => Some(Blocked::SiteLimit),
+                None => match create(pos, ty) {
+                    ReturnCode::Ok => None,
+                    code => Some(Blocked::Failed(code)),
+                },
+            };
+
+            match blocked {
+                Some(reason) => report.blocked.push((pos, ty, reason)),
+                None => {
+                    room.add_site(pos, ty);
+                    tick_left -= 1;
+                    report.placed.push((pos, ty));
+                }
+            }
+        }
+
+        report
+    }
+}
+
+/// What's already in a room, as far as the construction limits are
+/// concerned.
+struct RoomState {
+    rcl: u32,
+    /// Structures and construction sites, by position and type.
+    existing: HashSet<(Position, StructureType)>,
+    /// Number of structures and construction sites of each type.
+    counts: HashMap<StructureType, u32>,
+    /// Construction sites which can still be placed in the room.
+    sites_left: u32,
+}
+
+impl RoomState {
+    /// Why another structure of type `ty` can't be placed, if it can't.
+    fn limit(&self, ty: StructureType) -> Option<Blocked> {
+        let count = self.counts.get(&ty).copied().unwrap_or(0);
+        if count >= ty.controller_structures(self.rcl) {
+            Some(Blocked::ControllerLevel)
+        } else if self.sites_left == 0 {
+            Some(Blocked::SiteLimit)
+        } else {
+            None
+        }
+    }
+
+    fn add_site(&mut self, pos: Position, ty: StructureType) {
+        self.existing.insert((pos, ty));
+        *self.counts.entry(ty).or_insert(0) += 1;
+        self.sites_left -= 1;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::{HashMap, HashSet};
+
+    use super::{Blocked, ConstructionQueue, RoomState};
+    use crate::{
+        constants::{ReturnCode, StructureType},
+        local::{Position, RoomName},
+    };
+
+    fn room_name() -> RoomName {
+        RoomName::new("W1N1").unwrap()
+    }
+
+    fn pos(x: u32, y: u32) -> Position {
+        Position::new(x, y, room_name())
+    }
+
+    fn room_state(rcl: u32, sites_left: u32) -> RoomState {
+        RoomState {
+            rcl,
+            existing: HashSet::new(),
+            counts: HashMap::new(),
+            sites_left,
+        }
+    }
+
+    #[test]
+    fn limit_follows_controller_level_then_site_limit() {
+        let mut room = room_state(2, 10);
+        assert_eq!(room.limit(StructureType::Extension), None);
+        room.counts.insert(StructureType::Extension, 4);
+        assert_eq!(room.limit(StructureType::Extension), None);
+        room.counts.insert(StructureType::Extension, 5);
+        assert_eq!(
+            room.limit(StructureType::Extension),
+            Some(Blocked::ControllerLevel)
+        );
+        assert_eq!(
+            room.limit(StructureType::Tower),
+            Some(Blocked::ControllerLevel)
+        );
+
+        room.sites_left = 0;
+        assert_eq!(room.limit(StructureType::Road), Some(Blocked::SiteLimit));
+        assert_eq!(
+            room.limit(StructureType::Extension),
+            Some(Blocked::ControllerLevel)
+        );
+
+        let unowned = room_state(0, 10);
+        assert_eq!(
+            unowned.limit(StructureType::Spawn),
+            Some(Blocked::ControllerLevel)
+        );
+    }
+
+    #[test]
+    fn places_in_order_up_to_the_controller_limit() {
+        let other_room = Position::new(5, 5, RoomName::new("W2N1").unwrap());
+        let queue = ConstructionQueue::new()
+            .with(pos(10, 10), StructureType::Extension)
+            .with(other_room, StructureType::Extension)
+            .extend((11..17).map(|x| (pos(x, 10), StructureType::Extension)));
+
+        let mut room = room_state(2, 10);
+        room.existing
+            .insert((pos(10, 10), StructureType::Extension));
+        room.counts.insert(StructureType::Extension, 1);
+
+        let mut created = Vec::new();
+        let report = queue.place_with(room_name(), &mut room, |at, ty| {
+            created.push(at);
+            if at == pos(12, 10) {
+                ReturnCode::InvalidTarget
+            } else {
+                assert_eq!(ty, StructureType::Extension);
+                ReturnCode::Ok
+            }
+        });
+
+        let placed: Vec<_> = report.placed.iter().map(|&(pos, _)| pos.x()).collect();
+        assert_eq!(placed, [11, 13, 14, 15]);
+        assert_eq!(
+            report.blocked,
+            [
+                (
+                    pos(12, 10),
+                    StructureType::Extension,
+                    Blocked::Failed(ReturnCode::InvalidTarget)
+                ),
+                (
+                    pos(16, 10),
+                    StructureType::Extension,
+                    Blocked::ControllerLevel
+                ),
+            ]
+        );
+        assert_eq!(created.len(), 5);
+        assert_eq!(room.counts[&StructureType::Extension], 5);
+        assert_eq!(room.sites_left, 6);
+    }
+
+    #[test]
+    fn stops_at_site_limits() {
+        let queue =
+            ConstructionQueue::new().extend((0..4).map(|x| (pos(x, 20), StructureType::Road)));
+
+        let report = queue.place_with(room_name(), &mut room_state(1, 1), |_, _| ReturnCode::Ok);
+        assert_eq!(report.placed.len(), 1);
+        assert!(report
+            .blocked
+            .iter()
+            .all(|&(_, _, reason)| reason == Blocked::SiteLimit));
+
+        let mut calls = 0;
+        let report = queue.clone().max_sites_per_tick(2).place_with(
+            room_name(),
+            &mut room_state(1, 100),
+            |_, _| {
+                calls += 1;
+                ReturnCode::Ok
+            },
+        );
+        assert_eq!(calls, 2);
+        assert_eq!(report.placed.len(), 2);
+        assert_eq!(report.blocked.len(), 2);
+        assert!(report
+            .blocked
+            .iter()
+            .all(|&(_, _, reason)| reason == Blocked::SiteLimit));
+    }
+}
@@ -0,0 +1,79 @@
+//! Crate-wide error type.
+use std::{error, fmt};
+
+use crate::{
+    constants::ReturnCode,
+    local::{RawObjectIdParseError, ResolveError, RoomNameParseError},
+    ConversionError,
+};
+
+/// Any error which can be produced by this crate.
+///
+/// Every error type in the crate converts into `ScreepsError`, so functions
+/// mixing game calls and local parsing can use `?` on all of them. Use
+/// [`ReturnCode::as_result`] to turn a return code into something `?` can
+/// operate on.
+#[derive(Debug)]
+pub enum ScreepsError {
+    /// A game function returned a non-OK return code.
+    ReturnCode(ReturnCode),
+    /// A JavaScript value wasn't of the expected type.
+    Conversion(ConversionError),
+    RoomNameParse(RoomNameParseError),
+    RawObjectIdParse(RawObjectIdParseError),
+    Resolve(ResolveError),
+}
+
+impl fmt::Display for ScreepsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScreepsError::ReturnCode(e) => write!(f, "game returned error: {}", e),
+            ScreepsError::Conversion(e) => write!(f, "conversion error: {}", e),
+            ScreepsError::RoomNameParse(e) => e.fmt(f),
+            ScreepsError::RawObjectIdParse(e) => e.fmt(f),
+            ScreepsError::Resolve(e) => e.fmt(f),
+        }
+    }
+}
+
+impl error::Error for ScreepsError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            ScreepsError::ReturnCode(e) => Some(e),
+            ScreepsError::Conversion(e) => Some(e),
+            ScreepsError::RoomNameParse(e) => Some(e),
+            ScreepsError::RawObjectIdParse(e) => Some(e),
+            ScreepsError::Resolve(e) => Some(e),
+        }
+    }
+}
+
+impl From<ReturnCode> for ScreepsError {
+    fn from(e: ReturnCode) -> Self {
+        ScreepsError::ReturnCode(e)
+    }
+}
+
+impl From<ConversionError> for ScreepsError {
+    fn from(e: ConversionError) -> Self {
+        ScreepsError::Conversion(e)
+    }
+}
+
+impl From<RoomNameParseError> for ScreepsError {
+    fn from(e: RoomNameParseError) -> Self {
+        ScreepsError::RoomNameParse(e)
+    }
+}
+
+impl From<RawObjectIdParseError> for ScreepsError {
+    fn from(e: RawObjectIdParseError) -> Self {
+        ScreepsError::RawObjectIdParse(e)
+    }
+}
+
+impl From<ResolveError> for ScreepsError {
+    fn from(e: ResolveError) -> Self {
+        ScreepsError::Resolve(e)
+    }
+}
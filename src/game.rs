@@ -29,7 +29,20 @@ pub mod construction_sites {
 ///
 /// [http://docs.screeps.com/api/#Game.creeps]: http://docs.screeps.com/api/#Game.creeps
 pub mod creeps {
+    use crate::local::creep_name::CreepMeta;
+
     game_map_access!(objects::Creep, Game.creeps);
+
+    /// Your creeps whose names were written by [`CreepMeta::encode`], with
+    /// the metadata read from their names.
+    ///
+    /// [`CreepMeta::encode`]: crate::local::creep_name::CreepMeta::encode
+    pub fn with_meta() -> Vec<(CreepMeta, objects::Creep)> {
+        hashmap()
+            .into_iter()
+            .filter_map(|(name, creep)| CreepMeta::decode(&name).ok().map(|meta| (meta, creep)))
+            .collect()
+    }
 }
 
 /// See [http://docs.screeps.com/api/#Game.flags]
@@ -144,6 +157,21 @@ pub fn time() -> u32 {
     js_unwrap!(Game.time)
 }
 
+/// Counts of the intents issued so far this tick through this crate's action
+/// methods.
+///
+/// Each intent costs [`INTENT_CPU_COST`] when the tick ends, which isn't
+/// included in [`cpu::get_used`] until then. Intents issued directly from
+/// JavaScript aren't counted.
+///
+/// Requires the `track-intents` feature.
+///
+/// [`INTENT_CPU_COST`]: crate::intents::INTENT_CPU_COST
+#[cfg(feature = "track-intents")]
+pub fn intents() -> crate::intents::IntentSummary {
+    crate::intents::summary()
+}
+
 /// See [http://docs.screeps.com/api/#Game.getObjectById]
 ///
 /// This gets an object expecting a specific type and will return a
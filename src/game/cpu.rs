@@ -1,7 +1,7 @@
 //! See [http://docs.screeps.com/api/#Game.cpu]
 //!
 //! [http://docs.screeps.com/api/#Game.cpu]: http://docs.screeps.com/api/#Game.cpu
-use std::collections;
+use std::collections::{self, VecDeque};
 
 use serde::{Deserialize, Serialize};
 
@@ -10,7 +10,7 @@ use crate::{constants::ReturnCode, traits::TryInto};
 /// See [`v8_getheapstatistics`]
 ///
 /// [`v8_getheapstatistics`]: https://nodejs.org/dist/latest-v8.x/docs/api/v8.html#v8_v8_getheapstatistics
-#[derive(Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct HeapStatistics {
     pub total_heap_size: u32,
     pub total_heap_size_executable: u32,
@@ -76,17 +76,18 @@ pub fn unlocked_time() -> Option<u64> {
 ///
 /// [https://docs.screeps.com/api/#Game.cpu.getHeapStatistics]: https://docs.screeps.com/api/#Game.cpu.getHeapStatistics
 ///
-/// Returns object with all 0 values if heap statistics are not available.
-pub fn get_heap_statistics() -> HeapStatistics {
+/// Returns `None` if heap statistics are not available, such as on servers
+/// not running the isolated VM.
+pub fn get_heap_statistics() -> Option<HeapStatistics> {
     use stdweb::Value;
 
     let heap_stats: Value = js_unwrap!(Game.cpu.getHeapStatistics && Game.cpu.getHeapStatistics());
 
     match heap_stats {
-        Value::Null | Value::Undefined | Value::Bool(false) => HeapStatistics::default(),
-        other => other.try_into().expect(
+        Value::Null | Value::Undefined | Value::Bool(false) => None,
+        other => Some(other.try_into().expect(
             "expected Game.cpu.getHeapStatistics() to return an object with a known format",
-        ),
+        )),
     }
 }
 
@@ -135,3 +136,138 @@ pub fn generate_pixel() -> ReturnCode {
     // undefined on private servers, return OK in that case
     js_unwrap!(typeof(Game.cpu.generatePixel) == "function" && Game.cpu.generatePixel() || 0)
 }
+
+/// Starts a [`Budget`] for running work only when there's CPU to spare.
+///
+/// ```no_run
+/// use screeps::game::cpu;
+///
+/// # fn plan_bases() {}
+/// // only plan bases when the bucket is nearly full and this tick hasn't
+/// // used more than 80% of the CPU limit so far
+/// cpu::budget()
+///     .min_bucket(9000)
+///     .max_used_fraction(0.8)
+///     .run(plan_bases);
+/// ```
+pub fn budget() -> Budget {
+    Budget::default()
+}
+
+/// Thresholds on CPU use for running optional work, created by [`budget`].
+///
+/// With no thresholds set, work always runs.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Budget {
+    min_bucket: Option<u32>,
+    max_used: Option<f64>,
+    max_used_fraction: Option<f64>,
+}
+
+impl Budget {
+    /// Only runs work when the bucket holds at least `bucket` CPU.
+    pub fn min_bucket(mut self, bucket: u32) -> Self {
+        self.min_bucket = Some(bucket);
+        self
+    }
+
+    /// Only runs work while less than `used` CPU has been used this tick.
+    pub fn max_used(mut self, used: f64) -> Self {
+        self.max_used = Some(used);
+        self
+    }
+
+    /// Only runs work while less than `fraction` of [`limit`] has been used
+    /// this tick.
+    pub fn max_used_fraction(mut self, fraction: f64) -> Self {
+        self.max_used_fraction = Some(fraction);
+        self
+    }
+
+    /// Whether work can run right now.
+    ///
+    /// CPU used is checked each time this is called, so later calls in a tick
+    /// may return `false` after earlier ones returned `true`.
+    pub fn allows(&self) -> bool {
+        if let Some(min_bucket) = self.min_bucket {
+            if bucket() < min_bucket {
+                return false;
+            }
+        }
+        if self.max_used.is_none() && self.max_used_fraction.is_none() {
+            return true;
+        }
+        let used = get_used();
+        if let Some(max_used) = self.max_used {
+            if used >= max_used {
+                return false;
+            }
+        }
+        if let Some(fraction) = self.max_used_fraction {
+            if used >= f64::from(limit()) * fraction {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Runs `work` if the budget allows it, returning its result, or skips it
+    /// and returns `None`.
+    pub fn run<F, R>(&self, work: F) -> Option<R>
+    where
+        F: FnOnce() -> R,
+    {
+        if self.allows() {
+            Some(work())
+        } else {
+            None
+        }
+    }
+}
+
+/// A queue of work deferred until a [`Budget`] allows it to run.
+///
+/// The queue must be kept between ticks, for instance in a `thread_local!`,
+/// for deferred work to run on later ticks.
+#[derive(Default)]
+pub struct Deferred {
+    queue: VecDeque<Box<dyn FnOnce()>>,
+}
+
+impl Deferred {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds work to the end of the queue.
+    pub fn push<F>(&mut self, work: F)
+    where
+        F: FnOnce() + 'static,
+    {
+        self.queue.push_back(Box::new(work));
+    }
+
+    /// Runs queued work in order for as long as `budget` allows, leaving the
+    /// rest for a later call. Returns the number of items run.
+    pub fn run(&mut self, budget: &Budget) -> usize {
+        let mut ran = 0;
+        while !self.queue.is_empty() && budget.allows() {
+            let work = self
+                .queue
+                .pop_front()
+                .expect("expected queue to be non-empty");
+            work();
+            ran += 1;
+        }
+        ran
+    }
+
+    /// Number of items waiting to run.
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+}
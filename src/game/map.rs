@@ -1,7 +1,14 @@
 //! See [http://docs.screeps.com/api/#Game.map]
 //!
 //! [http://docs.screeps.com/api/#Game.map]: http://docs.screeps.com/api/#Game.map
-use std::{borrow::Cow, collections, mem, str::FromStr};
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    collections::{self, HashMap, HashSet},
+    mem,
+    str::FromStr,
+    sync::atomic::{AtomicU32, Ordering},
+};
 
 use num_traits::FromPrimitive;
 use parse_display::FromStr;
@@ -13,11 +20,14 @@ use stdweb::Value;
 
 use crate::{
     constants::{Direction, ExitDirection, ReturnCode},
-    local::RoomName,
+    game,
+    local::{RoomName, WorldBounds},
     objects::RoomTerrain,
-    traits::{TryFrom, TryInto},
+    traits::{IntoExpectedType, TryFrom, TryInto},
 };
 
+pub mod visual;
+
 /// See [http://docs.screeps.com/api/#Game.map.describeExits]
 ///
 /// [http://docs.screeps.com/api/#Game.map.describeExits]: http://docs.screeps.com/api/#Game.map.describeExits
@@ -47,8 +57,29 @@ pub fn get_room_linear_distance(room1: RoomName, room2: RoomName, continuous: bo
     js_unwrap!(Game.map.getRoomLinearDistance(@{room1}, @{room2}, @{continuous}))
 }
 
-pub fn get_room_terrain(room_name: RoomName) -> RoomTerrain {
-    js_unwrap!(Game.map.getRoomTerrain(@{room_name}))
+/// See [http://docs.screeps.com/api/#Game.map.getRoomTerrain]
+///
+/// Returns `None` if the room doesn't exist in this world.
+///
+/// Reading tiles from the returned [`RoomTerrain`] calls into JavaScript for
+/// every tile. To read more than a few, convert it into a
+/// [`LocalRoomTerrain`], which copies the whole room at once, or keep rooms
+/// in a [`TerrainCache`].
+///
+/// [http://docs.screeps.com/api/#Game.map.getRoomTerrain]: http://docs.screeps.com/api/#Game.map.getRoomTerrain
+/// [`LocalRoomTerrain`]: crate::local::LocalRoomTerrain
+/// [`TerrainCache`]: crate::local::TerrainCache
+pub fn get_room_terrain(room_name: RoomName) -> Option<RoomTerrain> {
+    let terrain = js! {
+        try {
+            return Game.map.getRoomTerrain(@{room_name});
+        } catch (e) {
+            return null;
+        }
+    };
+    terrain
+        .into_expected_type()
+        .expect("expected Game.map.getRoomTerrain to return a Room.Terrain")
 }
 
 /// See [http://docs.screeps.com/api/#Game.map.getWorldSize]
@@ -58,6 +89,22 @@ pub fn get_world_size() -> u32 {
     js_unwrap!(Game.map.getWorldSize())
 }
 
+/// The cached world size, or 0 if it hasn't been fetched yet.
+static WORLD_SIZE: AtomicU32 = AtomicU32::new(0);
+
+/// The bounds of the current world, from [`get_world_size`].
+///
+/// The world's size can't change while the code is running, so it's only
+/// fetched from JavaScript once per global.
+pub fn world_bounds() -> WorldBounds {
+    let mut size = WORLD_SIZE.load(Ordering::Relaxed);
+    if size == 0 {
+        size = get_world_size();
+        WORLD_SIZE.store(size, Ordering::Relaxed);
+    }
+    WorldBounds::new(size)
+}
+
 /// See [http://docs.screeps.com/api/#Game.map.getRoomStatus]
 ///
 /// [http://docs.screeps.com/api/#Game.map.getRoomStatus]: http://docs.screeps.com/api/#Game.map.getRoomStatus
@@ -65,15 +112,80 @@ pub fn get_room_status(room_name: RoomName) -> MapRoomStatus {
     js_unwrap!(Game.map.getRoomStatus(@{room_name}))
 }
 
+#[derive(Default)]
+struct StatusCache {
+    tick: u32,
+    statuses: HashMap<RoomName, MapRoomStatus>,
+}
+
+thread_local! {
+    static STATUS_CACHE: RefCell<StatusCache> = RefCell::new(StatusCache::default());
+}
+
+/// Like [`get_room_status`], but only calls into JavaScript the first time
+/// each room is looked up in a tick.
+pub fn get_room_status_cached(room_name: RoomName) -> MapRoomStatus {
+    let time = game::time();
+    STATUS_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if cache.tick != time {
+            cache.tick = time;
+            cache.statuses.clear();
+        }
+        cache
+            .statuses
+            .entry(room_name)
+            .or_insert_with(|| get_room_status(room_name))
+            .clone()
+    })
+}
+
+/// The current time in milliseconds since the Unix epoch, in the same format
+/// as [`MapRoomStatus::timestamp`].
+pub fn current_timestamp() -> u64 {
+    let now: f64 = js_unwrap!(Date.now());
+    now as u64
+}
+
 /// Represents the availability and respawn/novice state of a room on the map
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MapRoomStatus {
     pub status: RoomStatus,
+    /// When the status expires, in milliseconds since the Unix epoch, or
+    /// `None` if it's permanent.
     pub timestamp: Option<u64>,
 }
 js_deserializable!(MapRoomStatus);
 
+impl MapRoomStatus {
+    /// Milliseconds from `now` until this status expires, or `None` if it's
+    /// permanent. Returns `Some(0)` if it has already expired.
+    ///
+    /// `now` is in milliseconds since the Unix epoch, like
+    /// [`current_timestamp`].
+    pub fn time_until_expiry(&self, now: u64) -> Option<u64> {
+        self.timestamp.map(|ts| ts.saturating_sub(now))
+    }
+
+    /// Whether this status was temporary and has expired as of `now`.
+    pub fn has_expired(&self, now: u64) -> bool {
+        self.time_until_expiry(now) == Some(0)
+    }
+
+    /// The status this room will have at `now`, assuming novice and respawn
+    /// areas turn into normal rooms when they expire.
+    ///
+    /// Closed rooms stay closed, since when they open they could become any
+    /// kind of area.
+    pub fn status_at(&self, now: u64) -> RoomStatus {
+        match self.status {
+            RoomStatus::Novice | RoomStatus::Respawn if self.has_expired(now) => RoomStatus::Normal,
+            status => status,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, FromStr)]
 #[display(style = "camelCase")]
 pub enum RoomStatus {
@@ -83,6 +195,17 @@ pub enum RoomStatus {
     Respawn,
 }
 
+impl RoomStatus {
+    /// Whether creeps can move from a room with this status into a room with
+    /// `other` status.
+    ///
+    /// Novice and respawn areas are separated from normal rooms by walls
+    /// until they expire, and closed rooms can't be entered at all.
+    pub fn can_travel_to(self, other: RoomStatus) -> bool {
+        self == other && self != RoomStatus::Closed
+    }
+}
+
 impl<'de> Deserialize<'de> for RoomStatus {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -145,11 +268,27 @@ pub fn find_exit_with_callback(
         })
 }
 
-pub fn find_route(from_room: &str, to_room: &str) -> Result<Vec<RoomRouteStep>, ReturnCode> {
+/// See [http://docs.screeps.com/api/#Game.map.findRoute]
+///
+/// Returns each room entered on the way to `to_room` and the exit used to
+/// enter it, or `Err(ReturnCode::NoPath)` if there's no route. Use
+/// [`find_route_with_callback`] or [`find_route_with_options`] to change the
+/// cost of rooms.
+///
+/// [http://docs.screeps.com/api/#Game.map.findRoute]: http://docs.screeps.com/api/#Game.map.findRoute
+pub fn find_route(
+    from_room: RoomName,
+    to_room: RoomName,
+) -> Result<Vec<RoomRouteStep>, ReturnCode> {
     let v = js!(return Game.map.findRoute(@{from_room}, @{to_room}););
     parse_find_route_returned_value(v)
 }
 
+/// Implements `Game.map.findRoute` with a `routeCallback`.
+///
+/// The callback is called with the room being entered and the room it's
+/// entered from, and returns the cost of entering it. Return `f64::INFINITY`
+/// to block a room.
 pub fn find_route_with_callback(
     from_room: RoomName,
     to_room: RoomName,
@@ -183,6 +322,179 @@ pub fn find_route_with_callback(
     parse_find_route_returned_value(v)
 }
 
+/// Options for [`find_route_with_options`], combining a cost callback with
+/// rules for rooms to avoid or prefer.
+///
+/// Rooms blocked by any rule are never routed through. Otherwise preferred
+/// rooms cost [`RouteOptions::prefer_cost`], and all other rooms cost whatever
+/// the route callback returns, `1.0` by default.
+///
+/// # Example
+///
+/// ```no_run
+/// use screeps::{game::map::{self, RouteOptions, RoomStatus}, RoomName};
+///
+/// let from: RoomName = "W1N1".parse().unwrap();
+/// let to: RoomName = "W5N3".parse().unwrap();
+/// let hostile: RoomName = "W3N2".parse().unwrap();
+///
+/// let options = RouteOptions::new()
+///     .avoid_rooms(vec![hostile])
+///     .avoid_source_keepers(true)
+///     .reachable_from(RoomStatus::Normal)
+///     // prefer highways
+///     .route_callback(|room, _from| if room.is_highway() { 1.0 } else { 2.5 });
+///
+/// let route = map::find_route_with_options(from, to, options);
+/// # let _ = route;
+/// ```
+pub struct RouteOptions<F>
+where
+    F: FnMut(RoomName, RoomName) -> f64,
+{
+    route_callback: F,
+    avoid: HashSet<RoomName>,
+    prefer: HashSet<RoomName>,
+    prefer_cost: f64,
+    avoid_source_keepers: bool,
+    reachable_from: Option<RoomStatus>,
+}
+
+impl Default for RouteOptions<fn(RoomName, RoomName) -> f64> {
+    fn default() -> Self {
+        fn route_callback(_: RoomName, _: RoomName) -> f64 {
+            1.0
+        }
+
+        RouteOptions {
+            route_callback,
+            avoid: HashSet::new(),
+            prefer: HashSet::new(),
+            prefer_cost: 0.5,
+            avoid_source_keepers: false,
+            reachable_from: None,
+        }
+    }
+}
+
+impl RouteOptions<fn(RoomName, RoomName) -> f64> {
+    /// Creates default RouteOptions
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<F> RouteOptions<F>
+where
+    F: FnMut(RoomName, RoomName) -> f64,
+{
+    /// Sets the cost of rooms which aren't blocked or preferred - default
+    /// `|_, _| 1.0`.
+    ///
+    /// The callback is called with the room being entered and the room it's
+    /// entered from, like `routeCallback`. Return `f64::INFINITY` to block a
+    /// room.
+    pub fn route_callback<F2>(self, route_callback: F2) -> RouteOptions<F2>
+    where
+        F2: FnMut(RoomName, RoomName) -> f64,
+    {
+        let RouteOptions {
+            route_callback: _,
+            avoid,
+            prefer,
+            prefer_cost,
+            avoid_source_keepers,
+            reachable_from,
+        } = self;
+        RouteOptions {
+            route_callback,
+            avoid,
+            prefer,
+            prefer_cost,
+            avoid_source_keepers,
+            reachable_from,
+        }
+    }
+
+    /// Adds rooms which routes must not pass through.
+    pub fn avoid_rooms<I>(mut self, rooms: I) -> Self
+    where
+        I: IntoIterator<Item = RoomName>,
+    {
+        self.avoid.extend(rooms);
+        self
+    }
+
+    /// Adds rooms which routes should pass through if possible.
+    pub fn prefer_rooms<I>(mut self, rooms: I) -> Self
+    where
+        I: IntoIterator<Item = RoomName>,
+    {
+        self.prefer.extend(rooms);
+        self
+    }
+
+    /// Sets the cost of preferred rooms - default `0.5`.
+    #[inline]
+    pub fn prefer_cost(mut self, cost: f64) -> Self {
+        self.prefer_cost = cost;
+        self
+    }
+
+    /// Sets whether to avoid source keeper rooms - default `false`.
+    ///
+    /// The destination room is still allowed when it's a source keeper room.
+    #[inline]
+    pub fn avoid_source_keepers(mut self, avoid: bool) -> Self {
+        self.avoid_source_keepers = avoid;
+        self
+    }
+
+    /// Only allows rooms creeps can travel to from rooms with the given status,
+    /// using [`RoomStatus::can_travel_to`] - default: no restriction.
+    ///
+    /// Statuses are looked up with [`get_room_status_cached`].
+    #[inline]
+    pub fn reachable_from(mut self, status: RoomStatus) -> Self {
+        self.reachable_from = Some(status);
+        self
+    }
+
+    /// Finds the cost of entering `room_name` from `from_room_name`.
+    fn cost(&mut self, room_name: RoomName, from_room_name: RoomName, goal: RoomName) -> f64 {
+        if self.avoid.contains(&room_name) {
+            return f64::INFINITY;
+        }
+        if self.avoid_source_keepers && room_name != goal && room_name.is_source_keeper() {
+            return f64::INFINITY;
+        }
+        if let Some(status) = self.reachable_from {
+            if !status.can_travel_to(get_room_status_cached(room_name).status) {
+                return f64::INFINITY;
+            }
+        }
+        if self.prefer.contains(&room_name) {
+            return self.prefer_cost;
+        }
+        (self.route_callback)(room_name, from_room_name)
+    }
+}
+
+/// Implements `Game.map.findRoute` with the rules and callback from `options`.
+pub fn find_route_with_options<F>(
+    from_room: RoomName,
+    to_room: RoomName,
+    mut options: RouteOptions<F>,
+) -> Result<Vec<RoomRouteStep>, ReturnCode>
+where
+    F: FnMut(RoomName, RoomName) -> f64,
+{
+    find_route_with_callback(from_room, to_room, |room_name, from_room_name| {
+        options.cost(room_name, from_room_name, to_room)
+    })
+}
+
 fn parse_find_route_returned_value(v: Value) -> Result<Vec<RoomRouteStep>, ReturnCode> {
     match v {
         Value::Number(x) => Err(ReturnCode::try_from(Value::Number(x)).unwrap_or_else(|e| {
@@ -199,10 +511,107 @@ fn parse_find_route_returned_value(v: Value) -> Result<Vec<RoomRouteStep>, Retur
     }
 }
 
-#[derive(Clone, Debug, Deserialize)]
+/// One room on a route from [`find_route`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RoomRouteStep {
+    /// The exit leading into `room` from the previous room.
     pub exit: ExitDirection,
     pub room: RoomName,
 }
 js_deserializable!(RoomRouteStep);
+
+#[cfg(test)]
+mod test {
+    use super::{MapRoomStatus, RoomStatus};
+
+    fn status(status: RoomStatus, timestamp: Option<u64>) -> MapRoomStatus {
+        MapRoomStatus { status, timestamp }
+    }
+
+    #[test]
+    fn temporary_status_expires_at_its_timestamp() {
+        let novice = status(RoomStatus::Novice, Some(1000));
+
+        assert_eq!(novice.time_until_expiry(0), Some(1000));
+        assert_eq!(novice.time_until_expiry(999), Some(1));
+        assert!(!novice.has_expired(999));
+        assert_eq!(novice.status_at(999), RoomStatus::Novice);
+
+        assert_eq!(novice.time_until_expiry(1000), Some(0));
+        assert!(novice.has_expired(1000));
+        assert_eq!(novice.status_at(1000), RoomStatus::Normal);
+
+        assert_eq!(novice.time_until_expiry(5000), Some(0));
+        assert!(novice.has_expired(5000));
+        assert_eq!(novice.status_at(5000), RoomStatus::Normal);
+    }
+
+    #[test]
+    fn respawn_areas_become_normal_rooms() {
+        let respawn = status(RoomStatus::Respawn, Some(1000));
+        assert_eq!(respawn.status_at(999), RoomStatus::Respawn);
+        assert_eq!(respawn.status_at(1000), RoomStatus::Normal);
+    }
+
+    #[test]
+    fn closed_rooms_stay_closed() {
+        let closed = status(RoomStatus::Closed, Some(1000));
+        assert!(closed.has_expired(1000));
+        assert_eq!(closed.status_at(1000), RoomStatus::Closed);
+    }
+
+    #[test]
+    fn permanent_status_never_expires() {
+        for &room_status in &[
+            RoomStatus::Normal,
+            RoomStatus::Closed,
+            RoomStatus::Novice,
+            RoomStatus::Respawn,
+        ] {
+            let permanent = status(room_status, None);
+            assert_eq!(permanent.time_until_expiry(u64::MAX), None);
+            assert!(!permanent.has_expired(u64::MAX));
+            assert_eq!(permanent.status_at(u64::MAX), room_status);
+        }
+    }
+
+    #[test]
+    fn can_travel_to_only_rooms_with_the_same_open_status() {
+        use RoomStatus::*;
+        let expected = [
+            (Normal, Normal, true),
+            (Normal, Closed, false),
+            (Normal, Novice, false),
+            (Normal, Respawn, false),
+            (Closed, Normal, false),
+            (Closed, Closed, false),
+            (Closed, Novice, false),
+            (Closed, Respawn, false),
+            (Novice, Normal, false),
+            (Novice, Closed, false),
+            (Novice, Novice, true),
+            (Novice, Respawn, false),
+            (Respawn, Normal, false),
+            (Respawn, Closed, false),
+            (Respawn, Novice, false),
+            (Respawn, Respawn, true),
+        ];
+        for &(from, to, allowed) in &expected {
+            assert_eq!(from.can_travel_to(to), allowed, "{:?} -> {:?}", from, to);
+        }
+    }
+
+    #[test]
+    fn deserializes_get_room_status_result() {
+        let novice: MapRoomStatus =
+            serde_json::from_str(r#"{"status":"novice","timestamp":1600000000000}"#).unwrap();
+        assert_eq!(novice.status, RoomStatus::Novice);
+        assert_eq!(novice.timestamp, Some(1_600_000_000_000));
+
+        let normal: MapRoomStatus =
+            serde_json::from_str(r#"{"status":"normal","timestamp":null}"#).unwrap();
+        assert_eq!(normal.status, RoomStatus::Normal);
+        assert_eq!(normal.timestamp, None);
+    }
+}
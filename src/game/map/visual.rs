@@ -0,0 +1,108 @@
+//! See [http://docs.screeps.com/api/#Game.map-visual]
+//!
+//! Map visuals are drawn over the world map, and like room visuals are cleared
+//! every tick. [`save_to_segment`] and [`load_from_segment`] can be used to
+//! keep an expensive overlay around without regenerating it each tick.
+//!
+//! [http://docs.screeps.com/api/#Game.map-visual]: http://docs.screeps.com/api/#Game.map-visual
+use crate::{
+    local::Position,
+    objects::{CircleStyle, LineStyle, PolyStyle, RectStyle},
+    raw_memory,
+};
+
+/// Maximum total size of map visuals drawn in one tick, in bytes.
+pub const MAP_VISUALS_SIZE_LIMIT: u32 = 1000 * 1024;
+
+pub fn line(from: Position, to: Position, style: Option<LineStyle>) {
+    js! { @(no_return)
+        Game.map.visual.line(
+            pos_from_packed(@{from.packed_repr()}),
+            pos_from_packed(@{to.packed_repr()}),
+            @{style} || undefined
+        );
+    }
+}
+
+pub fn circle(pos: Position, style: Option<CircleStyle>) {
+    js! { @(no_return)
+        Game.map.visual.circle(pos_from_packed(@{pos.packed_repr()}), @{style} || undefined);
+    }
+}
+
+/// Draws a rectangle with its top-left corner at `top_left`. `width` and
+/// `height` are in room tiles.
+pub fn rect(top_left: Position, width: f32, height: f32, style: Option<RectStyle>) {
+    js! { @(no_return)
+        Game.map.visual.rect(
+            pos_from_packed(@{top_left.packed_repr()}),
+            @{width},
+            @{height},
+            @{style} || undefined
+        );
+    }
+}
+
+pub fn poly(points: &[Position], style: Option<PolyStyle>) {
+    let packed: Vec<i32> = points.iter().map(|p| p.packed_repr()).collect();
+    js! { @(no_return)
+        Game.map.visual.poly(@{packed}.map(pos_from_packed), @{style} || undefined);
+    }
+}
+
+/// Removes all map visuals drawn so far this tick.
+pub fn clear() {
+    js! { @(no_return)
+        Game.map.visual.clear();
+    }
+}
+
+/// Size of the map visuals drawn so far this tick, in bytes.
+pub fn get_size() -> u32 {
+    js_unwrap!(Game.map.visual.getSize())
+}
+
+/// Serializes the map visuals drawn so far this tick.
+pub fn export() -> String {
+    js_unwrap!(Game.map.visual.export())
+}
+
+/// Adds visuals previously serialized with [`export`].
+pub fn import(data: &str) {
+    js! { @(no_return)
+        Game.map.visual.import(@{data});
+    }
+}
+
+/// Stores the map visuals drawn so far this tick in memory segment `id`.
+///
+/// The segment must be active. Returns `false` without modifying the segment
+/// if the exported visuals don't fit in a single segment.
+pub fn save_to_segment(id: u32) -> bool {
+    if get_size() as usize > raw_memory::MAX_SEGMENT_SIZE {
+        return false;
+    }
+    let data = export();
+    if data.len() > raw_memory::MAX_SEGMENT_SIZE {
+        return false;
+    }
+    raw_memory::set_segment(id, &data);
+    true
+}
+
+/// Re-draws map visuals stored with [`save_to_segment`].
+///
+/// Returns `false` if segment `id` isn't active, is empty, or if importing it
+/// would exceed [`MAP_VISUALS_SIZE_LIMIT`] together with the visuals already
+/// drawn this tick.
+pub fn load_from_segment(id: u32) -> bool {
+    let data = match raw_memory::get_segment(id) {
+        Some(data) if !data.is_empty() => data,
+        _ => return false,
+    };
+    if get_size() as usize + data.len() > MAP_VISUALS_SIZE_LIMIT as usize {
+        return false;
+    }
+    import(&data);
+    true
+}
@@ -1,17 +1,26 @@
 //! See [https://docs.screeps.com/api/#Game-market]
 //!
 //! [https://docs.screeps.com/api/#Game-market]: https://docs.screeps.com/api/#Game-market
-use std::{borrow::Cow, collections::HashMap, str::FromStr};
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    collections::HashMap,
+    fmt,
+    ops::{Add, Mul, Sub},
+    rc::Rc,
+    str::FromStr,
+};
 
 use parse_display::FromStr;
 use serde::{
     de::{Deserializer, Error as _, Unexpected},
-    Deserialize,
+    Deserialize, Serialize, Serializer,
 };
 use serde_repr::{Deserialize_repr, Serialize_repr};
 
 use crate::{
     constants::{MarketResourceType, ResourceType, ReturnCode},
+    game,
     local::RoomName,
     traits::TryInto,
 };
@@ -48,6 +57,94 @@ impl OrderType {
     }
 }
 
+/// An amount of credits, stored as an integer number of thousandths of a
+/// credit.
+///
+/// The game keeps credits with three decimal places, but JavaScript hands them
+/// to us as floating point numbers. Storing them as integers keeps prices
+/// exact, so they can be compared with `==` and summed without drifting.
+///
+/// Converting from `f64` rounds to the nearest thousandth, and `Display`
+/// always shows three decimal places.
+///
+/// ```
+/// use screeps::game::market::Credits;
+///
+/// let price = Credits::from_f64(0.1) + Credits::from_f64(0.2);
+/// assert_eq!(price, Credits::from_f64(0.3));
+/// assert_eq!(price.millicredits(), 300);
+/// assert_eq!((price * 1000).to_string(), "300.000");
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Credits(i64);
+
+impl Credits {
+    pub const ZERO: Credits = Credits(0);
+
+    pub const fn from_millicredits(millicredits: i64) -> Credits {
+        Credits(millicredits)
+    }
+
+    /// Converts a floating point number of credits, rounding to the nearest
+    /// thousandth.
+    pub fn from_f64(credits: f64) -> Credits {
+        Credits((credits * 1000.0).round() as i64)
+    }
+
+    pub const fn millicredits(self) -> i64 {
+        self.0
+    }
+
+    pub fn as_f64(self) -> f64 {
+        self.0 as f64 / 1000.0
+    }
+}
+
+impl fmt::Display for Credits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let abs = self.0.unsigned_abs();
+        write!(f, "{}{}.{:03}", sign, abs / 1000, abs % 1000)
+    }
+}
+
+impl Add for Credits {
+    type Output = Credits;
+
+    fn add(self, other: Credits) -> Credits {
+        Credits(self.0 + other.0)
+    }
+}
+
+impl Sub for Credits {
+    type Output = Credits;
+
+    fn sub(self, other: Credits) -> Credits {
+        Credits(self.0 - other.0)
+    }
+}
+
+/// Total price of an amount of resources, at a price per unit.
+impl Mul<u32> for Credits {
+    type Output = Credits;
+
+    fn mul(self, amount: u32) -> Credits {
+        Credits(self.0 * i64::from(amount))
+    }
+}
+
+impl<'de> Deserialize<'de> for Credits {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        f64::deserialize(d).map(Credits::from_f64)
+    }
+}
+
+impl Serialize for Credits {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        self.as_f64().serialize(s)
+    }
+}
+
 // impl OrderType {
 //     fn as_string(&self) -> String {
 //         match self {
@@ -68,7 +165,7 @@ pub struct TransactionOrder {
     pub id: String,
     #[serde(rename = "type", deserialize_with = "OrderType::deserialize_from_str")]
     pub order_type: OrderType,
-    pub price: f64,
+    pub price: Credits,
 }
 js_deserializable!(TransactionOrder);
 
@@ -99,7 +196,7 @@ pub struct Transaction {
 }
 js_deserializable!(Transaction);
 
-#[derive(Deserialize, Debug)]
+#[derive(Clone, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Order {
     pub id: String,
@@ -115,7 +212,7 @@ pub struct Order {
     pub room_name: Option<RoomName>,
     pub amount: u32,
     pub remaining_amount: u32,
-    pub price: f64,
+    pub price: Credits,
 }
 js_deserializable!(Order);
 
@@ -137,7 +234,7 @@ pub struct MyOrder {
     pub amount: u32,
     pub remaining_amount: u32,
     pub total_amount: u32,
-    pub price: f64,
+    pub price: Credits,
 }
 js_deserializable!(MyOrder);
 
@@ -157,8 +254,8 @@ pub struct OrderHistoryRecord {
 }
 js_deserializable!(OrderHistoryRecord);
 
-pub fn credits() -> f64 {
-    js_unwrap!(Game.market.credits)
+pub fn credits() -> Credits {
+    Credits::from_f64(js_unwrap!(Game.market.credits))
 }
 
 pub fn incoming_transactions() -> Vec<Transaction> {
@@ -182,14 +279,14 @@ pub fn cancel_order(order_id: &str) -> ReturnCode {
     js_unwrap!(Game.market.cancelOrder(@{order_id}))
 }
 
-pub fn change_order_price(order_id: &str, new_price: f64) -> ReturnCode {
-    js_unwrap!(Game.market.changeOrderPrice(@{order_id}, @{new_price}))
+pub fn change_order_price(order_id: &str, new_price: Credits) -> ReturnCode {
+    js_unwrap!(Game.market.changeOrderPrice(@{order_id}, @{new_price.as_f64()}))
 }
 
 pub fn create_order(
     order_type: OrderType,
     resource_type: MarketResourceType,
-    price: f64,
+    price: Credits,
     total_amount: u32,
     room: Option<RoomName>,
 ) -> ReturnCode {
@@ -203,7 +300,7 @@ pub fn create_order(
                 Game.market.createOrder({
                     type: __order_type_num_to_str(@{order_type as u32}),
                     resourceType: __resource_type_num_to_str(@{resource_num}),
-                    price: @{price},
+                    price: @{price.as_f64()},
                     totalAmount: @{total_amount},
                     roomName: @{room_name.to_string()}
                 })
@@ -214,7 +311,7 @@ pub fn create_order(
                 Game.market.createOrder({
                     type: __order_type_num_to_str(@{order_type as u32}),
                     resourceType: __resource_type_num_to_str(@{resource_num}),
-                    price: @{price},
+                    price: @{price.as_f64()},
                     totalAmount: @{total_amount}
                 })
             }
@@ -261,6 +358,52 @@ pub fn get_all_orders(resource: Option<MarketResourceType>) -> Vec<Order> {
     }
 }
 
+#[derive(Default)]
+struct OrderCache {
+    tick: u32,
+    orders: HashMap<Option<MarketResourceType>, Rc<Vec<Order>>>,
+}
+
+thread_local! {
+    static ORDER_CACHE: RefCell<OrderCache> = RefCell::new(OrderCache::default());
+}
+
+/// Like [`get_all_orders`], but only calls into JavaScript the first time
+/// each resource's orders are requested in a tick, sharing the result with
+/// every later call that tick.
+///
+/// If all orders have already been fetched this tick, orders for a single
+/// resource are picked out of them rather than fetched again.
+///
+/// The cached orders aren't updated by [`deal`] or other market actions made
+/// during the tick, so their `remaining_amount`s may be out of date by the
+/// time they're used.
+pub fn get_all_orders_cached(resource: Option<MarketResourceType>) -> Rc<Vec<Order>> {
+    let time = game::time();
+    ORDER_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if cache.tick != time {
+            cache.tick = time;
+            cache.orders.clear();
+        }
+        if let Some(orders) = cache.orders.get(&resource) {
+            return orders.clone();
+        }
+
+        let orders = match (resource, cache.orders.get(&None)) {
+            (Some(resource_type), Some(all)) => all
+                .iter()
+                .filter(|order| order.resource_type == resource_type)
+                .cloned()
+                .collect(),
+            _ => get_all_orders(resource),
+        };
+        let orders = Rc::new(orders);
+        cache.orders.insert(resource, orders.clone());
+        orders
+    })
+}
+
 /// Provides historical information on the price of each resource over the last
 /// 14 days
 ///
@@ -0,0 +1,212 @@
+//! A compact record of what a room looked like when last seen.
+//!
+//! [`RoomIntel::scan`] summarizes a visible room into a [`RoomIntel`], which
+//! scouting code can store, for instance in a memory segment keyed by room
+//! name, and consult once the room is out of sight.
+//!
+//! ```no_run
+//! use std::collections::BTreeMap;
+//!
+//! use screeps::{game, intel::RoomIntel, raw_memory, RoomName};
+//!
+//! let mut known: BTreeMap<RoomName, RoomIntel> = raw_memory::get_segment(40)
+//!     .and_then(|data| serde_json::from_str(&data).ok())
+//!     .unwrap_or_default();
+//!
+//! for room in game::rooms::values() {
+//!     known.insert(room.name(), RoomIntel::scan(&room));
+//! }
+//!
+//! raw_memory::set_segment(40, &serde_json::to_string(&known).unwrap());
+//! ```
+//!
+//! Field names are shortened when serialized to keep stored intel small.
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::{find, Part, ResourceType, StructureType},
+    game,
+    local::{Position, RoomName},
+    objects::{HasPosition, OwnedStructureProperties, Room, StructureProperties},
+};
+
+/// Hostile creeps in a room and the active parts they can fight with.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThreatSummary {
+    #[serde(rename = "c")]
+    pub hostile_creeps: u32,
+    #[serde(rename = "a")]
+    pub attack_parts: u32,
+    #[serde(rename = "r")]
+    pub ranged_attack_parts: u32,
+    #[serde(rename = "h")]
+    pub heal_parts: u32,
+}
+
+impl ThreatSummary {
+    /// Whether any hostile creep could attack or heal.
+    pub fn is_dangerous(&self) -> bool {
+        self.attack_parts + self.ranged_attack_parts + self.heal_parts > 0
+    }
+}
+
+/// What a room looked like when it was last scanned.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoomIntel {
+    #[serde(rename = "r")]
+    pub room_name: RoomName,
+    /// Game tick the room was scanned on.
+    #[serde(rename = "t")]
+    pub last_seen: u32,
+    /// Username of the controller's owner, if it's owned.
+    #[serde(rename = "o", default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    /// Controller level, or `0` for rooms without an owned controller.
+    #[serde(rename = "l", default)]
+    pub level: u32,
+    #[serde(rename = "tw", default)]
+    pub towers: u32,
+    #[serde(rename = "sp", default)]
+    pub spawns: u32,
+    /// `(x, y)` of each source in the room.
+    #[serde(rename = "s", default)]
+    pub sources: Vec<(u32, u32)>,
+    #[serde(rename = "m", default, skip_serializing_if = "Option::is_none")]
+    pub mineral: Option<ResourceType>,
+    #[serde(rename = "h", default)]
+    pub threat: ThreatSummary,
+}
+
+impl RoomIntel {
+    /// Summarizes a visible room as of the current tick.
+    pub fn scan(room: &Room) -> RoomIntel {
+        let controller = room.controller();
+        let owner = controller.as_ref().and_then(|c| c.owner_name());
+        let level = match owner {
+            Some(_) => controller.map(|c| c.level()).unwrap_or(0),
+            None => 0,
+        };
+
+        let mut towers = 0;
+        let mut spawns = 0;
+        for structure in room.find(find::STRUCTURES) {
+            match structure.structure_type() {
+                StructureType::Tower => towers += 1,
+                StructureType::Spawn => spawns += 1,
+                _ => (),
+            }
+        }
+
+        let sources = room
+            .find(find::SOURCES)
+            .iter()
+            .map(|source| {
+                let pos = source.pos();
+                (pos.x(), pos.y())
+            })
+            .collect();
+        let mineral = room
+            .find(find::MINERALS)
+            .first()
+            .map(|mineral| mineral.mineral_type());
+
+        let mut threat = ThreatSummary::default();
+        for creep in room.find(find::HOSTILE_CREEPS) {
+            threat.hostile_creeps += 1;
+            threat.attack_parts += creep.get_active_bodyparts(Part::Attack);
+            threat.ranged_attack_parts += creep.get_active_bodyparts(Part::RangedAttack);
+            threat.heal_parts += creep.get_active_bodyparts(Part::Heal);
+        }
+
+        RoomIntel {
+            room_name: room.name(),
+            last_seen: game::time(),
+            owner,
+            level,
+            towers,
+            spawns,
+            sources,
+            mineral,
+            threat,
+        }
+    }
+
+    /// Positions of the room's sources.
+    pub fn source_positions(&self) -> impl Iterator<Item = Position> + '_ {
+        self.sources
+            .iter()
+            .map(move |&(x, y)| Position::new(x, y, self.room_name))
+    }
+
+    /// Ticks since the room was scanned.
+    pub fn age(&self) -> u32 {
+        game::time().saturating_sub(self.last_seen)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{RoomIntel, ThreatSummary};
+    use crate::{constants::ResourceType, local::RoomName};
+
+    #[test]
+    fn reads_intel_without_defaulted_fields() {
+        let intel: RoomIntel = serde_json::from_str(r#"{"r":"W1N1","t":100}"#).unwrap();
+        assert_eq!(
+            intel,
+            RoomIntel {
+                room_name: RoomName::new("W1N1").unwrap(),
+                last_seen: 100,
+                owner: None,
+                level: 0,
+                towers: 0,
+                spawns: 0,
+                sources: Vec::new(),
+                mineral: None,
+                threat: ThreatSummary::default(),
+            }
+        );
+
+        let intel: RoomIntel =
+            serde_json::from_str(r#"{"r":"E3S5","t":7,"o":"Alice","s":[[10,20],[33,4]]}"#).unwrap();
+        assert_eq!(intel.owner.as_deref(), Some("Alice"));
+        assert_eq!(intel.level, 0);
+        assert_eq!(intel.sources, vec![(10, 20), (33, 4)]);
+        assert_eq!(intel.threat, ThreatSummary::default());
+    }
+
+    #[test]
+    fn round_trips() {
+        let intel = RoomIntel {
+            room_name: RoomName::new("E12N7").unwrap(),
+            last_seen: 31_415_926,
+            owner: Some("Bob".to_owned()),
+            level: 7,
+            towers: 3,
+            spawns: 2,
+            sources: vec![(5, 44), (40, 12)],
+            mineral: Some(ResourceType::Catalyst),
+            threat: ThreatSummary {
+                hostile_creeps: 2,
+                attack_parts: 10,
+                ranged_attack_parts: 4,
+                heal_parts: 6,
+            },
+        };
+        let json = serde_json::to_string(&intel).unwrap();
+        assert_eq!(serde_json::from_str::<RoomIntel>(&json).unwrap(), intel);
+
+        let unowned = RoomIntel {
+            owner: None,
+            mineral: None,
+            ..intel
+        };
+        let json = serde_json::to_string(&unowned).unwrap();
+        assert!(
+            !json.contains(r#""o":"#) && !json.contains(r#""m":"#),
+            "{}",
+            json
+        );
+        assert_eq!(serde_json::from_str::<RoomIntel>(&json).unwrap(), unowned);
+    }
+}
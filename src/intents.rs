@@ -0,0 +1,470 @@
+//! Debugging aids for the intents issued by game objects.
+//!
+//! Every successful action is recorded when either of these features is
+//! enabled:
+//!
+//! - `track-intents` counts the intents issued this tick, available from
+//!   [`game::intents`]. Each intent costs [`INTENT_CPU_COST`] on top of the
+//!   CPU used by the script itself.
+//! - `check-intent-conflicts` reports issuing an action which conflicts with
+//!   one already issued by the same object this tick.
+//!
+//! The engine only runs one action out of each conflicting set, so these bugs
+//! otherwise show up as actions silently doing nothing, despite returning
+//! [`ReturnCode::Ok`].
+//!
+//! Separately, the `log-failed-intents` feature logs every action which
+//! returns something other than [`ReturnCode::Ok`], with the object's id or
+//! name, the action and the return code. Each combination of those is logged
+//! at most once per tick, at the level set with [`set_failure_log_level`]
+//! (by default, `warn`).
+//!
+//! Conflicts follow the [simultaneous actions] rules:
+//!
+//! - issuing the same action twice only runs the last one, except for
+//!   `createConstructionSite`, which can be issued any number of times
+//! - for creeps, within each of these pipelines, only the rightmost action
+//!   issued runs:
+//!   - `harvest`, `attack`, `build`, `repair`, `dismantle`,
+//!     `attackController`, `rangedHeal`, `heal`
+//!   - `rangedAttack`, `rangedMassAttack`, `build`, `repair`, `rangedHeal`
+//!   - `upgradeController`, `build`, `repair`, `withdraw`, `transfer`, `drop`
+//!
+//! [`ReturnCode::Ok`]: crate::constants::ReturnCode::Ok
+//! [`game::intents`]: crate::game::intents
+//! [simultaneous actions]: https://docs.screeps.com/simultaneous-actions.html
+#[cfg(any(
+    feature = "check-intent-conflicts",
+    feature = "track-intents",
+    feature = "log-failed-intents"
+))]
+use std::cell::RefCell;
+#[cfg(any(feature = "check-intent-conflicts", feature = "track-intents"))]
+use std::collections::HashMap;
+#[cfg(feature = "log-failed-intents")]
+use std::collections::HashSet;
+
+use parse_display::{Display, FromStr};
+
+#[cfg(any(
+    feature = "check-intent-conflicts",
+    feature = "track-intents",
+    feature = "log-failed-intents"
+))]
+use stdweb::Reference;
+
+#[cfg(feature = "log-failed-intents")]
+use crate::constants::ReturnCode;
+
+/// CPU charged for each intent which was successfully issued.
+pub const INTENT_CPU_COST: f64 = 0.2;
+
+/// Actions which issue an intent when they return [`ReturnCode::Ok`].
+///
+/// Named after the JavaScript method issuing the intent, so `move_to`,
+/// `move_direction` and friends are all [`IntentKind::Move`]. Displays as,
+/// and parses from, the JavaScript method name.
+///
+/// [`ReturnCode::Ok`]: crate::constants::ReturnCode::Ok
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Display, FromStr)]
+#[display(style = "camelCase")]
+pub enum IntentKind {
+    ActivateSafeMode,
+    Attack,
+    AttackController,
+    BoostCreep,
+    Build,
+    Cancel,
+    ClaimController,
+    CreateConstructionSite,
+    Destroy,
+    Dismantle,
+    Drop,
+    EnableRoom,
+    GenerateSafeMode,
+    Harvest,
+    Heal,
+    LaunchNuke,
+    Move,
+    NotifyWhenAttacked,
+    ObserveRoom,
+    Pickup,
+    ProcessPower,
+    Produce,
+    Pull,
+    RangedAttack,
+    RangedHeal,
+    RangedMassAttack,
+    RecycleCreep,
+    Renew,
+    RenewCreep,
+    Repair,
+    ReserveController,
+    ReverseReaction,
+    RunReaction,
+    Say,
+    Send,
+    SetDirections,
+    SignController,
+    Spawn,
+    SpawnCreep,
+    Suicide,
+    Transfer,
+    TransferEnergy,
+    UnboostCreep,
+    Unclaim,
+    UpgradeController,
+    UsePower,
+    Withdraw,
+}
+
+/// Actions which can't run in the same tick; within each pipeline, later
+/// actions take priority over earlier ones.
+#[cfg(any(test, feature = "check-intent-conflicts"))]
+const PIPELINES: &[&[IntentKind]] = {
+    use IntentKind::*;
+    &[
+        &[
+            Harvest,
+            Attack,
+            Build,
+            Repair,
+            Dismantle,
+            AttackController,
+            RangedHeal,
+            Heal,
+        ],
+        &[RangedAttack, RangedMassAttack, Build, Repair, RangedHeal],
+        &[UpgradeController, Build, Repair, Withdraw, Transfer, Drop],
+    ]
+};
+
+/// Actions which the engine queues up rather than replacing, so they can be
+/// issued any number of times in one tick.
+#[cfg(any(test, feature = "check-intent-conflicts"))]
+const REPEATABLE: &[IntentKind] = &[IntentKind::CreateConstructionSite];
+
+/// What to do when conflicting intents are issued.
+#[cfg(feature = "check-intent-conflicts")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum ConflictMode {
+    /// Log a warning with the `log` crate. This is the default.
+    #[default]
+    Warn,
+    /// Panic, pointing at the action which caused the conflict.
+    Panic,
+}
+
+#[cfg(any(feature = "check-intent-conflicts", feature = "track-intents"))]
+#[derive(Default)]
+struct TickIntents {
+    tick: u32,
+    #[cfg(feature = "check-intent-conflicts")]
+    mode: ConflictMode,
+    #[cfg(feature = "check-intent-conflicts")]
+    issued: HashMap<String, Vec<IntentKind>>,
+    #[cfg(feature = "track-intents")]
+    counts: HashMap<IntentKind, u32>,
+}
+
+#[cfg(any(feature = "check-intent-conflicts", feature = "track-intents"))]
+impl TickIntents {
+    /// Forgets everything recorded in previous ticks.
+    fn refresh(&mut self, time: u32) {
+        if self.tick != time {
+            self.tick = time;
+            #[cfg(feature = "check-intent-conflicts")]
+            self.issued.clear();
+            #[cfg(feature = "track-intents")]
+            self.counts.clear();
+        }
+    }
+}
+
+#[cfg(any(feature = "check-intent-conflicts", feature = "track-intents"))]
+thread_local! {
+    static TICK_INTENTS: RefCell<TickIntents> = RefCell::new(TickIntents::default());
+}
+
+/// Sets how conflicting intents are reported.
+#[cfg(feature = "check-intent-conflicts")]
+pub fn set_conflict_mode(mode: ConflictMode) {
+    TICK_INTENTS.with(|state| state.borrow_mut().mode = mode);
+}
+
+/// Finds which of two intents issued by the same object would be dropped, if
+/// they conflict. `creep` enables the creep action pipelines.
+#[cfg(any(test, feature = "check-intent-conflicts"))]
+fn conflict(earlier: IntentKind, later: IntentKind, creep: bool) -> Option<IntentKind> {
+    if earlier == later {
+        if REPEATABLE.contains(&later) {
+            return None;
+        }
+        return Some(earlier);
+    }
+    if !creep {
+        return None;
+    }
+    PIPELINES.iter().find_map(|pipeline| {
+        let earlier_idx = pipeline.iter().position(|&k| k == earlier)?;
+        let later_idx = pipeline.iter().position(|&k| k == later)?;
+        Some(if earlier_idx < later_idx {
+            earlier
+        } else {
+            later
+        })
+    })
+}
+
+/// Reports `kind` if it conflicts with an intent already issued by the
+/// object identified by `key`.
+#[cfg(feature = "check-intent-conflicts")]
+fn check_conflicts(state: &mut TickIntents, key: String, creep: bool, kind: IntentKind) {
+    let mode = state.mode;
+    let tick = state.tick;
+    let issued = state.issued.entry(key.clone()).or_default();
+    for &earlier in issued.iter() {
+        if let Some(dropped) = conflict(earlier, kind, creep) {
+            let message = format!(
+                "object {} issued `{}` after `{}` in tick {}; `{}` will not run",
+                key, kind, earlier, tick, dropped,
+            );
+            match mode {
+                ConflictMode::Warn => log::warn!("{}", message),
+                ConflictMode::Panic => panic!("{}", message),
+            }
+        }
+    }
+    issued.push(kind);
+}
+
+/// Identifies the object issuing an intent in logs and conflict reports, by
+/// its id or name. Positions, which have neither, are identified by their
+/// JavaScript string form.
+#[cfg(any(feature = "check-intent-conflicts", feature = "log-failed-intents"))]
+fn object_key(object: &Reference) -> String {
+    js_unwrap!(@{object}.id || @{object}.name || String(@{object}))
+}
+
+/// Records an intent which was successfully issued by `object`.
+///
+/// Called by the action methods through the `record_intent!` macro.
+#[cfg(any(feature = "check-intent-conflicts", feature = "track-intents"))]
+pub(crate) fn record(object: &Reference, kind: IntentKind) {
+    let time = crate::game::time();
+
+    TICK_INTENTS.with(|state| {
+        let mut state = state.borrow_mut();
+        state.refresh(time);
+
+        #[cfg(feature = "track-intents")]
+        {
+            *state.counts.entry(kind).or_insert(0) += 1;
+        }
+
+        #[cfg(feature = "check-intent-conflicts")]
+        {
+            let key = object_key(object);
+            let creep: bool = js_unwrap!(@{object} instanceof Creep);
+            check_conflicts(&mut state, key, creep, kind);
+        }
+    });
+
+    #[cfg(not(feature = "check-intent-conflicts"))]
+    let _ = object;
+}
+
+/// Counts of the intents issued during one tick, by kind.
+///
+/// See [`game::intents`](crate::game::intents).
+#[cfg(feature = "track-intents")]
+#[derive(Clone, Debug, Default)]
+pub struct IntentSummary {
+    counts: HashMap<IntentKind, u32>,
+}
+
+#[cfg(feature = "track-intents")]
+impl IntentSummary {
+    /// Number of intents of one kind issued.
+    pub fn count(&self, kind: IntentKind) -> u32 {
+        self.counts.get(&kind).copied().unwrap_or(0)
+    }
+
+    /// Iterates over the kinds of intents issued, with their counts.
+    pub fn iter(&self) -> impl Iterator<Item = (IntentKind, u32)> + '_ {
+        self.counts.iter().map(|(&kind, &count)| (kind, count))
+    }
+
+    /// Number of intents issued, of any kind.
+    pub fn total(&self) -> u32 {
+        self.counts.values().sum()
+    }
+
+    /// CPU which will be charged for these intents.
+    pub fn cpu_cost(&self) -> f64 {
+        f64::from(self.total()) * INTENT_CPU_COST
+    }
+}
+
+#[cfg(feature = "track-intents")]
+pub(crate) fn summary() -> IntentSummary {
+    let time = crate::game::time();
+
+    TICK_INTENTS.with(|state| {
+        let mut state = state.borrow_mut();
+        state.refresh(time);
+        IntentSummary {
+            counts: state.counts.clone(),
+        }
+    })
+}
+
+#[cfg(feature = "log-failed-intents")]
+struct FailureLog {
+    tick: u32,
+    level: log::Level,
+    logged: HashSet<(String, String, ReturnCode)>,
+}
+
+#[cfg(feature = "log-failed-intents")]
+thread_local! {
+    static FAILURE_LOG: RefCell<FailureLog> = RefCell::new(FailureLog {
+        tick: 0,
+        level: log::Level::Warn,
+        logged: HashSet::new(),
+    });
+}
+
+/// Sets the level failed actions are logged at.
+#[cfg(feature = "log-failed-intents")]
+pub fn set_failure_log_level(level: log::Level) {
+    FAILURE_LOG.with(|state| state.borrow_mut().level = level);
+}
+
+/// Logs an action by `object` which returned `result`, unless the same
+/// failure has already been logged this tick.
+///
+/// Called by the action methods through the `record_intent!` macro.
+#[cfg(feature = "log-failed-intents")]
+pub(crate) fn log_failure(object: &Reference, action: &str, result: ReturnCode) {
+    let time = crate::game::time();
+    let key = object_key(object);
+
+    FAILURE_LOG.with(|state| {
+        let mut state = state.borrow_mut();
+        if state.tick != time {
+            state.tick = time;
+            state.logged.clear();
+        }
+        let level = state.level;
+        if state
+            .logged
+            .insert((key.clone(), action.to_owned(), result))
+        {
+            log::log!(
+                level,
+                "object {} failed `{}` in tick {}: {:?} ({})",
+                key,
+                action,
+                time,
+                result,
+                result,
+            );
+        }
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::{conflict, IntentKind::*, PIPELINES};
+
+    #[test]
+    fn repeated_action_drops_the_earlier_one() {
+        assert_eq!(conflict(Move, Move, true), Some(Move));
+        assert_eq!(conflict(Say, Say, true), Some(Say));
+        assert_eq!(conflict(Transfer, Transfer, false), Some(Transfer));
+        assert_eq!(conflict(SpawnCreep, SpawnCreep, false), Some(SpawnCreep));
+    }
+
+    #[test]
+    fn repeatable_actions_never_conflict() {
+        assert_eq!(
+            conflict(CreateConstructionSite, CreateConstructionSite, false),
+            None
+        );
+        assert_eq!(
+            conflict(CreateConstructionSite, CreateConstructionSite, true),
+            None
+        );
+    }
+
+    #[test]
+    fn pipeline_drops_the_leftmost_action() {
+        assert_eq!(conflict(Harvest, Attack, true), Some(Harvest));
+        assert_eq!(conflict(Attack, Harvest, true), Some(Harvest));
+        assert_eq!(conflict(Heal, Dismantle, true), Some(Dismantle));
+        assert_eq!(
+            conflict(RangedAttack, RangedMassAttack, true),
+            Some(RangedAttack)
+        );
+        assert_eq!(conflict(Build, RangedAttack, true), Some(RangedAttack));
+        assert_eq!(conflict(Repair, RangedHeal, true), Some(Repair));
+        assert_eq!(
+            conflict(Drop, UpgradeController, true),
+            Some(UpgradeController)
+        );
+        assert_eq!(conflict(Withdraw, Transfer, true), Some(Withdraw));
+    }
+
+    #[test]
+    fn pipeline_conflicts_are_symmetric() {
+        for pipeline in PIPELINES {
+            for (i, &left) in pipeline.iter().enumerate() {
+                for &right in &pipeline[i + 1..] {
+                    assert_eq!(
+                        conflict(left, right, true),
+                        Some(left),
+                        "{} {}",
+                        left,
+                        right
+                    );
+                    assert_eq!(
+                        conflict(right, left, true),
+                        Some(left),
+                        "{} {}",
+                        right,
+                        left
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn actions_in_different_pipelines_are_allowed() {
+        assert_eq!(conflict(Harvest, RangedAttack, true), None);
+        assert_eq!(conflict(RangedMassAttack, Heal, true), None);
+        assert_eq!(conflict(Attack, RangedAttack, true), None);
+        assert_eq!(conflict(Harvest, Transfer, true), None);
+        assert_eq!(conflict(Heal, UpgradeController, true), None);
+        assert_eq!(conflict(RangedHeal, Withdraw, true), None);
+    }
+
+    #[test]
+    fn actions_outside_pipelines_are_allowed() {
+        assert_eq!(conflict(Move, Harvest, true), None);
+        assert_eq!(conflict(Harvest, Move, true), None);
+        assert_eq!(conflict(Say, Attack, true), None);
+        assert_eq!(conflict(Pickup, Transfer, true), None);
+        assert_eq!(conflict(Pull, Move, true), None);
+        assert_eq!(conflict(Suicide, Say, true), None);
+    }
+
+    #[test]
+    fn pipelines_only_apply_to_creeps() {
+        assert_eq!(conflict(Withdraw, Transfer, false), None);
+        assert_eq!(conflict(Transfer, Drop, false), None);
+        assert_eq!(conflict(Heal, RangedHeal, false), None);
+        assert_eq!(conflict(RunReaction, ReverseReaction, false), None);
+    }
+}
@@ -14,7 +14,28 @@
 //! > This data has nothing to do with Memory contents, it's a separate data
 //! > container.
 //!
+//! [`ShardData`] gives each shard's data a common JSON layout, with named
+//! sections and claims, so shards can share state and coordinate without a
+//! custom protocol:
+//!
+//! ```no_run
+//! use screeps::inter_shard_memory::ShardData;
+//!
+//! let mut local = ShardData::load_local();
+//! // hand a creep over to shard1, unless shard1 or shard3 is already
+//! // taking it
+//! if local.try_claim("handoff:Bob", 1500, &["shard1", "shard3"]) {
+//!     local.set_section("handoff", &vec!["Bob"]);
+//! }
+//! local.save_local();
+//! ```
+//!
 //! [`InterShardMemory`]: https://docs.screeps.com/api/#InterShardMemory
+use std::collections::BTreeMap;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::game;
 
 /// Returns the string contents of the current shard's data, `None` if it hasn't
 /// been set or on a private server without the intershard memory interface
@@ -39,3 +60,138 @@ pub fn set_local(value: &str) {
 pub fn get_remote(shard: &str) -> Option<String> {
     js_unwrap!(typeof(InterShardMemory) == "object" && InterShardMemory.getRemote(@{shard}) || null)
 }
+
+/// A claim on some shared resource, held by the shard whose data it's in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Claim {
+    /// The tick, on the holding shard, when this claim expires.
+    pub expires: u32,
+}
+
+/// One shard's data, in a layout shared by every shard.
+///
+/// The JSON layout is `{"sections": {...}, "claims": {...}}`, where each
+/// section holds any JSON value and each claim is `{"expires": <tick>}`.
+///
+/// Ticks differ between shards, so claims are only expired by the shard
+/// holding them, when it calls [`ShardData::save_local`]. Other shards treat
+/// every claim they see as held.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ShardData {
+    #[serde(default)]
+    pub sections: BTreeMap<String, serde_json::Value>,
+    #[serde(default)]
+    pub claims: BTreeMap<String, Claim>,
+}
+
+impl ShardData {
+    /// Reads the current shard's data, or empty data if it hasn't been set or
+    /// isn't in this layout.
+    pub fn load_local() -> ShardData {
+        get_local()
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default()
+    }
+
+    /// Reads another shard's data, or `None` if it hasn't been set or isn't
+    /// in this layout.
+    pub fn load_remote(shard: &str) -> Option<ShardData> {
+        serde_json::from_str(&get_remote(shard)?).ok()
+    }
+
+    /// Drops expired claims and replaces the current shard's data with this.
+    pub fn save_local(&mut self) {
+        let time = game::time();
+        self.claims.retain(|_, claim| claim.expires > time);
+        let data = serde_json::to_string(self).expect("expected shard data to serialize");
+        set_local(&data);
+    }
+
+    /// Gets a section, or `None` if it's missing or isn't a `T`.
+    pub fn section<T: DeserializeOwned>(&self, name: &str) -> Option<T> {
+        serde_json::from_value(self.sections.get(name)?.clone()).ok()
+    }
+
+    /// Sets a section, replacing any section with the same name.
+    pub fn set_section<T: Serialize>(&mut self, name: &str, value: &T) {
+        let value = serde_json::to_value(value).expect("expected section to serialize");
+        self.sections.insert(name.to_owned(), value);
+    }
+
+    pub fn remove_section(&mut self, name: &str) {
+        self.sections.remove(name);
+    }
+
+    /// Whether this data holds an unexpired claim on `key`, according to the
+    /// current shard's tick.
+    ///
+    /// Only meaningful for the current shard's own data.
+    pub fn holds_claim(&self, key: &str) -> bool {
+        matches!(self.claims.get(key), Some(claim) if claim.expires > game::time())
+    }
+
+    /// Claims `key` for the next `ticks` ticks, replacing any existing claim
+    /// on it.
+    pub fn claim(&mut self, key: &str, ticks: u32) {
+        let expires = game::time() + ticks;
+        self.claims.insert(key.to_owned(), Claim { expires });
+    }
+
+    pub fn release(&mut self, key: &str) {
+        self.claims.remove(key);
+    }
+
+    /// Claims `key` for the next `ticks` ticks if none of `other_shards`
+    /// holds a claim on it, returning whether the claim was made.
+    ///
+    /// A claim already held by the current shard is renewed. Both shards can
+    /// still claim the same key if they do so in the same tick, so claims
+    /// should be checked again in later ticks before relying on them.
+    pub fn try_claim(&mut self, key: &str, ticks: u32, other_shards: &[&str]) -> bool {
+        let taken = other_shards.iter().any(|shard| {
+            matches!(ShardData::load_remote(shard), Some(data) if data.claims.contains_key(key))
+        });
+        if !taken {
+            self.claim(key, ticks);
+        }
+        !taken
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Claim, ShardData};
+
+    #[test]
+    fn serializes_as_documented() {
+        let mut data = ShardData::default();
+        data.set_section("handoff", &vec!["Bob"]);
+        data.set_section("rooms", &3);
+        data.claims
+            .insert("handoff:Bob".to_owned(), Claim { expires: 1500 });
+
+        let json = serde_json::to_string(&data).unwrap();
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"sections":{"handoff":["Bob"],"rooms":3},"#,
+                r#""claims":{"handoff:Bob":{"expires":1500}}}"#
+            )
+        );
+        assert_eq!(serde_json::from_str::<ShardData>(&json).unwrap(), data);
+    }
+
+    #[test]
+    fn reads_sections_and_missing_fields() {
+        let data: ShardData = serde_json::from_str(r#"{"sections":{"handoff":["Bob"]}}"#).unwrap();
+        assert_eq!(data.section::<Vec<String>>("handoff").unwrap(), ["Bob"]);
+        assert_eq!(data.section::<u32>("handoff"), None);
+        assert_eq!(data.section::<u32>("missing"), None);
+        assert!(data.claims.is_empty());
+
+        assert_eq!(
+            serde_json::from_str::<ShardData>("{}").unwrap(),
+            ShardData::default()
+        );
+    }
+}
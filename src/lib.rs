@@ -25,21 +25,32 @@ extern crate stdweb;
 #[macro_use]
 pub mod macros;
 
+pub mod console;
 pub mod constants;
+pub mod construction;
+mod error;
 pub mod game;
+pub mod intel;
+pub mod intents;
 pub mod inter_shard_memory;
 pub mod js_collections;
 pub mod local;
 pub mod memory;
 pub mod objects;
 pub mod pathfinder;
+pub mod profiling;
 pub mod raw_memory;
+pub mod repair;
+pub mod safe_mode;
+pub mod screeps_logging;
+pub mod stats;
 pub mod traits;
 
 pub use stdweb::private::ConversionError;
 
 pub use crate::{
     constants::*,
+    error::ScreepsError,
     js_collections::JsVec,
     local::{ObjectId, Position, RawObjectId, RawObjectIdParseError, RoomName, RoomNameParseError},
     objects::*,
@@ -1,9 +1,24 @@
 //! Pure-data structures relating to Screeps.
 use std::ops::Range;
 
+mod body;
 mod object_id;
 mod room_name;
 mod room_position;
+mod serialized_path;
+mod terrain;
+mod terrain_cache;
+mod world_bounds;
+
+pub mod combat;
+pub mod creep_name;
+pub mod labs;
+pub mod links;
+pub mod path_search;
+pub mod remote_mining;
+pub mod route;
+pub mod stamps;
+pub mod terminal_balance;
 
 /// Represents two constants related to room names.
 ///
@@ -18,4 +33,7 @@ const HALF_WORLD_SIZE: i32 = 128;
 /// Valid room name coordinates.
 const VALID_ROOM_NAME_COORDINATES: Range<i32> = -HALF_WORLD_SIZE..HALF_WORLD_SIZE;
 
-pub use self::{object_id::*, room_name::*, room_position::*};
+pub use self::{
+    body::*, object_id::*, room_name::*, room_position::*, serialized_path::*, terrain::*,
+    terrain_cache::*, world_bounds::*,
+};
@@ -0,0 +1,211 @@
+use std::{error, fmt};
+
+use crate::constants::{Part, MAX_CREEP_SIZE};
+
+/// Builds a creep body as a `Vec<Part>`, from parts and how many of each to
+/// include, in order.
+///
+/// Part names are the lowercase `BODYPART_*` names: `move`, `work`, `carry`,
+/// `attack`, `ranged_attack`, `tough`, `heal` and `claim`.
+///
+/// This doesn't check the body's size or cost; use [`BodyBuilder`] for that.
+///
+/// # Example
+///
+/// ```
+/// use screeps::{body, Part};
+///
+/// let body = body![work(2), carry(1), move(3 - 1)];
+/// assert_eq!(
+///     body,
+///     [Part::Work, Part::Work, Part::Carry, Part::Move, Part::Move]
+/// );
+/// ```
+///
+/// [`BodyBuilder`]: crate::local::BodyBuilder
+#[macro_export]
+macro_rules! body {
+    (@part move) => { $crate::constants::Part::Move };
+    (@part work) => { $crate::constants::Part::Work };
+    (@part carry) => { $crate::constants::Part::Carry };
+    (@part attack) => { $crate::constants::Part::Attack };
+    (@part ranged_attack) => { $crate::constants::Part::RangedAttack };
+    (@part tough) => { $crate::constants::Part::Tough };
+    (@part heal) => { $crate::constants::Part::Heal };
+    (@part claim) => { $crate::constants::Part::Claim };
+    ($($part:tt ( $count:expr )),* $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut body: ::std::vec::Vec<$crate::constants::Part> = ::std::vec::Vec::new();
+        $(
+            body.extend(::std::iter::repeat($crate::body!(@part $part)).take($count));
+        )*
+        body
+    }};
+}
+
+/// An error from [`BodyBuilder::build`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BodyError {
+    /// The body has no parts.
+    Empty,
+    /// The body has more than `MAX_CREEP_SIZE` parts.
+    TooLarge { size: u32 },
+    /// The body costs more energy than is available.
+    TooExpensive { cost: u32, energy: u32 },
+}
+
+impl fmt::Display for BodyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BodyError::Empty => write!(f, "expected creep body to have at least one part"),
+            BodyError::TooLarge { size } => write!(
+                f,
+                "expected creep body of at most {} parts, got {} parts",
+                MAX_CREEP_SIZE, size
+            ),
+            BodyError::TooExpensive { cost, energy } => write!(
+                f,
+                "creep body costs {} energy, but only {} is available",
+                cost, energy
+            ),
+        }
+    }
+}
+
+impl error::Error for BodyError {}
+
+/// Builds a creep body, checking it can be spawned.
+///
+/// # Example
+///
+/// ```
+/// use screeps::{local::BodyBuilder, Part};
+///
+/// let body = BodyBuilder::new()
+///     .part(Part::Work, 2)
+///     .part(Part::Carry, 1)
+///     .part(Part::Move, 2);
+/// assert_eq!(body.cost(), 350);
+/// assert!(body.clone().build(300).is_err());
+/// assert_eq!(body.build(550).unwrap().len(), 5);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BodyBuilder {
+    parts: Vec<Part>,
+}
+
+impl BodyBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `count` parts of one type to the end of the body.
+    pub fn part(mut self, part: Part, count: u32) -> Self {
+        self.parts.resize(self.parts.len() + count as usize, part);
+        self
+    }
+
+    /// Adds copies of `pattern` to the end of the body while the whole body
+    /// costs at most `energy` and has at most `MAX_CREEP_SIZE` parts.
+    ///
+    /// Useful for bodies which scale with the energy available, such as
+    /// repeated `[work, carry, move]` groups.
+    pub fn repeat(mut self, pattern: &[Part], energy: u32) -> Self {
+        let pattern_cost: u32 = pattern.iter().map(|p| p.cost()).sum();
+        if pattern.is_empty() {
+            return self;
+        }
+        while self.len() + pattern.len() as u32 <= MAX_CREEP_SIZE
+            && self.cost() + pattern_cost <= energy
+        {
+            self.parts.extend_from_slice(pattern);
+        }
+        self
+    }
+
+    pub fn parts(&self) -> &[Part] {
+        &self.parts
+    }
+
+    /// Number of parts in the body.
+    pub fn len(&self) -> u32 {
+        self.parts.len() as u32
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parts.is_empty()
+    }
+
+    /// Energy needed to spawn the body.
+    pub fn cost(&self) -> u32 {
+        self.parts.iter().map(|p| p.cost()).sum()
+    }
+
+    /// Finishes the body, checking it has between 1 and `MAX_CREEP_SIZE`
+    /// parts and costs at most `energy`.
+    pub fn build(self, energy: u32) -> Result<Vec<Part>, BodyError> {
+        if self.is_empty() {
+            return Err(BodyError::Empty);
+        }
+        if self.len() > MAX_CREEP_SIZE {
+            return Err(BodyError::TooLarge { size: self.len() });
+        }
+        let cost = self.cost();
+        if cost > energy {
+            return Err(BodyError::TooExpensive { cost, energy });
+        }
+        Ok(self.parts)
+    }
+}
+
+impl From<Vec<Part>> for BodyBuilder {
+    fn from(parts: Vec<Part>) -> Self {
+        BodyBuilder { parts }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BodyBuilder, BodyError};
+    use crate::constants::Part;
+
+    #[test]
+    fn macro_builds_in_order() {
+        let body = body![tough(1), ranged_attack(2), heal(1), move(4),];
+        assert_eq!(body.len(), 8);
+        assert_eq!(body[0], Part::Tough);
+        assert_eq!(body[2], Part::RangedAttack);
+        assert_eq!(body[3], Part::Heal);
+        assert!(body[4..].iter().all(|&p| p == Part::Move));
+
+        let empty: Vec<Part> = body![];
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn builder_validates() {
+        assert_eq!(BodyBuilder::new().build(1000), Err(BodyError::Empty));
+        assert_eq!(
+            BodyBuilder::new().part(Part::Move, 51).build(100_000),
+            Err(BodyError::TooLarge { size: 51 })
+        );
+        assert_eq!(
+            BodyBuilder::from(body![claim(1), move(1)]).build(600),
+            Err(BodyError::TooExpensive {
+                cost: 650,
+                energy: 600
+            })
+        );
+    }
+
+    #[test]
+    fn repeat_fits_energy_and_size() {
+        let pattern = [Part::Work, Part::Carry, Part::Move];
+        let body = BodyBuilder::new().repeat(&pattern, 800);
+        assert_eq!(body.len(), 12);
+        assert_eq!(body.cost(), 800);
+
+        let body = BodyBuilder::new().repeat(&[Part::Move], 100_000);
+        assert_eq!(body.len(), 50);
+    }
+}
@@ -0,0 +1,303 @@
+//! Combat calculations done locally, from creeps' positions and bodies.
+//!
+//! A [`Combatant`] is a snapshot of a creep's position and active parts,
+//! including boosts. From those, this module works out how far hostiles can
+//! reach, whether `rangedMassAttack` beats `rangedAttack` on a group of
+//! targets, and which way to move to kite away from threats.
+//!
+//! ```
+//! use screeps::{
+//!     local::combat::{self, Combatant},
+//!     Direction, Part, Position, RoomName,
+//! };
+//!
+//! let room = RoomName::new("W1N1").unwrap();
+//! let me = Combatant::new(
+//!     Position::new(25, 25, room),
+//!     &[Part::RangedAttack, Part::Move],
+//! );
+//! let hostiles = [
+//!     Combatant::new(Position::new(27, 25, room), &[Part::Attack, Part::Move]),
+//!     Combatant::new(Position::new(26, 26, room), &[Part::Attack, Part::Move]),
+//! ];
+//!
+//! assert_eq!(combat::max_threat_range(&hostiles), Some(1));
+//! assert!(combat::is_threatened(me.pos, &hostiles));
+//! assert!(combat::prefer_mass_attack(&me, &hostiles));
+//! assert_eq!(combat::kite_direction(me.pos, &hostiles), Some(Direction::TopLeft));
+//! ```
+use crate::{
+    constants::{
+        Boost, Direction, Part, ResourceType, ATTACK_POWER, HEAL_POWER, RANGED_ATTACK_POWER,
+    },
+    objects::{Creep, HasPosition},
+};
+
+use super::Position;
+
+/// Fraction of `RANGED_ATTACK_POWER` which `rangedMassAttack` deals at each
+/// range, from the engine's `RANGED_MASS_ATTACK_DISTANCE_RATE`.
+fn mass_attack_rate(range: u32) -> f64 {
+    match range {
+        0 | 1 => 1.0,
+        2 => 0.4,
+        3 => 0.1,
+        _ => 0.0,
+    }
+}
+
+/// A creep's position and active parts, as far as combat is concerned.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Combatant {
+    pub pos: Position,
+    /// Each active part, with the compound it's boosted with if any.
+    pub parts: Vec<(Part, Option<ResourceType>)>,
+}
+
+impl Combatant {
+    /// A combatant with unboosted parts.
+    pub fn new(pos: Position, parts: &[Part]) -> Self {
+        Combatant {
+            pos,
+            parts: parts.iter().map(|&part| (part, None)).collect(),
+        }
+    }
+
+    /// A snapshot of a visible creep, leaving out parts with no hits left.
+    pub fn from_creep(creep: &Creep) -> Self {
+        Combatant {
+            pos: creep.pos(),
+            parts: creep
+                .body()
+                .into_iter()
+                .filter(|part| part.hits > 0)
+                .map(|part| (part.part, part.boost))
+                .collect(),
+        }
+    }
+
+    /// Sum of `base` for each part of type `part`, multiplied by its boost.
+    fn power(&self, part: Part, base: u32) -> f64 {
+        self.parts
+            .iter()
+            .filter(|&&(p, _)| p == part)
+            .map(|&(_, boost)| {
+                let multiplier = match boost.and_then(ResourceType::boost) {
+                    Some(Boost::Attack(m)) if part == Part::Attack => m,
+                    Some(Boost::RangedAttack(m)) if part == Part::RangedAttack => m,
+                    Some(Boost::Heal(m)) if part == Part::Heal => m,
+                    _ => 1.0,
+                };
+                f64::from(base) * multiplier
+            })
+            .sum()
+    }
+
+    /// Damage dealt by `attack` per tick.
+    pub fn attack_damage(&self) -> f64 {
+        self.power(Part::Attack, ATTACK_POWER)
+    }
+
+    /// Damage dealt by `rangedAttack` per tick, at any range up to 3.
+    pub fn ranged_attack_damage(&self) -> f64 {
+        self.power(Part::RangedAttack, RANGED_ATTACK_POWER)
+    }
+
+    /// Hits restored by `heal` on an adjacent creep per tick.
+    pub fn heal_power(&self) -> f64 {
+        self.power(Part::Heal, HEAL_POWER)
+    }
+
+    /// How far this creep can deal damage from without moving: `3` with
+    /// ranged attack parts, `1` with only attack parts, or `None` if it can't
+    /// attack.
+    pub fn threat_range(&self) -> Option<u32> {
+        let has = |part| self.parts.iter().any(|&(p, _)| p == part);
+        if has(Part::RangedAttack) {
+            Some(3)
+        } else if has(Part::Attack) {
+            Some(1)
+        } else {
+            None
+        }
+    }
+
+    /// How far away this creep can deal damage from next tick, counting one
+    /// move towards the target if it has a move part.
+    pub fn reach(&self) -> Option<u32> {
+        let can_move = self.parts.iter().any(|&(p, _)| p == Part::Move);
+        self.threat_range().map(|range| range + u32::from(can_move))
+    }
+
+    /// Damage `rangedMassAttack` would deal to targets at the given
+    /// positions, in total.
+    ///
+    /// This ignores tough parts and ramparts, as [`Combatant::ranged_attack_damage`]
+    /// does.
+    pub fn mass_attack_damage<I>(&self, targets: I) -> f64
+    where
+        I: IntoIterator<Item = Position>,
+    {
+        let damage = self.ranged_attack_damage();
+        targets
+            .into_iter()
+            .map(|target| damage * mass_attack_rate(self.pos.get_range_to(&target)))
+            .sum()
+    }
+}
+
+/// The furthest any of `hostiles` can deal damage from without moving, or
+/// `None` if none of them can attack.
+pub fn max_threat_range(hostiles: &[Combatant]) -> Option<u32> {
+    hostiles.iter().filter_map(Combatant::threat_range).max()
+}
+
+/// Whether any of `hostiles` could deal damage at `pos` next tick, after
+/// moving once.
+pub fn is_threatened(pos: Position, hostiles: &[Combatant]) -> bool {
+    hostiles.iter().any(|hostile| match hostile.reach() {
+        Some(reach) => pos.get_range_to(&hostile.pos) <= reach,
+        None => false,
+    })
+}
+
+/// Whether `rangedMassAttack` would deal more total damage to `targets` than
+/// `rangedAttack` on one of them.
+///
+/// Returns `false` if no target is in range.
+pub fn prefer_mass_attack(attacker: &Combatant, targets: &[Combatant]) -> bool {
+    let in_range = targets
+        .iter()
+        .filter(|target| attacker.pos.get_range_to(&target.pos) <= 3);
+    let mass = attacker.mass_attack_damage(in_range.clone().map(|target| target.pos));
+    in_range.count() > 0 && mass > attacker.ranged_attack_damage()
+}
+
+/// The direction away from hostiles which could reach `pos`, as a sum of
+/// vectors pointing away from each, weighted so closer hostiles count more.
+///
+/// Returns `None` if no hostile could reach `pos` next tick.
+pub fn kite_vector(pos: Position, hostiles: &[Combatant]) -> Option<(f64, f64)> {
+    let mut vector = None;
+    for hostile in hostiles {
+        let range = pos.get_range_to(&hostile.pos);
+        match hostile.reach() {
+            Some(reach) if range <= reach && range > 0 => {
+                let (dx, dy) = pos - hostile.pos;
+                let weight = 1.0 / f64::from(range * range);
+                let (x, y) = vector.unwrap_or((0.0, 0.0));
+                vector = Some((x + f64::from(dx) * weight, y + f64::from(dy) * weight));
+            }
+            _ => (),
+        }
+    }
+    vector
+}
+
+/// The direction to move in to get away from hostiles which could reach
+/// `pos` next tick, or `None` if there are none or they're balanced on
+/// either side.
+///
+/// This doesn't take terrain into account; check the tile in the returned
+/// direction can be moved to before moving.
+pub fn kite_direction(pos: Position, hostiles: &[Combatant]) -> Option<Direction> {
+    let (dx, dy) = kite_vector(pos, hostiles)?;
+    // same rules as `Position::get_direction_to`
+    if dx.abs() > dy.abs() * 2.0 {
+        Some(if dx > 0.0 {
+            Direction::Right
+        } else {
+            Direction::Left
+        })
+    } else if dy.abs() > dx.abs() * 2.0 {
+        Some(if dy > 0.0 {
+            Direction::Bottom
+        } else {
+            Direction::Top
+        })
+    } else if dx > 0.0 && dy > 0.0 {
+        Some(Direction::BottomRight)
+    } else if dx > 0.0 && dy < 0.0 {
+        Some(Direction::TopRight)
+    } else if dx < 0.0 && dy > 0.0 {
+        Some(Direction::BottomLeft)
+    } else if dx < 0.0 && dy < 0.0 {
+        Some(Direction::TopLeft)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{is_threatened, kite_direction, max_threat_range, prefer_mass_attack, Combatant};
+    use crate::{
+        constants::{Direction, Part, ResourceType},
+        local::{Position, RoomName},
+    };
+
+    fn pos(x: u32, y: u32) -> Position {
+        Position::new(x, y, RoomName::new("E1N1").unwrap())
+    }
+
+    #[test]
+    fn boosted_damage() {
+        let mut creep = Combatant::new(pos(10, 10), &[Part::Attack, Part::Attack, Part::Heal]);
+        assert_eq!(creep.attack_damage(), 60.0);
+        creep.parts[0].1 = Some(ResourceType::CatalyzedUtriumAcid);
+        // a heal boost on an attack part does nothing
+        creep.parts[1].1 = Some(ResourceType::LemergiumOxide);
+        assert_eq!(creep.attack_damage(), 150.0);
+        assert_eq!(creep.heal_power(), 12.0);
+        assert_eq!(creep.ranged_attack_damage(), 0.0);
+    }
+
+    #[test]
+    fn threat_ranges() {
+        let melee = Combatant::new(pos(10, 10), &[Part::Attack, Part::Move]);
+        let stationary_ranged = Combatant::new(pos(20, 10), &[Part::RangedAttack]);
+        let healer = Combatant::new(pos(30, 10), &[Part::Heal, Part::Move]);
+        assert_eq!(melee.reach(), Some(2));
+        assert_eq!(stationary_ranged.reach(), Some(3));
+        assert_eq!(healer.reach(), None);
+
+        let hostiles = [melee, stationary_ranged, healer];
+        assert_eq!(max_threat_range(&hostiles), Some(3));
+        assert!(is_threatened(pos(12, 12), &hostiles));
+        assert!(!is_threatened(pos(13, 10), &hostiles));
+        assert!(is_threatened(pos(17, 10), &hostiles));
+        assert!(!is_threatened(pos(30, 11), &hostiles));
+        assert_eq!(max_threat_range(&hostiles[2..]), None);
+    }
+
+    #[test]
+    fn mass_attack_choice() {
+        let me = Combatant::new(pos(10, 10), &[Part::RangedAttack]);
+        let far = |x, y| Combatant::new(pos(x, y), &[Part::Move]);
+
+        assert!(!prefer_mass_attack(&me, &[]));
+        assert!(!prefer_mass_attack(&me, &[far(11, 11)]));
+        // 4 + 4 + 1 is less than 10
+        assert!(!prefer_mass_attack(
+            &me,
+            &[far(12, 10), far(8, 10), far(13, 13)]
+        ));
+        assert!(prefer_mass_attack(&me, &[far(11, 10), far(12, 12)]));
+        assert!(!prefer_mass_attack(&me, &[far(20, 20), far(30, 30)]));
+    }
+
+    #[test]
+    fn kiting() {
+        let me = pos(25, 25);
+        let melee = |x, y| Combatant::new(pos(x, y), &[Part::Attack, Part::Move]);
+
+        assert_eq!(kite_direction(me, &[melee(26, 25)]), Some(Direction::Left));
+        assert_eq!(kite_direction(me, &[melee(25, 27)]), Some(Direction::Top));
+        assert_eq!(
+            kite_direction(me, &[melee(24, 24), melee(24, 26)]),
+            Some(Direction::Right)
+        );
+        assert_eq!(kite_direction(me, &[melee(24, 25), melee(26, 25)]), None);
+        assert_eq!(kite_direction(me, &[melee(20, 25)]), None);
+    }
+}
@@ -0,0 +1,308 @@
+//! Storing a creep's role, home room and spawn tick in its name.
+//!
+//! Bots which identify creeps by name don't need to keep anything in
+//! `Memory` for them. [`CreepMeta::encode`] writes the metadata as a short
+//! name like `"3-W1N1-1satc-0-14"`, ending in a checksum so other players'
+//! creeps and hand-named creeps aren't mistaken for encoded names.
+//!
+//! ```
+//! use screeps::{local::creep_name::CreepMeta, RoomName};
+//!
+//! let meta = CreepMeta {
+//!     role: 3,
+//!     home: RoomName::new("W1N1").unwrap(),
+//!     born: 3_000_000,
+//!     index: 0,
+//! };
+//! let name = meta.encode();
+//! assert_eq!(CreepMeta::decode(&name), Ok(meta));
+//! assert!(CreepMeta::decode("harvester-1234-0").is_err());
+//! ```
+//!
+//! [`CreepNameGenerator`] picks names in this format for new creeps, and
+//! [`game::creeps::with_meta`] decodes the names of all your creeps.
+//!
+//! [`game::creeps::with_meta`]: crate::game::creeps::with_meta
+use std::{error, fmt};
+
+use crate::game;
+
+use super::RoomName;
+
+/// Digits used for numbers in names.
+const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// An error from [`CreepMeta::decode`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CreepNameError {
+    /// The name isn't made of the parts [`CreepMeta::encode`] writes.
+    Malformed,
+    /// The name has the right parts, but its checksum doesn't match them.
+    ChecksumMismatch,
+}
+
+impl fmt::Display for CreepNameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CreepNameError::Malformed => {
+                write!(
+                    f,
+                    "expected creep name formatted `role-home-born-index-checksum`"
+                )
+            }
+            CreepNameError::ChecksumMismatch => write!(f, "creep name checksum doesn't match"),
+        }
+    }
+}
+
+impl error::Error for CreepNameError {}
+
+/// Metadata stored in a creep's name.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CreepMeta {
+    /// A role id, with whatever meaning the bot gives it.
+    pub role: u16,
+    /// The room the creep belongs to.
+    pub home: RoomName,
+    /// The tick the creep was spawned on.
+    pub born: u32,
+    /// Distinguishes creeps with the same role and home spawned on the same
+    /// tick; usually `0`.
+    pub index: u32,
+}
+
+impl CreepMeta {
+    /// Writes the metadata as a creep name.
+    pub fn encode(&self) -> String {
+        let body = format!(
+            "{}-{}-{}-{}",
+            encode_number(u32::from(self.role)),
+            self.home,
+            encode_number(self.born),
+            encode_number(self.index)
+        );
+        let check = checksum(&body);
+        format!("{}-{}", body, check)
+    }
+
+    /// Reads metadata from a name written by [`CreepMeta::encode`].
+    pub fn decode(name: &str) -> Result<CreepMeta, CreepNameError> {
+        let split = name.rfind('-').ok_or(CreepNameError::Malformed)?;
+        let (body, check) = (&name[..split], &name[split + 1..]);
+
+        let parts: Vec<&str> = body.split('-').collect();
+        if parts.len() != 4 || check.len() != 2 {
+            return Err(CreepNameError::Malformed);
+        }
+        let role = decode_number(parts[0])?;
+        let home: RoomName = parts[1].parse().map_err(|_| CreepNameError::Malformed)?;
+        // room names parse in any case, but `encode` only writes one form
+        if home.to_array_string().as_str() != parts[1] || role > u32::from(u16::MAX) {
+            return Err(CreepNameError::Malformed);
+        }
+        let born = decode_number(parts[2])?;
+        let index = decode_number(parts[3])?;
+        if check != checksum(body) {
+            return Err(CreepNameError::ChecksumMismatch);
+        }
+
+        Ok(CreepMeta {
+            role: role as u16,
+            home,
+            born,
+            index,
+        })
+    }
+}
+
+/// Generates unique creep names in the format written by
+/// [`CreepMeta::encode`].
+///
+/// Each name holds the role and home room given, the current tick as
+/// `born`, and a counter reset each tick as `index`, skipping any names
+/// already used by a creep in `Game.creeps`. Keep one generator between
+/// calls so names handed out earlier in the same tick aren't repeated.
+///
+/// ```no_run
+/// use screeps::{game, local::creep_name::CreepNameGenerator, Part, RoomName};
+///
+/// const WORKER: u16 = 1;
+///
+/// let mut names = CreepNameGenerator::new();
+/// let home = RoomName::new("W1N1").unwrap();
+/// let spawn = game::spawns::values().pop().unwrap();
+/// spawn.spawn_creep(&[Part::Work, Part::Carry, Part::Move], &names.next_name(WORKER, home));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CreepNameGenerator {
+    tick: u32,
+    counter: u32,
+}
+
+impl CreepNameGenerator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Generates a name for a creep with the given role and home room which
+    /// isn't used by any existing creep.
+    pub fn next_name(&mut self, role: u16, home: RoomName) -> String {
+        self.next_meta(
+            game::time(),
+            role,
+            home,
+            |name| js_unwrap!(@{name} in Game.creeps),
+        )
+        .encode()
+    }
+
+    /// Picks the metadata for the next name at tick `time`, skipping names
+    /// for which `taken` returns `true`.
+    fn next_meta<F>(&mut self, time: u32, role: u16, home: RoomName, mut taken: F) -> CreepMeta
+    where
+        F: FnMut(&str) -> bool,
+    {
+        if self.tick != time {
+            self.tick = time;
+            self.counter = 0;
+        }
+        loop {
+            let meta = CreepMeta {
+                role,
+                home,
+                born: time,
+                index: self.counter,
+            };
+            self.counter += 1;
+            if !taken(&meta.encode()) {
+                return meta;
+            }
+        }
+    }
+}
+
+/// Writes a number in base 36, with lowercase letters.
+fn encode_number(mut n: u32) -> String {
+    let mut digits = Vec::new();
+    loop {
+        digits.push(DIGITS[(n % 36) as usize]);
+        n /= 36;
+        if n == 0 {
+            break;
+        }
+    }
+    digits.reverse();
+    String::from_utf8(digits).expect("expected base 36 digits to be ASCII")
+}
+
+/// Reads a number written by [`encode_number`].
+fn decode_number(s: &str) -> Result<u32, CreepNameError> {
+    let canonical = !s.is_empty()
+        && (s == "0" || !s.starts_with('0'))
+        && s.bytes().all(|b| DIGITS.contains(&b));
+    if !canonical {
+        return Err(CreepNameError::Malformed);
+    }
+    u32::from_str_radix(s, 36).map_err(|_| CreepNameError::Malformed)
+}
+
+/// Two base 36 digits from a Fletcher checksum of `body`.
+fn checksum(body: &str) -> String {
+    let (mut sum, mut sum_of_sums) = (0u32, 0u32);
+    for byte in body.bytes() {
+        sum = (sum + u32::from(byte)) % 36;
+        sum_of_sums = (sum_of_sums + sum) % 36;
+    }
+    let mut check = String::with_capacity(2);
+    check.push(char::from(DIGITS[sum as usize]));
+    check.push(char::from(DIGITS[sum_of_sums as usize]));
+    check
+}
+
+#[cfg(test)]
+mod test {
+    use super::{decode_number, encode_number, CreepMeta, CreepNameError, CreepNameGenerator};
+    use crate::local::RoomName;
+
+    fn meta(role: u16, home: &str, born: u32, index: u32) -> CreepMeta {
+        CreepMeta {
+            role,
+            home: RoomName::new(home).unwrap(),
+            born,
+            index,
+        }
+    }
+
+    #[test]
+    fn round_trips() {
+        for &n in &[0, 1, 35, 36, 1295, 1296, u32::MAX] {
+            assert_eq!(decode_number(&encode_number(n)), Ok(n));
+        }
+
+        for m in &[
+            meta(0, "W0N0", 0, 0),
+            meta(3, "E12S45", 31_415_926, 2),
+            meta(u16::MAX, "W127S127", u32::MAX, u32::MAX),
+            meta(7, "sim", 100, 0),
+        ] {
+            let name = m.encode();
+            assert!(name.len() <= 40, "{}", name);
+            assert_eq!(CreepMeta::decode(&name), Ok(*m), "{}", name);
+        }
+    }
+
+    #[test]
+    fn rejects_other_names() {
+        let name = meta(3, "W1N1", 3_000_000, 0).encode();
+        assert_eq!(name, "3-W1N1-1satc-0-14");
+
+        for other in &[
+            "",
+            "Alice",
+            "harvester-1234-0",
+            "3-W1N1-1satc-0",
+            "3-w1n1-1satc-0-14",
+            "3-W1N1-1SA2O-0-14",
+            "3-W1N1-01satc-0-14",
+            "3-W1N1-1satc-0-0-14",
+            "1ekh-W1N1-1satc-0-14",
+        ] {
+            assert_eq!(
+                CreepMeta::decode(other),
+                Err(CreepNameError::Malformed),
+                "{}",
+                other
+            );
+        }
+        assert_eq!(
+            CreepMeta::decode("4-W1N1-1satc-0-14"),
+            Err(CreepNameError::ChecksumMismatch)
+        );
+    }
+
+    #[test]
+    fn generator_counts_up_within_a_tick() {
+        let home = RoomName::new("W1N1").unwrap();
+        let mut names = CreepNameGenerator::new();
+        let never_taken = |_: &str| false;
+
+        assert_eq!(
+            names.next_meta(100, 3, home, never_taken),
+            meta(3, "W1N1", 100, 0)
+        );
+        assert_eq!(
+            names.next_meta(100, 4, home, never_taken),
+            meta(4, "W1N1", 100, 1)
+        );
+        assert_eq!(
+            names.next_meta(101, 3, home, never_taken),
+            meta(3, "W1N1", 101, 0)
+        );
+
+        // left over from a previous global, with the counter reset
+        let existing = meta(3, "W1N1", 102, 0).encode();
+        let generated = names.next_meta(102, 3, home, |name| name == existing);
+        assert_eq!(generated, meta(3, "W1N1", 102, 1));
+        assert_eq!(CreepMeta::decode(&generated.encode()), Ok(generated));
+    }
+}
@@ -0,0 +1,323 @@
+//! Assigning roles to a room's labs and planning reactions for them.
+//!
+//! [`LabCluster::assign`] picks two input labs which the most other labs are
+//! within range 2 of, as `StructureLab.runReaction` requires, and makes those
+//! other labs outputs. [`reaction_chain`] lists the reactions needed to make
+//! a compound from base minerals, and [`LabCluster::run_plan`] picks which
+//! output labs can run one of those reactions this tick.
+//!
+//! ```
+//! use screeps::{
+//!     local::labs::{reaction_chain, LabCluster},
+//!     Position, ResourceType, RoomName,
+//! };
+//!
+//! let room = RoomName::new("W1N1").unwrap();
+//! let positions: Vec<Position> = [(20, 20), (21, 20), (19, 21), (20, 22), (22, 21)]
+//!     .iter()
+//!     .map(|&(x, y)| Position::new(x, y, room))
+//!     .collect();
+//! let cluster = LabCluster::assign(&positions).unwrap();
+//! assert_eq!(cluster.outputs.len(), 3);
+//!
+//! let chain = reaction_chain(ResourceType::UtriumAcid).unwrap();
+//! assert_eq!(chain.len(), 3);
+//! assert_eq!(chain[2].product, ResourceType::UtriumAcid);
+//! ```
+use std::{error, fmt};
+
+use crate::{
+    constants::{ResourceType, LAB_MINERAL_CAPACITY, LAB_REACTION_AMOUNT},
+    objects::{HasCooldown, HasPosition, HasStore, StructureLab},
+};
+
+use super::Position;
+
+/// Range within which output labs must be of both input labs.
+const REACTION_RANGE: u32 = 2;
+
+/// An error from [`LabCluster::assign`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LabClusterError {
+    /// Fewer than three labs were given.
+    TooFewLabs,
+    /// No two labs have another lab within range 2 of both of them.
+    NoOutputs,
+}
+
+impl fmt::Display for LabClusterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LabClusterError::TooFewLabs => {
+                write!(f, "expected at least 3 labs to form a cluster")
+            }
+            LabClusterError::NoOutputs => write!(
+                f,
+                "expected a pair of labs with another lab within range {} of both",
+                REACTION_RANGE
+            ),
+        }
+    }
+}
+
+impl error::Error for LabClusterError {}
+
+/// One reaction in a chain, from [`reaction_chain`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ReactionStep {
+    pub product: ResourceType,
+    pub inputs: [ResourceType; 2],
+    /// Cooldown of an output lab after running this reaction.
+    pub time: u32,
+}
+
+/// Lists the reactions needed to make `product`, each after the reactions
+/// making its inputs, and ending with `product` itself.
+///
+/// Base minerals aren't included, as they're mined rather than made.
+/// Returns `None` if `product` isn't made by a reaction.
+pub fn reaction_chain(product: ResourceType) -> Option<Vec<ReactionStep>> {
+    fn visit(product: ResourceType, chain: &mut Vec<ReactionStep>) {
+        let inputs = match product.reaction_components() {
+            Some(inputs) => inputs,
+            None => return,
+        };
+        if chain.iter().any(|step| step.product == product) {
+            return;
+        }
+        for &input in &inputs {
+            visit(input, chain);
+        }
+        chain.push(ReactionStep {
+            product,
+            inputs,
+            time: product.reaction_time().unwrap_or(0),
+        });
+    }
+
+    product.reaction_components()?;
+    let mut chain = Vec::new();
+    visit(product, &mut chain);
+    Some(chain)
+}
+
+/// The contents and cooldown of one lab, for [`LabCluster::run_plan`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LabState {
+    pub pos: Position,
+    /// The mineral in the lab, and how much of it.
+    pub mineral: Option<(ResourceType, u32)>,
+    pub cooldown: u32,
+}
+
+impl LabState {
+    pub fn scan(lab: &StructureLab) -> LabState {
+        LabState {
+            pos: lab.pos(),
+            mineral: lab
+                .mineral_type()
+                .map(|mineral| (mineral, lab.store_of(mineral))),
+            cooldown: lab.cooldown(),
+        }
+    }
+
+    fn amount_of(&self, resource: ResourceType) -> u32 {
+        match self.mineral {
+            Some((mineral, amount)) if mineral == resource => amount,
+            _ => 0,
+        }
+    }
+}
+
+/// A room's labs, split into the two labs holding reagents and the labs
+/// running reactions from them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LabCluster {
+    pub inputs: [Position; 2],
+    /// Labs within range 2 of both inputs.
+    pub outputs: Vec<Position>,
+    /// Labs too far from the inputs to run reactions, which can still be
+    /// used for boosting.
+    pub unused: Vec<Position>,
+}
+
+impl LabCluster {
+    /// Picks the pair of inputs with the most outputs, preferring earlier
+    /// labs in `labs` on a tie.
+    pub fn assign(labs: &[Position]) -> Result<LabCluster, LabClusterError> {
+        if labs.len() < 3 {
+            return Err(LabClusterError::TooFewLabs);
+        }
+
+        let outputs_of = |a: usize, b: usize| {
+            (0..labs.len()).filter(move |&i| {
+                i != a
+                    && i != b
+                    && labs[i].get_range_to(&labs[a]) <= REACTION_RANGE
+                    && labs[i].get_range_to(&labs[b]) <= REACTION_RANGE
+            })
+        };
+
+        let mut best: Option<(usize, usize, usize)> = None;
+        for a in 0..labs.len() {
+            for b in (a + 1)..labs.len() {
+                let count = outputs_of(a, b).count();
+                if count > best.map(|(_, _, count)| count).unwrap_or(0) {
+                    best = Some((a, b, count));
+                }
+            }
+        }
+        let (a, b, _) = best.ok_or(LabClusterError::NoOutputs)?;
+
+        let outputs: Vec<usize> = outputs_of(a, b).collect();
+        Ok(LabCluster {
+            inputs: [labs[a], labs[b]],
+            outputs: outputs.iter().map(|&i| labs[i]).collect(),
+            unused: (0..labs.len())
+                .filter(|&i| i != a && i != b && !outputs.contains(&i))
+                .map(|i| labs[i])
+                .collect(),
+        })
+    }
+
+    /// Picks the output labs which should run `step` this tick.
+    ///
+    /// Outputs need to be off cooldown, and empty or holding some of the
+    /// product with room for more. Each reaction uses `LAB_REACTION_AMOUNT`
+    /// of each input, from the first and second input labs respectively, so
+    /// fewer outputs are picked when the inputs are running low. Labs not
+    /// in `labs` are treated as empty and off cooldown.
+    pub fn run_plan(&self, step: &ReactionStep, labs: &[LabState]) -> Vec<Position> {
+        let state = |pos: Position| labs.iter().find(|lab| lab.pos == pos);
+        let input_amount = |i: usize| {
+            state(self.inputs[i])
+                .map(|lab| lab.amount_of(step.inputs[i]))
+                .unwrap_or(0)
+        };
+        let runs = input_amount(0).min(input_amount(1)) / LAB_REACTION_AMOUNT;
+
+        self.outputs
+            .iter()
+            .cloned()
+            .filter(|&pos| match state(pos) {
+                None => true,
+                Some(lab) => {
+                    lab.cooldown == 0
+                        && match lab.mineral {
+                            None => true,
+                            Some((mineral, amount)) => {
+                                mineral == step.product
+                                    && amount + LAB_REACTION_AMOUNT <= LAB_MINERAL_CAPACITY
+                            }
+                        }
+                }
+            })
+            .take(runs as usize)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{reaction_chain, LabCluster, LabClusterError, LabState};
+    use crate::{
+        constants::ResourceType,
+        local::{Position, RoomName},
+    };
+
+    fn pos(x: u32, y: u32) -> Position {
+        Position::new(x, y, RoomName::new("E5S5").unwrap())
+    }
+
+    #[test]
+    fn assigns_inputs_with_most_outputs() {
+        assert_eq!(
+            LabCluster::assign(&[pos(1, 1), pos(2, 2)]),
+            Err(LabClusterError::TooFewLabs)
+        );
+        assert_eq!(
+            LabCluster::assign(&[pos(1, 1), pos(10, 10), pos(20, 20)]),
+            Err(LabClusterError::NoOutputs)
+        );
+
+        // a standard 10-lab "flower" around two centre labs
+        let labs = [
+            pos(10, 10),
+            pos(12, 10),
+            pos(13, 10),
+            pos(11, 11),
+            pos(12, 11),
+            pos(10, 12),
+            pos(11, 12),
+            pos(13, 12),
+            pos(11, 13),
+            pos(12, 13),
+        ];
+        let cluster = LabCluster::assign(&labs).unwrap();
+        assert_eq!(cluster.inputs, [pos(11, 11), pos(12, 11)]);
+        assert_eq!(cluster.outputs.len(), 8);
+        assert!(cluster.unused.is_empty());
+
+        let labs = [pos(10, 10), pos(11, 10), pos(12, 10), pos(30, 30)];
+        let cluster = LabCluster::assign(&labs).unwrap();
+        assert_eq!(cluster.unused, [pos(30, 30)]);
+    }
+
+    #[test]
+    fn chains_in_order() {
+        assert_eq!(reaction_chain(ResourceType::Hydrogen), None);
+
+        let chain = reaction_chain(ResourceType::CatalyzedGhodiumAcid).unwrap();
+        let products: Vec<_> = chain.iter().map(|step| step.product).collect();
+        assert_eq!(
+            products,
+            [
+                ResourceType::UtriumLemergite,
+                ResourceType::ZynthiumKeanite,
+                ResourceType::Ghodium,
+                ResourceType::GhodiumHydride,
+                ResourceType::Hydroxide,
+                ResourceType::GhodiumAcid,
+                ResourceType::CatalyzedGhodiumAcid,
+            ]
+        );
+        assert!(chain.iter().all(|step| step.time > 0));
+    }
+
+    #[test]
+    fn plans_runs_within_inputs() {
+        let cluster = LabCluster {
+            inputs: [pos(1, 1), pos(2, 1)],
+            outputs: vec![pos(1, 2), pos(2, 2), pos(3, 2)],
+            unused: vec![],
+        };
+        let step = reaction_chain(ResourceType::Hydroxide).unwrap()[0];
+        let lab = |pos, mineral, cooldown| LabState {
+            pos,
+            mineral,
+            cooldown,
+        };
+
+        let labs = [
+            lab(pos(1, 1), Some((ResourceType::Oxygen, 100)), 0),
+            lab(pos(2, 1), Some((ResourceType::Hydrogen, 10)), 0),
+            lab(pos(1, 2), Some((ResourceType::Hydroxide, 50)), 0),
+            lab(pos(2, 2), Some((ResourceType::Hydroxide, 50)), 5),
+        ];
+        assert_eq!(cluster.run_plan(&step, &labs), [pos(1, 2), pos(3, 2)]);
+
+        let wrong_output = [
+            lab(pos(1, 1), Some((ResourceType::Oxygen, 100)), 0),
+            lab(pos(2, 1), Some((ResourceType::Hydrogen, 100)), 0),
+            lab(pos(1, 2), Some((ResourceType::Keanium, 50)), 0),
+            lab(pos(2, 2), Some((ResourceType::Hydroxide, 3000)), 0),
+        ];
+        assert_eq!(cluster.run_plan(&step, &wrong_output), [pos(3, 2)]);
+
+        let swapped = [
+            lab(pos(1, 1), Some((ResourceType::Hydrogen, 100)), 0),
+            lab(pos(2, 1), Some((ResourceType::Oxygen, 100)), 0),
+        ];
+        assert!(cluster.run_plan(&step, &swapped).is_empty());
+    }
+}
@@ -0,0 +1,271 @@
+//! Classifying a room's links and planning energy transfers between them.
+//!
+//! Links near sources collect energy, and links near the controller, spawns
+//! and storage use it. [`LinkNetwork`] gives each link a [`LinkRole`] by
+//! what it's near, and [`LinkNetwork::plan`] picks this tick's
+//! `StructureLink.transferEnergy` calls.
+//!
+//! ```no_run
+//! use screeps::{game, local::links::LinkNetwork, RoomName};
+//!
+//! let room = game::rooms::get(RoomName::new("W1N1").unwrap()).unwrap();
+//! for transfer in LinkNetwork::scan(&room).plan() {
+//!     transfer.execute();
+//! }
+//! ```
+use crate::{
+    constants::{find, ReturnCode, LINK_CAPACITY, LINK_LOSS_RATIO},
+    objects::{
+        HasCooldown, HasId, HasPosition, HasStore, OwnedStructureProperties, Room, Structure,
+        StructureLink,
+    },
+};
+
+use super::{ObjectId, Position};
+
+/// What a link is used for, from what it's near.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LinkRole {
+    /// Within range 2 of a source; sends energy harvested into it.
+    Source,
+    /// Within range 3 of the controller; receives energy for upgraders.
+    Controller,
+    /// Within range 2 of the storage; receives surplus energy from source
+    /// links, and sends energy to controller and hub links.
+    Storage,
+    /// Within range 2 of a spawn; receives energy for refilling spawns and
+    /// extensions.
+    Hub,
+    /// Not near any of those.
+    Other,
+}
+
+impl LinkRole {
+    /// Classifies a link at `pos`, checking roles in the order they're
+    /// declared in.
+    pub fn classify(
+        pos: Position,
+        sources: &[Position],
+        controller: Option<Position>,
+        storage: Option<Position>,
+        spawns: &[Position],
+    ) -> LinkRole {
+        let near = |target: &Position, range| pos.get_range_to(target) <= range;
+        if sources.iter().any(|source| near(source, 2)) {
+            LinkRole::Source
+        } else if controller.map(|c| near(&c, 3)).unwrap_or(false) {
+            LinkRole::Controller
+        } else if storage.map(|s| near(&s, 2)).unwrap_or(false) {
+            LinkRole::Storage
+        } else if spawns.iter().any(|spawn| near(spawn, 2)) {
+            LinkRole::Hub
+        } else {
+            LinkRole::Other
+        }
+    }
+}
+
+/// One link's role, energy and cooldown.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LinkState {
+    pub id: ObjectId<StructureLink>,
+    pub pos: Position,
+    pub role: LinkRole,
+    pub energy: u32,
+    pub cooldown: u32,
+}
+
+/// A planned `StructureLink.transferEnergy`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LinkTransfer {
+    pub from: ObjectId<StructureLink>,
+    pub to: ObjectId<StructureLink>,
+    pub amount: u32,
+    /// Energy which arrives, after `LINK_LOSS_RATIO` is lost.
+    pub received: u32,
+}
+
+impl LinkTransfer {
+    /// Transfers the energy, or returns `ReturnCode::NotFound` if either
+    /// link can't be seen.
+    pub fn execute(&self) -> ReturnCode {
+        match (self.from.resolve(), self.to.resolve()) {
+            (Some(from), Some(to)) => from.transfer_energy(&to, Some(self.amount)),
+            _ => ReturnCode::NotFound,
+        }
+    }
+}
+
+/// Energy which arrives when a link sends `amount`.
+fn received(amount: u32) -> u32 {
+    amount - (f64::from(amount) * f64::from(LINK_LOSS_RATIO)).ceil() as u32
+}
+
+/// The links in one room.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinkNetwork {
+    pub links: Vec<LinkState>,
+    min_transfer: u32,
+}
+
+impl LinkNetwork {
+    pub fn new(links: Vec<LinkState>) -> Self {
+        LinkNetwork {
+            links,
+            min_transfer: 100,
+        }
+    }
+
+    /// Classifies each of your links in a visible room.
+    pub fn scan(room: &Room) -> Self {
+        let sources: Vec<Position> = room.find(find::SOURCES).iter().map(|s| s.pos()).collect();
+        let spawns: Vec<Position> = room.find(find::MY_SPAWNS).iter().map(|s| s.pos()).collect();
+        let controller = room.controller().map(|c| c.pos());
+        let storage = room.storage().map(|s| s.pos());
+
+        let links = room
+            .find(find::STRUCTURES)
+            .into_iter()
+            .filter_map(|structure| match structure {
+                Structure::Link(link) if link.my() => Some(link),
+                _ => None,
+            })
+            .map(|link| LinkState {
+                id: link.id(),
+                pos: link.pos(),
+                role: LinkRole::classify(link.pos(), &sources, controller, storage, &spawns),
+                energy: link.energy(),
+                cooldown: link.cooldown(),
+            })
+            .collect();
+        LinkNetwork::new(links)
+    }
+
+    /// Sets the smallest amount worth sending - default `100`.
+    pub fn min_transfer(mut self, amount: u32) -> Self {
+        self.min_transfer = amount;
+        self
+    }
+
+    /// Plans this tick's transfers.
+    ///
+    /// Source links send to controller links first, then hub links, then
+    /// storage links. Storage links send to controller and hub links. Each
+    /// link off cooldown sends at most once, and never more than its
+    /// target has room for, counting other transfers to it this tick.
+    pub fn plan(&self) -> Vec<LinkTransfer> {
+        let mut space: Vec<u32> = self
+            .links
+            .iter()
+            .map(|link| LINK_CAPACITY.saturating_sub(link.energy))
+            .collect();
+        let mut transfers = Vec::new();
+
+        let senders = [
+            (
+                LinkRole::Source,
+                &[LinkRole::Controller, LinkRole::Hub, LinkRole::Storage][..],
+            ),
+            (
+                LinkRole::Storage,
+                &[LinkRole::Controller, LinkRole::Hub][..],
+            ),
+        ];
+        for &(sender_role, receiver_roles) in &senders {
+            for (i, sender) in self.links.iter().enumerate() {
+                if sender.role != sender_role
+                    || sender.cooldown > 0
+                    || sender.energy < self.min_transfer
+                {
+                    continue;
+                }
+                let receiver = receiver_roles.iter().find_map(|&role| {
+                    (0..self.links.len())
+                        .filter(|&j| self.links[j].role == role && space[j] >= self.min_transfer)
+                        .max_by_key(|&j| space[j])
+                });
+                if let Some(j) = receiver {
+                    let amount = sender.energy.min(space[j]);
+                    let received = received(amount);
+                    space[j] -= received;
+                    space[i] += amount;
+                    transfers.push(LinkTransfer {
+                        from: sender.id,
+                        to: self.links[j].id,
+                        amount,
+                        received,
+                    });
+                }
+            }
+        }
+
+        transfers
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{received, LinkNetwork, LinkRole, LinkState};
+    use crate::local::{ObjectId, Position, RoomName};
+
+    fn pos(x: u32, y: u32) -> Position {
+        Position::new(x, y, RoomName::new("W5N5").unwrap())
+    }
+
+    fn link(n: u32, role: LinkRole, energy: u32, cooldown: u32) -> LinkState {
+        LinkState {
+            id: ObjectId::from_packed([0, 0, n]),
+            pos: pos(n, n),
+            role,
+            energy,
+            cooldown,
+        }
+    }
+
+    #[test]
+    fn classifies_by_proximity() {
+        let sources = [pos(10, 10)];
+        let controller = Some(pos(40, 40));
+        let storage = Some(pos(25, 25));
+        let spawns = [pos(25, 30)];
+        let classify = |p| LinkRole::classify(p, &sources, controller, storage, &spawns);
+
+        assert_eq!(classify(pos(12, 11)), LinkRole::Source);
+        assert_eq!(classify(pos(37, 40)), LinkRole::Controller);
+        assert_eq!(classify(pos(26, 27)), LinkRole::Storage);
+        assert_eq!(classify(pos(25, 32)), LinkRole::Hub);
+        assert_eq!(classify(pos(2, 2)), LinkRole::Other);
+    }
+
+    #[test]
+    fn plans_by_priority() {
+        assert_eq!(received(800), 776);
+
+        let links = vec![
+            link(1, LinkRole::Source, 800, 0),
+            link(2, LinkRole::Source, 400, 0),
+            link(3, LinkRole::Source, 800, 2),
+            link(4, LinkRole::Controller, 300, 0),
+            link(5, LinkRole::Storage, 0, 0),
+        ];
+        let transfers = LinkNetwork::new(links).plan();
+        assert_eq!(transfers.len(), 2);
+        assert_eq!(transfers[0].to, ObjectId::from_packed([0, 0, 4]));
+        assert_eq!(transfers[0].amount, 500);
+        assert_eq!(transfers[0].received, 485);
+        // the controller link's 15 remaining space is too little
+        assert_eq!(transfers[1].from, ObjectId::from_packed([0, 0, 2]));
+        assert_eq!(transfers[1].to, ObjectId::from_packed([0, 0, 5]));
+        assert_eq!(transfers[1].amount, 400);
+
+        let links = vec![
+            link(1, LinkRole::Storage, 800, 0),
+            link(2, LinkRole::Hub, 100, 0),
+            link(3, LinkRole::Other, 0, 0),
+        ];
+        let transfers = LinkNetwork::new(links).min_transfer(50).plan();
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].to, ObjectId::from_packed([0, 0, 2]));
+        assert_eq!(transfers[0].amount, 700);
+    }
+}
@@ -16,6 +16,8 @@ use crate::{
     ConversionError,
 };
 
+use super::RoomName;
+
 mod errors;
 mod raw;
 
@@ -39,6 +41,8 @@ pub use raw::*;
 ///
 /// Use `into` to convert between `ObjectId<T>` and [`RawObjectId`], and
 /// [`ObjectId::into_type`] to change the type this `ObjectId` points to freely.
+/// [`ObjectId::try_into_type`] changes the type after checking it against the
+/// object in the game.
 ///
 /// # Ordering
 ///
@@ -144,6 +148,38 @@ impl<T> ObjectId<T> {
         RawObjectId::from(self).into()
     }
 
+    /// Changes the type this [`ObjectId`] points to, checking that the object
+    /// it points to is actually a `U`.
+    ///
+    /// This is most useful for ids stored generically, like an
+    /// `ObjectId<Structure>` which is known to be a spawn. Since the check
+    /// needs the object itself, this looks it up with `Game.getObjectById`.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the object exists, but isn't a `U`.
+    ///
+    /// Will return `Ok(None)` if the object no longer exists, or is in a room
+    /// we don't have vision for, as the type can't be checked.
+    pub fn try_into_type<U>(self) -> Result<Option<ObjectId<U>>, ConversionError>
+    where
+        U: HasId + SizedRoomObject,
+    {
+        Ok(self.try_resolve_as::<U>()?.map(|_| self.into_type()))
+    }
+
+    /// Resolves this object ID into an object of a different type, checking
+    /// that the object is of that type.
+    ///
+    /// This is a shortcut for `id.into_type::<U>().try_resolve()`. See
+    /// [`ObjectId::try_resolve`] for error conditions.
+    pub fn try_resolve_as<U>(self) -> Result<Option<U>, ConversionError>
+    where
+        U: HasId + SizedRoomObject,
+    {
+        self.into_type::<U>().try_resolve()
+    }
+
     /// Creates an object ID from its packed representation.
     ///
     /// The input to this function is the bytes representing the up-to-24 hex
@@ -287,6 +323,38 @@ impl<T> ObjectId<T> {
         crate::game::get_object_typed(self)
     }
 
+    /// Resolves this object ID into an object which was last seen in
+    /// `room_name`, telling apart objects which are gone from objects which
+    /// can't be seen.
+    ///
+    /// Useful for deciding whether to drop a cached id: an object in a room
+    /// without vision may still exist, while one missing from a visible room
+    /// won't come back.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ResolveError::Gone`] if `room_name` is visible but the object
+    /// doesn't exist, [`ResolveError::NotVisible`] if it doesn't exist and
+    /// `room_name` isn't visible, and [`ResolveError::WrongType`] if the
+    /// object isn't a `T`.
+    pub fn try_resolve_in(self, room_name: RoomName) -> Result<T, ResolveError>
+    where
+        T: HasId + SizedRoomObject,
+    {
+        match self.try_resolve() {
+            Ok(Some(object)) => Ok(object),
+            Ok(None) => {
+                let visible: bool = js_unwrap!(Game.rooms[@{room_name}] !== undefined);
+                if visible {
+                    Err(ResolveError::Gone)
+                } else {
+                    Err(ResolveError::NotVisible)
+                }
+            }
+            Err(e) => Err(ResolveError::WrongType(e)),
+        }
+    }
+
     /// Resolves this ID into an object, panicking on type mismatch.
     ///
     /// This is a shortcut for [`id.try_resolve().expect(...)`][1]
@@ -1,5 +1,7 @@
 use std::{error::Error, fmt, num::ParseIntError};
 
+use crate::ConversionError;
+
 #[derive(Debug, Clone)]
 pub enum RawObjectIdParseError {
     Parse(ParseIntError),
@@ -42,3 +44,37 @@ impl RawObjectIdParseError {
         RawObjectIdParseError::LargeValue(val)
     }
 }
+
+/// Why an object id couldn't be resolved by [`ObjectId::try_resolve_in`].
+///
+/// [`ObjectId::try_resolve_in`]: crate::local::ObjectId::try_resolve_in
+#[derive(Debug)]
+pub enum ResolveError {
+    /// The object's room is visible, but the object isn't there: it's been
+    /// destroyed, has died, or has left the room.
+    Gone,
+    /// The object's room isn't visible, so whether the object still exists
+    /// is unknown.
+    NotVisible,
+    /// The object exists, but isn't of the type the id points to.
+    WrongType(ConversionError),
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResolveError::Gone => write!(f, "object no longer exists"),
+            ResolveError::NotVisible => write!(f, "object's room is not visible"),
+            ResolveError::WrongType(e) => write!(f, "object is of the wrong type: {}", e),
+        }
+    }
+}
+
+impl Error for ResolveError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ResolveError::WrongType(e) => Some(e),
+            ResolveError::Gone | ResolveError::NotVisible => None,
+        }
+    }
+}
@@ -0,0 +1,1261 @@
+//! Finding paths in Rust, without calling `PathFinder.search`.
+//!
+//! [`LocalPathFinder`] runs an A* search over cached terrain and
+//! [`LocalCostMatrix`]es, with the same costs and options as the game's
+//! `PathFinder`. Nothing crosses into JavaScript, so planning many paths at
+//! once, such as for roads in a base layout, costs only the time spent in
+//! wasm.
+//!
+//! [`LocalSearchMode::JumpPoint`] switches to jump point search, which
+//! expands far fewer tiles over open terrain such as highways, and
+//! [`LocalSearchMode::Bidirectional`] to bidirectional A*, which does better
+//! when walls box in one end of a long path.
+//!
+//! ```
+//! use screeps::{
+//!     local::{path_search::LocalPathFinder, LocalRoomTerrain, TerrainCache},
+//!     pathfinder::SearchGoal,
+//!     Position, RoomName,
+//! };
+//!
+//! let room = RoomName::new("W1N1").unwrap();
+//! let mut terrain = TerrainCache::new();
+//! terrain.insert(room, LocalRoomTerrain::new_from_bits(Box::new([0; 2500])));
+//!
+//! let results = LocalPathFinder::new(&terrain).search(
+//!     Position::new(10, 10, room),
+//!     SearchGoal::new(Position::new(20, 15, room), 1),
+//! );
+//! assert!(!results.incomplete);
+//! assert_eq!(results.path.len(), 9);
+//! ```
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+};
+
+use crate::{
+    constants::Terrain,
+    pathfinder::{LocalCostMatrix, SearchGoal},
+};
+
+use super::{LocalRoomTerrain, Position, RoomName, TerrainCache, HALF_WORLD_SIZE};
+
+/// What a [`LocalPathFinder`]'s room callback returns for each room.
+#[derive(Clone, Debug)]
+pub enum LocalRoomCost {
+    /// Use this matrix's costs where they're not `0`, and the terrain's
+    /// costs elsewhere. `255` is impassable.
+    CostMatrix(LocalCostMatrix),
+    /// Never enter the room.
+    Impassable,
+    /// Use only the terrain's costs.
+    Default,
+}
+
+/// How a [`LocalPathFinder`] searches.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LocalSearchMode {
+    /// Plain A*, expanding every neighbor of each tile searched.
+    AStar,
+    /// A* with jump point search: across runs of tiles which all cost the
+    /// same as their neighbors, the search jumps straight to the next tile
+    /// where the path might turn, rather than expanding every tile on the
+    /// way.
+    ///
+    /// Paths cost the same or very nearly the same as with
+    /// [`LocalSearchMode::AStar`], and over open terrain far fewer tiles
+    /// are expanded, so the same `max_ops` reaches much further.
+    JumpPoint,
+    /// Bidirectional A*: searches forwards from the origin and backwards
+    /// from the goals at once, finishing once the two searches meet and no
+    /// cheaper path is left.
+    ///
+    /// Where walls box in one end of a long path, A* expands every tile in
+    /// front of them before finding the way around, and this expands far
+    /// fewer tiles. Over open terrain, A* already heads straight for the
+    /// goal, and this may expand up to twice as many.
+    ///
+    /// Flee searches have nothing to search backwards from, so they run as
+    /// [`LocalSearchMode::AStar`].
+    Bidirectional,
+}
+
+/// The result of a [`LocalPathFinder`] search.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LocalSearchResults {
+    /// Positions along the path, not including the origin.
+    pub path: Vec<Position>,
+    /// The index of the goal the path reached, or `None` for incomplete and
+    /// flee searches.
+    pub reached_goal: Option<usize>,
+    /// Number of tiles the search expanded. With
+    /// [`LocalSearchMode::JumpPoint`], only the tiles jumped to count, and
+    /// with [`LocalSearchMode::Bidirectional`], tiles expanded in both
+    /// directions count.
+    pub ops: u32,
+    /// Total cost of the path found.
+    pub cost: u32,
+    /// Whether the search stopped before reaching a goal, because it ran out
+    /// of operations or no goal could be reached. The path then leads to the
+    /// closest position found instead.
+    pub incomplete: bool,
+}
+
+/// World coordinates of a tile, as from [`Position::world_coords`].
+type Coords = (i32, i32);
+
+/// A tile waiting to be searched, ordered so that `BinaryHeap` pops the
+/// lowest estimated total cost first.
+struct Candidate {
+    estimate: f64,
+    coords: Coords,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.estimate == other.estimate
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // estimates are never NaN, as costs and the heuristic weight are
+        // finite
+        other
+            .estimate
+            .partial_cmp(&self.estimate)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+/// One direction of a search: the cost of each tile reached and the tile it
+/// was reached from, and the tiles left to search.
+#[derive(Default)]
+struct Frontier {
+    reached: HashMap<Coords, (u32, Option<Coords>)>,
+    open: BinaryHeap<Candidate>,
+}
+
+impl Frontier {
+    /// Records reaching `coords` for `cost` from `parent`, returning whether
+    /// that's cheaper than any way of reaching it found before.
+    fn insert(
+        &mut self,
+        coords: Coords,
+        cost: u32,
+        parent: Option<Coords>,
+        heuristic: f64,
+    ) -> bool {
+        if let Some(&(known, _)) = self.reached.get(&coords) {
+            if known <= cost {
+                return false;
+            }
+        }
+        self.reached.insert(coords, (cost, parent));
+        self.open.push(Candidate {
+            estimate: f64::from(cost) + heuristic,
+            coords,
+        });
+        true
+    }
+
+    /// Drops tiles already searched with a lower cost from the top of the
+    /// open set, then returns the estimated total cost of the next tile.
+    fn top<H>(&mut self, heuristic: H) -> Option<f64>
+    where
+        H: Fn(Coords) -> f64,
+    {
+        loop {
+            let (estimate, coords) = {
+                let next = self.open.peek()?;
+                (next.estimate, next.coords)
+            };
+            if estimate > f64::from(self.reached[&coords].0) + heuristic(coords) {
+                self.open.pop();
+            } else {
+                return Some(estimate);
+            }
+        }
+    }
+
+    /// Positions along the way from where this search started to `coords`,
+    /// not including the start.
+    fn path_to(&self, mut coords: Coords) -> Vec<Position> {
+        let mut path = Vec::new();
+        while let Some(previous) = self.reached[&coords].1 {
+            path.push(Position::from_world_coords(coords.0, coords.1));
+            coords = previous;
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// The goals of a search, and whether it's fleeing from them.
+struct Goals {
+    goals: Vec<SearchGoal>,
+    flee: bool,
+}
+
+impl Goals {
+    /// How many more tiles the path needs to cover from `coords`, and the
+    /// goal it's closest to reaching.
+    fn distance(&self, coords: Coords) -> Option<(u32, usize)> {
+        let distances = self.goals.iter().enumerate().map(|(i, goal)| {
+            let (gx, gy) = goal.pos.world_coords();
+            let range = (coords.0 - gx).abs().max((coords.1 - gy).abs()) as u32;
+            if self.flee {
+                (goal.range.saturating_sub(range), i)
+            } else {
+                (range.saturating_sub(goal.range), i)
+            }
+        });
+        if self.flee {
+            distances.max()
+        } else {
+            distances.min()
+        }
+    }
+
+    fn reached(&self, coords: Coords) -> bool {
+        self.distance(coords).map(|(d, _)| d == 0).unwrap_or(true)
+    }
+}
+
+/// The eight directions a creep can move in, as world coordinate offsets.
+const DIRECTIONS: [Coords; 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+fn in_world(coords: Coords) -> bool {
+    let world_range = -HALF_WORLD_SIZE * 50..HALF_WORLD_SIZE * 50;
+    world_range.contains(&coords.0) && world_range.contains(&coords.1)
+}
+
+fn step(coords: Coords, direction: Coords) -> Coords {
+    (coords.0 + direction.0, coords.1 + direction.1)
+}
+
+/// A room's terrain and cost matrix, once loaded.
+struct RoomCosts<'a> {
+    terrain: &'a LocalRoomTerrain,
+    matrix: Option<LocalCostMatrix>,
+    /// The room's costs laid out for jump point search, filled in the first
+    /// time they're needed.
+    grid: Option<JumpGrid>,
+}
+
+impl RoomCosts<'_> {
+    fn new(terrain: &LocalRoomTerrain, matrix: Option<LocalCostMatrix>) -> RoomCosts<'_> {
+        RoomCosts {
+            terrain,
+            matrix,
+            grid: None,
+        }
+    }
+
+    /// The cost of moving onto a tile, or `None` if it's impassable.
+    fn cost(&self, x: u8, y: u8, plain_cost: u8, swamp_cost: u8) -> Option<u8> {
+        let matrix_cost = self.matrix.as_ref().map(|m| m.get(x, y)).unwrap_or(0);
+        match matrix_cost {
+            255 => None,
+            0 => match self.terrain.get(x, y) {
+                Terrain::Wall => None,
+                Terrain::Swamp => Some(swamp_cost),
+                Terrain::Plain => Some(plain_cost),
+            },
+            cost => Some(cost),
+        }
+    }
+
+    /// Lays out the room's costs for jump point search, given where its
+    /// `(0, 0)` tile is in world coordinates.
+    fn grid(&mut self, plain_cost: u8, swamp_cost: u8, origin: Coords, goals: &Goals) -> &JumpGrid {
+        if self.grid.is_none() {
+            let mut costs = vec![None; 2500];
+            let mut goal = vec![false; 2500];
+            for x in 0..50 {
+                for y in 0..50 {
+                    let index = x as usize * 50 + y as usize;
+                    costs[index] = self.cost(x, y, plain_cost, swamp_cost);
+                    goal[index] = goals.reached((origin.0 + i32::from(x), origin.1 + i32::from(y)));
+                }
+            }
+            let mut skip = vec![false; 2500];
+            for x in 1..49 {
+                for y in 1..49 {
+                    let index = x * 50 + y;
+                    skip[index] = costs[index].is_some()
+                        && !goal[index]
+                        && DIRECTIONS.iter().all(|&(dx, dy)| {
+                            let neighbor = (x as i32 + dx) as usize * 50 + (y as i32 + dy) as usize;
+                            costs[neighbor] == costs[index]
+                        });
+                }
+            }
+            let mut ahead = [
+                vec![false; 2500],
+                vec![false; 2500],
+                vec![false; 2500],
+                vec![false; 2500],
+            ];
+            for (&(dx, dy), ahead) in STRAIGHT_DIRECTIONS.iter().zip(ahead.iter_mut()) {
+                // fill in tiles furthest along the direction first, so the
+                // next tile is always known
+                for along in 0..50 {
+                    let along = if dx + dy > 0 { 49 - along } else { along };
+                    for across in 0..50 {
+                        let (x, y) = if dx != 0 {
+                            (along, across)
+                        } else {
+                            (across, along)
+                        };
+                        let index = x as usize * 50 + y as usize;
+                        let edge = x == 0 || x == 49 || y == 0 || y == 49;
+                        ahead[index] = if costs[index].is_none() || edge && !goal[index] {
+                            false
+                        } else if skip[index] {
+                            ahead[(x + dx) as usize * 50 + (y + dy) as usize]
+                        } else {
+                            true
+                        };
+                    }
+                }
+            }
+            self.grid = Some(JumpGrid { costs, skip, ahead });
+        }
+        self.grid
+            .as_ref()
+            .expect("expected grid to have just been filled in")
+    }
+}
+
+/// The four straight directions, in the order [`JumpGrid::ahead`] uses.
+const STRAIGHT_DIRECTIONS: [Coords; 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// A room's costs laid out for jump point search, with each field indexed
+/// like [`LocalCostMatrix`].
+struct JumpGrid {
+    costs: Vec<Option<u8>>,
+    /// Whether each tile can be jumped over: it's passable, costs the same
+    /// as all of its neighbors and isn't a goal. Elsewhere the cheapest path
+    /// may turn or end. Tiles on the room's edge are never skipped.
+    skip: Vec<bool>,
+    /// For each of [`STRAIGHT_DIRECTIONS`], whether moving straight that way
+    /// from each tile, starting with that tile, reaches one which can't be
+    /// skipped before reaching a room edge or an impassable tile.
+    ahead: [Vec<bool>; 4],
+}
+
+impl JumpGrid {
+    /// Moves from the tile at `(x, y)` in `direction`, starting with that
+    /// tile, until reaching a tile where the path might turn. Returns that
+    /// tile and the cost of moving to it, or `None` if an impassable tile is
+    /// reached first.
+    fn jump(
+        &self,
+        (mut x, mut y): Coords,
+        direction: Coords,
+        max_cost: u32,
+    ) -> Option<(Coords, u32)> {
+        let (dx, dy) = direction;
+        let mut cost = 0u32;
+        loop {
+            let index = x as usize * 50 + y as usize;
+            cost = cost.saturating_add(u32::from(self.costs[index]?));
+            if !self.skip[index] || cost > max_cost {
+                return Some(((x, y), cost));
+            }
+            // a diagonal jump stops where a straight jump from it would find
+            // somewhere to turn
+            if dx != 0 && dy != 0 {
+                let horizontal = if dx > 0 { 1 } else { 3 };
+                let vertical = if dy > 0 { 2 } else { 0 };
+                if self.ahead[horizontal][(x + dx) as usize * 50 + y as usize]
+                    || self.ahead[vertical][x as usize * 50 + (y + dy) as usize]
+                {
+                    return Some(((x, y), cost));
+                }
+            }
+            x += dx;
+            y += dy;
+        }
+    }
+}
+
+/// Searches for paths over cached terrain, like `PathFinder.search`.
+///
+/// Rooms whose terrain isn't in the [`TerrainCache`] are never entered, so
+/// fetch the terrain of any rooms the path may cross beforehand.
+pub struct LocalPathFinder<'a, F>
+where
+    F: FnMut(RoomName) -> LocalRoomCost,
+{
+    terrain: &'a TerrainCache,
+    room_callback: F,
+    plain_cost: u8,
+    swamp_cost: u8,
+    flee: bool,
+    max_ops: u32,
+    max_rooms: u32,
+    max_cost: u32,
+    heuristic_weight: f64,
+    mode: LocalSearchMode,
+}
+
+impl<'a> LocalPathFinder<'a, fn(RoomName) -> LocalRoomCost> {
+    pub fn new(terrain: &'a TerrainCache) -> Self {
+        fn default_cost(_: RoomName) -> LocalRoomCost {
+            LocalRoomCost::Default
+        }
+
+        LocalPathFinder {
+            terrain,
+            room_callback: default_cost,
+            plain_cost: 1,
+            swamp_cost: 5,
+            flee: false,
+            max_ops: 2000,
+            max_rooms: 16,
+            max_cost: u32::MAX,
+            heuristic_weight: 1.2,
+            mode: LocalSearchMode::AStar,
+        }
+    }
+}
+
+impl<'a, F> LocalPathFinder<'a, F>
+where
+    F: FnMut(RoomName) -> LocalRoomCost,
+{
+    /// Sets room callback - default `|_| LocalRoomCost::Default`.
+    ///
+    /// The callback is called at most once per room in each search.
+    pub fn room_callback<F2>(self, room_callback: F2) -> LocalPathFinder<'a, F2>
+    where
+        F2: FnMut(RoomName) -> LocalRoomCost,
+    {
+        LocalPathFinder {
+            terrain: self.terrain,
+            room_callback,
+            plain_cost: self.plain_cost,
+            swamp_cost: self.swamp_cost,
+            flee: self.flee,
+            max_ops: self.max_ops,
+            max_rooms: self.max_rooms,
+            max_cost: self.max_cost,
+            heuristic_weight: self.heuristic_weight,
+            mode: self.mode,
+        }
+    }
+
+    /// Sets plain cost - default `1`.
+    pub fn plain_cost(mut self, cost: u8) -> Self {
+        self.plain_cost = cost;
+        self
+    }
+
+    /// Sets swamp cost - default `5`.
+    pub fn swamp_cost(mut self, cost: u8) -> Self {
+        self.swamp_cost = cost;
+        self
+    }
+
+    /// Sets whether this is a flee search - default `false`.
+    ///
+    /// Flee searches find a path leading at least each goal's range away
+    /// from every goal, rather than into range of one of them.
+    pub fn flee(mut self, flee: bool) -> Self {
+        self.flee = flee;
+        self
+    }
+
+    /// Sets maximum tiles expanded - default `2000`.
+    pub fn max_ops(mut self, ops: u32) -> Self {
+        self.max_ops = ops;
+        self
+    }
+
+    /// Sets maximum rooms entered - default `16`.
+    pub fn max_rooms(mut self, rooms: u32) -> Self {
+        self.max_rooms = rooms;
+        self
+    }
+
+    /// Sets maximum path cost - default no limit.
+    pub fn max_cost(mut self, cost: u32) -> Self {
+        self.max_cost = cost;
+        self
+    }
+
+    /// Sets the heuristic weight - default `1.2`.
+    ///
+    /// Higher weights find paths faster, but they may be more costly.
+    pub fn heuristic_weight(mut self, weight: f64) -> Self {
+        self.heuristic_weight = weight;
+        self
+    }
+
+    /// Sets how the search runs - default [`LocalSearchMode::AStar`].
+    pub fn mode(mut self, mode: LocalSearchMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Finds a path from `origin` to within range of `goal`.
+    pub fn search(self, origin: Position, goal: SearchGoal) -> LocalSearchResults {
+        self.search_many(origin, Some(goal))
+    }
+
+    /// Finds a path from `origin` to within range of the closest of `goals`.
+    pub fn search_many<G>(mut self, origin: Position, goals: G) -> LocalSearchResults
+    where
+        G: IntoIterator<Item = SearchGoal>,
+    {
+        let goals = Goals {
+            goals: goals.into_iter().collect(),
+            flee: self.flee,
+        };
+        if self.mode == LocalSearchMode::Bidirectional && !self.flee {
+            return self.search_bidirectional(origin.world_coords(), &goals);
+        }
+
+        let weight = self.heuristic_weight;
+        let heuristic = move |distance: u32| f64::from(distance) * weight;
+
+        let mut rooms: HashMap<RoomName, Option<RoomCosts<'a>>> = HashMap::new();
+        let mut reached: HashMap<Coords, (u32, Option<Coords>)> = HashMap::new();
+        let mut open = BinaryHeap::new();
+        let mut ops = 0;
+
+        let start = origin.world_coords();
+        reached.insert(start, (0, None));
+        open.push(Candidate {
+            estimate: 0.0,
+            coords: start,
+        });
+        // the closest tile to a goal found so far, and its distance
+        let mut best = (start, u32::MAX);
+        let mut reached_goal = None;
+        let mut complete = false;
+
+        while let Some(Candidate { estimate, coords }) = open.pop() {
+            let (cost, parent) = reached[&coords];
+            let (distance, goal) = match goals.distance(coords) {
+                Some(found) => found,
+                None => break,
+            };
+            if estimate > f64::from(cost) + heuristic(distance) {
+                // already searched with a lower cost
+                continue;
+            }
+            if distance < best.1 {
+                best = (coords, distance);
+            }
+            if distance == 0 {
+                complete = true;
+                if !self.flee {
+                    reached_goal = Some(goal);
+                }
+                break;
+            }
+            if ops >= self.max_ops {
+                break;
+            }
+            ops += 1;
+
+            for (next, step_cost) in self.successors(&mut rooms, &goals, coords, parent) {
+                let next_cost = cost.saturating_add(step_cost);
+                if next_cost > self.max_cost {
+                    continue;
+                }
+                let better = match reached.get(&next) {
+                    Some(&(known, _)) => next_cost < known,
+                    None => true,
+                };
+                if better {
+                    reached.insert(next, (next_cost, Some(coords)));
+                    let distance = goals.distance(next).map(|(d, _)| d).unwrap_or(0);
+                    open.push(Candidate {
+                        estimate: f64::from(next_cost) + heuristic(distance),
+                        coords: next,
+                    });
+                }
+            }
+        }
+
+        // jumps are always in a straight line or diagonal, so the tiles
+        // between each tile and the one before it can be filled back in
+        let mut path = Vec::new();
+        let mut coords = best.0;
+        while let Some(previous) = reached[&coords].1 {
+            let back = (
+                (previous.0 - coords.0).signum(),
+                (previous.1 - coords.1).signum(),
+            );
+            while coords != previous {
+                path.push(Position::from_world_coords(coords.0, coords.1));
+                coords = step(coords, back);
+            }
+        }
+        path.reverse();
+
+        LocalSearchResults {
+            path,
+            reached_goal,
+            ops,
+            cost: reached[&best.0].0,
+            incomplete: !complete,
+        }
+    }
+
+    /// Searches forwards from `start` and backwards from the edges of the
+    /// goals' ranges, always expanding the side with fewer open tiles.
+    fn search_bidirectional(&mut self, start: Coords, goals: &Goals) -> LocalSearchResults {
+        if let Some((0, goal)) = goals.distance(start) {
+            return LocalSearchResults {
+                path: Vec::new(),
+                reached_goal: Some(goal),
+                ops: 0,
+                cost: 0,
+                incomplete: false,
+            };
+        }
+
+        let weight = self.heuristic_weight;
+        let forward_heuristic = |coords: Coords| {
+            f64::from(goals.distance(coords).map(|(d, _)| d).unwrap_or(0)) * weight
+        };
+        let backward_heuristic = |coords: Coords| {
+            let range = (coords.0 - start.0).abs().max((coords.1 - start.1).abs());
+            f64::from(range as u32) * weight
+        };
+
+        let mut rooms: HashMap<RoomName, Option<RoomCosts<'a>>> = HashMap::new();
+        let mut forward = Frontier::default();
+        forward.insert(start, 0, None, forward_heuristic(start));
+        // every path into a goal's range first reaches a tile at exactly
+        // that range, so those are the only tiles to search back from
+        let mut backward = Frontier::default();
+        for goal in &goals.goals {
+            let (gx, gy) = goal.pos.world_coords();
+            let range = goal.range as i32;
+            for x in gx - range..=gx + range {
+                let ys = if x == gx - range || x == gx + range {
+                    (gy - range..=gy + range).collect()
+                } else {
+                    vec![gy - range, gy + range]
+                };
+                for y in ys {
+                    if in_world((x, y)) && self.tile_cost(&mut rooms, (x, y)).is_some() {
+                        backward.insert((x, y), 0, None, backward_heuristic((x, y)));
+                    }
+                }
+            }
+        }
+
+        let mut ops = 0;
+        // the closest tile to a goal the forward search found, and its
+        // distance, for when the searches never meet
+        let mut best = (start, u32::MAX);
+        // the cheapest path found, and the tile where the searches met on it
+        let mut meeting: Option<(u32, Coords)> = None;
+
+        while let (Some(forward_top), Some(backward_top)) = (
+            forward.top(forward_heuristic),
+            backward.top(backward_heuristic),
+        ) {
+            if let Some((cost, _)) = meeting {
+                if f64::from(cost) <= forward_top.max(backward_top) {
+                    break;
+                }
+            }
+            if ops >= self.max_ops {
+                break;
+            }
+            ops += 1;
+
+            if forward.open.len() <= backward.open.len() {
+                let coords = forward.open.pop().expect("expected open tile").coords;
+                let cost = forward.reached[&coords].0;
+                let distance = goals.distance(coords).map(|(d, _)| d).unwrap_or(0);
+                if distance < best.1 {
+                    best = (coords, distance);
+                }
+                for (next, step_cost) in self.successors(&mut rooms, goals, coords, None) {
+                    let next_cost = cost.saturating_add(step_cost);
+                    if next_cost > self.max_cost
+                        || !forward.insert(next, next_cost, Some(coords), forward_heuristic(next))
+                    {
+                        continue;
+                    }
+                    if let Some(&(rest, _)) = backward.reached.get(&next) {
+                        let total = next_cost.saturating_add(rest);
+                        if meeting.map(|(known, _)| total < known).unwrap_or(true) {
+                            meeting = Some((total, next));
+                        }
+                    }
+                }
+            } else {
+                let coords = backward.open.pop().expect("expected open tile").coords;
+                if coords == start {
+                    continue;
+                }
+                let cost = backward.reached[&coords].0;
+                // moving onto `coords` from any of its neighbors costs the same
+                let step_cost = match self.tile_cost(&mut rooms, coords) {
+                    Some(step_cost) => step_cost,
+                    None => continue,
+                };
+                let previous_cost = cost.saturating_add(step_cost);
+                if previous_cost > self.max_cost {
+                    continue;
+                }
+                for &direction in &DIRECTIONS {
+                    let previous = step(coords, direction);
+                    if !in_world(previous)
+                        || previous != start && self.tile_cost(&mut rooms, previous).is_none()
+                        || !backward.insert(
+                            previous,
+                            previous_cost,
+                            Some(coords),
+                            backward_heuristic(previous),
+                        )
+                    {
+                        continue;
+                    }
+                    if let Some(&(before, _)) = forward.reached.get(&previous) {
+                        let total = before.saturating_add(previous_cost);
+                        if meeting.map(|(known, _)| total < known).unwrap_or(true) {
+                            meeting = Some((total, previous));
+                        }
+                    }
+                }
+            }
+        }
+
+        match meeting {
+            Some((cost, middle)) => {
+                let mut path = forward.path_to(middle);
+                let mut end = middle;
+                while let Some(next) = backward.reached[&end].1 {
+                    path.push(Position::from_world_coords(next.0, next.1));
+                    end = next;
+                }
+                LocalSearchResults {
+                    path,
+                    reached_goal: goals.distance(end).map(|(_, goal)| goal),
+                    ops,
+                    cost,
+                    incomplete: false,
+                }
+            }
+            None => LocalSearchResults {
+                path: forward.path_to(best.0),
+                reached_goal: None,
+                ops,
+                cost: forward.reached[&best.0].0,
+                incomplete: true,
+            },
+        }
+    }
+
+    /// The tiles to search next from `coords`, reached from `parent`, and
+    /// the cost of getting to each of them from `coords`.
+    fn successors(
+        &mut self,
+        rooms: &mut HashMap<RoomName, Option<RoomCosts<'a>>>,
+        goals: &Goals,
+        coords: Coords,
+        parent: Option<Coords>,
+    ) -> Vec<(Coords, u32)> {
+        match self.mode {
+            LocalSearchMode::AStar | LocalSearchMode::Bidirectional => DIRECTIONS
+                .iter()
+                .map(|&direction| step(coords, direction))
+                .filter(|&next| in_world(next))
+                .filter_map(|next| Some((next, self.tile_cost(rooms, next)?)))
+                .collect(),
+            LocalSearchMode::JumpPoint => {
+                let skip = self
+                    .grid(rooms, goals, coords)
+                    .map(|grid| {
+                        let (x, y) = (coords.0.rem_euclid(50), coords.1.rem_euclid(50));
+                        grid.skip[x as usize * 50 + y as usize]
+                    })
+                    .unwrap_or(false);
+                let pruned = match parent {
+                    Some(parent) if skip => {
+                        let dx = (coords.0 - parent.0).signum();
+                        let dy = (coords.1 - parent.1).signum();
+                        if dx != 0 && dy != 0 {
+                            vec![(dx, 0), (0, dy), (dx, dy)]
+                        } else {
+                            vec![(dx, dy)]
+                        }
+                    }
+                    _ => DIRECTIONS.to_vec(),
+                };
+                pruned
+                    .into_iter()
+                    .filter_map(|direction| self.jump(rooms, goals, coords, direction))
+                    .collect()
+            }
+        }
+    }
+
+    /// The grid of the room `coords` is in, for jump point search, or
+    /// `None` if the room can't be entered.
+    fn grid<'r>(
+        &mut self,
+        rooms: &'r mut HashMap<RoomName, Option<RoomCosts<'a>>>,
+        goals: &Goals,
+        coords: Coords,
+    ) -> Option<&'r JumpGrid> {
+        let room_name = Position::from_world_coords(coords.0, coords.1).room_name();
+        self.load_room(rooms, room_name);
+        let origin = (
+            coords.0 - coords.0.rem_euclid(50),
+            coords.1 - coords.1.rem_euclid(50),
+        );
+        let (plain_cost, swamp_cost) = (self.plain_cost, self.swamp_cost);
+        rooms
+            .get_mut(&room_name)?
+            .as_mut()
+            .map(|costs| costs.grid(plain_cost, swamp_cost, origin, goals))
+    }
+
+    /// Jumps from `coords` in `direction`, as in [`JumpGrid::jump`],
+    /// returning the world coordinates of the tile jumped to.
+    ///
+    /// Jumps stop on room edges, so each only ever covers one room.
+    fn jump(
+        &mut self,
+        rooms: &mut HashMap<RoomName, Option<RoomCosts<'a>>>,
+        goals: &Goals,
+        coords: Coords,
+        direction: Coords,
+    ) -> Option<(Coords, u32)> {
+        let first = step(coords, direction);
+        if !in_world(first) {
+            return None;
+        }
+        let max_cost = self.max_cost;
+        let grid = self.grid(rooms, goals, first)?;
+        let (x, y) = (first.0.rem_euclid(50), first.1.rem_euclid(50));
+        grid.jump((x, y), direction, max_cost)
+            .map(|((jx, jy), cost)| ((first.0 + jx - x, first.1 + jy - y), cost))
+    }
+
+    /// Loads a room's terrain and cost matrix the first time it's reached.
+    fn load_room(&mut self, rooms: &mut HashMap<RoomName, Option<RoomCosts<'a>>>, room: RoomName) {
+        if rooms.contains_key(&room) {
+            return;
+        }
+        let costs = if rooms.len() as u32 >= self.max_rooms {
+            None
+        } else {
+            self.terrain
+                .get_cached(room)
+                .and_then(|terrain| match (self.room_callback)(room) {
+                    LocalRoomCost::CostMatrix(matrix) => {
+                        Some(RoomCosts::new(terrain, Some(matrix)))
+                    }
+                    LocalRoomCost::Default => Some(RoomCosts::new(terrain, None)),
+                    LocalRoomCost::Impassable => None,
+                })
+        };
+        rooms.insert(room, costs);
+    }
+
+    /// The cost of moving onto the tile at world coordinates `coords`, or
+    /// `None` if it's impassable.
+    fn tile_cost(
+        &mut self,
+        rooms: &mut HashMap<RoomName, Option<RoomCosts<'a>>>,
+        coords: Coords,
+    ) -> Option<u32> {
+        let pos = Position::from_world_coords(coords.0, coords.1);
+        let room_name = pos.room_name();
+        self.load_room(rooms, room_name);
+        let cost = rooms[&room_name].as_ref()?.cost(
+            pos.x() as u8,
+            pos.y() as u8,
+            self.plain_cost,
+            self.swamp_cost,
+        )?;
+        Some(u32::from(cost))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{LocalPathFinder, LocalRoomCost, LocalSearchMode};
+    use crate::{
+        local::{LocalRoomTerrain, Position, RoomName, TerrainCache},
+        pathfinder::{LocalCostMatrix, SearchGoal},
+    };
+
+    fn name(s: &str) -> RoomName {
+        RoomName::new(s).unwrap()
+    }
+
+    fn plains(rooms: &[&str]) -> TerrainCache {
+        let mut cache = TerrainCache::new();
+        for room in rooms {
+            cache.insert(
+                name(room),
+                LocalRoomTerrain::new_from_bits(Box::new([0; 2500])),
+            );
+        }
+        cache
+    }
+
+    #[test]
+    fn straight_and_around_walls() {
+        let room = name("W1N1");
+        let terrain = plains(&["W1N1"]);
+        let from = Position::new(10, 25, room);
+        let to = SearchGoal::new(Position::new(20, 25, room), 0);
+
+        let results = LocalPathFinder::new(&terrain).search(from, to);
+        assert_eq!(results.path.len(), 10);
+        assert_eq!(results.cost, 10);
+        assert_eq!(results.reached_goal, Some(0));
+        assert_eq!(results.path.last(), Some(&to.pos));
+
+        // a wall from y = 20 to y = 30 at x = 15
+        let mut bits = Box::new([0; 2500]);
+        for y in 20..=30 {
+            bits[y * 50 + 15] = 1;
+        }
+        let mut walled = TerrainCache::new();
+        walled.insert(room, LocalRoomTerrain::new_from_bits(bits));
+        let results = LocalPathFinder::new(&walled).search(from, to);
+        assert!(!results.incomplete);
+        assert!(results.path.len() > 10);
+        assert!(results
+            .path
+            .iter()
+            .all(|pos| pos.x() != 15 || pos.y() < 20 || pos.y() > 30));
+    }
+
+    #[test]
+    fn uses_cost_matrices() {
+        let room = name("W1N1");
+        let terrain = plains(&["W1N1"]);
+        let mut matrix = LocalCostMatrix::new();
+        for y in 0..50 {
+            matrix.set(15, y, 255);
+        }
+        let results = LocalPathFinder::new(&terrain)
+            .room_callback(|_| LocalRoomCost::CostMatrix(matrix.clone()))
+            .search(
+                Position::new(10, 25, room),
+                SearchGoal::new(Position::new(20, 25, room), 0),
+            );
+        assert!(results.incomplete);
+        assert_eq!(results.reached_goal, None);
+        assert_eq!(results.path.last().map(|pos| pos.x()), Some(14));
+    }
+
+    #[test]
+    fn crosses_cached_rooms_only() {
+        let from = Position::new(45, 25, name("W1N1"));
+        let goal = SearchGoal::new(Position::new(5, 25, name("W0N1")), 1);
+
+        let terrain = plains(&["W1N1", "W0N1"]);
+        let results = LocalPathFinder::new(&terrain).search(from, goal);
+        assert!(!results.incomplete);
+        assert_eq!(results.path.len(), 9);
+        assert_eq!(results.path.last().unwrap().room_name(), name("W0N1"));
+
+        let results = LocalPathFinder::new(&plains(&["W1N1"])).search(from, goal);
+        assert!(results.incomplete);
+
+        let results = LocalPathFinder::new(&terrain)
+            .room_callback(|room| {
+                if room == name("W0N1") {
+                    LocalRoomCost::Impassable
+                } else {
+                    LocalRoomCost::Default
+                }
+            })
+            .search(from, goal);
+        assert!(results.incomplete);
+    }
+
+    #[test]
+    fn flees_from_every_goal() {
+        let room = name("W1N1");
+        let terrain = plains(&["W1N1"]);
+        let threats = vec![
+            SearchGoal::new(Position::new(20, 25, room), 5),
+            SearchGoal::new(Position::new(25, 20, room), 5),
+        ];
+        let results = LocalPathFinder::new(&terrain)
+            .flee(true)
+            .search_many(Position::new(22, 22, room), threats.clone());
+        assert!(!results.incomplete);
+        assert_eq!(results.reached_goal, None);
+        let end = *results.path.last().unwrap();
+        assert!(threats
+            .iter()
+            .all(|threat| end.get_range_to(&threat.pos) >= 5));
+        // diagonally away to (20, 20)
+        assert_eq!(results.path.len(), 2);
+    }
+
+    #[test]
+    fn stops_at_max_ops() {
+        let room = name("W1N1");
+        let terrain = plains(&["W1N1"]);
+        let results = LocalPathFinder::new(&terrain).max_ops(3).search(
+            Position::new(1, 1, room),
+            SearchGoal::new(Position::new(48, 48, room), 0),
+        );
+        assert!(results.incomplete);
+        assert_eq!(results.ops, 3);
+        assert_eq!(results.path.len(), 3);
+    }
+
+    /// Terrain for one room with pseudo-random walls and swamps.
+    fn scattered(seed: u32) -> LocalRoomTerrain {
+        let mut bits = Box::new([0; 2500]);
+        let mut state = seed;
+        for bit in bits.iter_mut() {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            *bit = match (state >> 16) % 10 {
+                0 | 1 => 1,
+                2 => 2,
+                _ => 0,
+            };
+        }
+        LocalRoomTerrain::new_from_bits(bits)
+    }
+
+    /// Checks that each step of `path` is to a neighboring, passable tile.
+    fn assert_walkable(terrain: &TerrainCache, from: Position, path: &[Position]) {
+        let mut previous = from;
+        for pos in path {
+            assert_eq!(previous.get_range_to(pos), 1, "{:?} to {:?}", previous, pos);
+            let room = terrain.get_cached(pos.room_name()).unwrap();
+            assert!(room.get_mask(pos.x() as u8, pos.y() as u8).is_walkable());
+            previous = *pos;
+        }
+    }
+
+    #[test]
+    fn jump_point_search_matches_a_star() {
+        let room = name("W1N1");
+        for seed in 0..20 {
+            let mut terrain = TerrainCache::new();
+            terrain.insert(room, scattered(seed));
+            let from = Position::new(2 + seed % 5, 3, room);
+            let goal = SearchGoal::new(Position::new(46, 40 + seed % 7, room), seed % 3);
+
+            let a_star = LocalPathFinder::new(&terrain)
+                .heuristic_weight(1.0)
+                .max_ops(10_000)
+                .search(from, goal);
+            let jump_point = LocalPathFinder::new(&terrain)
+                .heuristic_weight(1.0)
+                .max_ops(10_000)
+                .mode(LocalSearchMode::JumpPoint)
+                .search(from, goal);
+
+            assert_eq!(jump_point.incomplete, a_star.incomplete, "seed {}", seed);
+            if !a_star.incomplete {
+                assert_eq!(jump_point.cost, a_star.cost, "seed {}", seed);
+                assert_walkable(&terrain, from, &jump_point.path);
+                assert_eq!(jump_point.reached_goal, Some(0));
+            }
+        }
+    }
+
+    #[test]
+    fn jump_point_search_expands_less_in_the_open() {
+        let terrain = plains(&["W2N1", "W1N1", "W0N1"]);
+        let from = Position::new(5, 10, name("W2N1"));
+        let goal = SearchGoal::new(Position::new(40, 35, name("W0N1")), 1);
+
+        let a_star = LocalPathFinder::new(&terrain).search(from, goal);
+        let jump_point = LocalPathFinder::new(&terrain)
+            .mode(LocalSearchMode::JumpPoint)
+            .search(from, goal);
+        assert!(!jump_point.incomplete);
+        assert_eq!(jump_point.cost, a_star.cost);
+        assert_eq!(jump_point.path.len(), a_star.path.len());
+        assert_walkable(&terrain, from, &jump_point.path);
+        assert!(
+            jump_point.ops * 4 < a_star.ops,
+            "{} vs {}",
+            jump_point.ops,
+            a_star.ops
+        );
+
+        // the room callback and max_rooms still apply
+        let blocked = LocalPathFinder::new(&terrain)
+            .mode(LocalSearchMode::JumpPoint)
+            .room_callback(|room| {
+                if room == name("W1N1") {
+                    LocalRoomCost::Impassable
+                } else {
+                    LocalRoomCost::Default
+                }
+            })
+            .search(from, goal);
+        assert!(blocked.incomplete);
+        let limited = LocalPathFinder::new(&terrain)
+            .mode(LocalSearchMode::JumpPoint)
+            .max_rooms(2)
+            .search(from, goal);
+        assert!(limited.incomplete);
+    }
+
+    #[test]
+    fn jump_point_flee() {
+        let room = name("W1N1");
+        let mut terrain = TerrainCache::new();
+        terrain.insert(room, scattered(7));
+        let threat = vec![SearchGoal::new(Position::new(25, 25, room), 6)];
+        let from = (20..30)
+            .flat_map(|x| (20..30).map(move |y| Position::new(x, y, room)))
+            .find(|pos| {
+                let room = terrain.get_cached(room).unwrap();
+                room.get_mask(pos.x() as u8, pos.y() as u8).is_walkable()
+            })
+            .unwrap();
+
+        let results = LocalPathFinder::new(&terrain)
+            .flee(true)
+            .mode(LocalSearchMode::JumpPoint)
+            .search_many(from, threat.clone());
+        assert!(!results.incomplete);
+        assert_walkable(&terrain, from, &results.path);
+        assert!(results.path.last().unwrap().get_range_to(&threat[0].pos) >= 6);
+    }
+
+    /// Terrain for one room with a wall around the east side of the room's
+    /// middle, open to the east.
+    fn pocket() -> LocalRoomTerrain {
+        let mut bits = Box::new([0; 2500]);
+        for y in 5..45 {
+            bits[y * 50 + 20] = 1;
+        }
+        for x in 20..45 {
+            bits[5 * 50 + x] = 1;
+            bits[44 * 50 + x] = 1;
+        }
+        LocalRoomTerrain::new_from_bits(bits)
+    }
+
+    #[test]
+    fn bidirectional_matches_a_star() {
+        let room = name("W1N1");
+        for seed in 0..20 {
+            let mut terrain = TerrainCache::new();
+            terrain.insert(room, scattered(seed));
+            let from = Position::new(2 + seed % 5, 3, room);
+            let goal = SearchGoal::new(Position::new(46, 40 + seed % 7, room), seed % 3);
+
+            let a_star = LocalPathFinder::new(&terrain)
+                .heuristic_weight(1.0)
+                .max_ops(10_000)
+                .search(from, goal);
+            let bidirectional = LocalPathFinder::new(&terrain)
+                .heuristic_weight(1.0)
+                .max_ops(10_000)
+                .mode(LocalSearchMode::Bidirectional)
+                .search(from, goal);
+
+            assert_eq!(bidirectional.incomplete, a_star.incomplete, "seed {}", seed);
+            if !a_star.incomplete {
+                assert_eq!(bidirectional.cost, a_star.cost, "seed {}", seed);
+                assert_walkable(&terrain, from, &bidirectional.path);
+                let end = *bidirectional.path.last().unwrap();
+                assert!(end.get_range_to(&goal.pos) <= seed % 3);
+                assert_eq!(bidirectional.reached_goal, Some(0));
+            }
+        }
+    }
+
+    #[test]
+    fn bidirectional_expands_less_around_a_pocket() {
+        let mut terrain = plains(&["W2N1", "W1N1"]);
+        terrain.insert(name("W0N1"), pocket());
+        let from = Position::new(10, 20, name("W2N1"));
+        let goal = SearchGoal::new(Position::new(30, 25, name("W0N1")), 1);
+
+        let a_star = LocalPathFinder::new(&terrain)
+            .max_ops(100_000)
+            .search(from, goal);
+        let bidirectional = LocalPathFinder::new(&terrain)
+            .max_ops(100_000)
+            .mode(LocalSearchMode::Bidirectional)
+            .search(from, goal);
+        assert!(!bidirectional.incomplete);
+        assert_eq!(bidirectional.cost, a_star.cost);
+        assert_walkable(&terrain, from, &bidirectional.path);
+        assert!(
+            bidirectional.ops * 4 < a_star.ops,
+            "{} vs {}",
+            bidirectional.ops,
+            a_star.ops
+        );
+    }
+
+    #[test]
+    fn bidirectional_without_a_path() {
+        let room = name("W1N1");
+        let terrain = plains(&["W1N1"]);
+        let mut matrix = LocalCostMatrix::new();
+        for y in 0..50 {
+            matrix.set(15, y, 255);
+        }
+        let goals = vec![
+            SearchGoal::new(Position::new(20, 25, room), 0),
+            SearchGoal::new(Position::new(5, 25, room), 2),
+        ];
+        let search = || {
+            LocalPathFinder::new(&terrain)
+                .room_callback(|_| LocalRoomCost::CostMatrix(matrix.clone()))
+                .mode(LocalSearchMode::Bidirectional)
+        };
+
+        let results = search().search(Position::new(10, 25, room), goals[0]);
+        assert!(results.incomplete);
+        assert_eq!(results.reached_goal, None);
+        assert_eq!(results.path.last().map(|pos| pos.x()), Some(14));
+
+        // the reachable goal
+        let results = search().search_many(Position::new(10, 25, room), goals.clone());
+        assert!(!results.incomplete);
+        assert_eq!(results.reached_goal, Some(1));
+        assert_eq!(results.path.len(), 3);
+
+        // flee searches run as A*
+        let threat = SearchGoal::new(Position::new(5, 25, room), 8);
+        let results = search()
+            .flee(true)
+            .search(Position::new(10, 25, room), threat);
+        assert!(!results.incomplete);
+        assert!(results.path.last().unwrap().get_range_to(&threat.pos) >= 8);
+    }
+}
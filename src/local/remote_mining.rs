@@ -0,0 +1,279 @@
+//! Estimating what a remote source is worth before mining it.
+//!
+//! [`RemoteMining::estimate`] finds a route from a home position, such as a
+//! storage, to a source in another room, estimates how far carriers walk,
+//! and works out how many carry parts are needed to haul the source's energy
+//! home and how much energy is left over once miners and carriers are paid
+//! for.
+//!
+//! ```
+//! use screeps::{
+//!     local::{remote_mining::RemoteMining, route::exits_within, LocalRoomTerrain, WorldBounds},
+//!     Position, RoomName,
+//! };
+//!
+//! let home = Position::new(25, 25, RoomName::new("W1N1").unwrap());
+//! let source = Position::new(10, 25, RoomName::new("W2N1").unwrap());
+//! let terrain = LocalRoomTerrain::new_from_bits(Box::new([0; 2500]));
+//! let exits = exits_within(WorldBounds::new(202));
+//!
+//! let estimate = RemoteMining::new()
+//!     .estimate(home, source, &terrain, exits)
+//!     .unwrap();
+//! assert_eq!(estimate.distance, 63);
+//! assert_eq!(estimate.carry_parts, 26);
+//! assert!(estimate.energy_per_tick > 6.0);
+//! ```
+use crate::constants::{
+    ExitDirection, Part, Terrain, CARRY_CAPACITY, CREEP_LIFE_TIME, ENERGY_REGEN_TIME,
+    HARVEST_POWER, SOURCE_ENERGY_CAPACITY, SOURCE_ENERGY_NEUTRAL_CAPACITY,
+};
+
+use super::{
+    route::{RoutePlanner, RouteRoom, RouteStep, Via},
+    LocalRoomTerrain, Position,
+};
+
+/// What mining a remote source is expected to cost and bring in.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RemoteEstimate {
+    /// Rooms entered on the way from home to the source.
+    pub route: Vec<RouteStep>,
+    /// Estimated tiles walked from home to next to the source.
+    pub distance: u32,
+    /// Tiles next to the source which aren't walls, where miners can stand.
+    pub harvest_spots: u32,
+    /// Carry parts needed to haul everything the source produces.
+    pub carry_parts: u32,
+    /// Work parts needed to harvest everything the source produces.
+    pub work_parts: u32,
+    /// Energy produced per tick, minus the spawning cost of the miner and
+    /// carriers.
+    pub energy_per_tick: f64,
+}
+
+impl RemoteEstimate {
+    /// Ticks for a carrier to walk to the source and back.
+    pub fn round_trip(&self) -> u32 {
+        self.distance * 2
+    }
+}
+
+/// Settings for estimating remote sources.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RemoteMining {
+    reserved: bool,
+    max_rooms: usize,
+}
+
+impl Default for RemoteMining {
+    fn default() -> Self {
+        RemoteMining {
+            reserved: true,
+            max_rooms: 16,
+        }
+    }
+}
+
+impl RemoteMining {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether the source's room will be reserved, doubling the
+    /// source's capacity - default `true`.
+    ///
+    /// The cost of the creep reserving the room isn't counted against
+    /// [`RemoteEstimate::energy_per_tick`], as it's shared by every source
+    /// in the room.
+    pub fn reserved(mut self, reserved: bool) -> Self {
+        self.reserved = reserved;
+        self
+    }
+
+    /// Sets the most rooms searched for a route - default `16`.
+    pub fn max_rooms(mut self, max_rooms: usize) -> Self {
+        self.max_rooms = max_rooms;
+        self
+    }
+
+    /// Estimates mining the source at `source`, with `terrain` being the
+    /// terrain of the source's room. The route is found with a
+    /// [`RoutePlanner`] using `exits`, such as [`route::game_exits`].
+    ///
+    /// Returns `None` if there's no route between the rooms, or every tile
+    /// next to the source is a wall.
+    ///
+    /// [`route::game_exits`]: super::route::game_exits
+    pub fn estimate<E>(
+        &self,
+        home: Position,
+        source: Position,
+        terrain: &LocalRoomTerrain,
+        exits: E,
+    ) -> Option<RemoteEstimate>
+    where
+        E: FnMut(&RouteRoom) -> Vec<ExitDirection>,
+    {
+        let harvest_spots = harvest_spots(source, terrain);
+        if harvest_spots == 0 {
+            return None;
+        }
+        let route = RoutePlanner::new(exits)
+            .max_rooms(self.max_rooms)
+            .find(home.room_name(), source.room_name())?;
+        let distance = route_distance(home, source, &route);
+
+        let capacity = if self.reserved {
+            SOURCE_ENERGY_CAPACITY
+        } else {
+            SOURCE_ENERGY_NEUTRAL_CAPACITY
+        };
+        let per_tick = f64::from(capacity) / f64::from(ENERGY_REGEN_TIME);
+        let work_parts = (per_tick / f64::from(HARVEST_POWER)).ceil() as u32;
+        let carry_parts =
+            (per_tick * f64::from(distance * 2) / f64::from(CARRY_CAPACITY)).ceil() as u32;
+
+        // carriers with one move part per two carry parts, for roads, and a
+        // miner with one move part per two work parts
+        let spawn_cost = carry_parts * Part::Carry.cost()
+            + (carry_parts - carry_parts / 2) * Part::Move.cost()
+            + work_parts * Part::Work.cost()
+            + (work_parts - work_parts / 2) * Part::Move.cost();
+        let energy_per_tick = per_tick - f64::from(spawn_cost) / f64::from(CREEP_LIFE_TIME);
+
+        Some(RemoteEstimate {
+            route,
+            distance,
+            harvest_spots,
+            carry_parts,
+            work_parts,
+            energy_per_tick,
+        })
+    }
+}
+
+/// Number of tiles around `pos` which aren't walls.
+fn harvest_spots(pos: Position, terrain: &LocalRoomTerrain) -> u32 {
+    let (x, y) = (pos.x() as i32, pos.y() as i32);
+    let mut spots = 0;
+    for dx in -1..=1 {
+        for dy in -1..=1 {
+            let (nx, ny) = (x + dx, y + dy);
+            if (dx, dy) == (0, 0) || nx < 0 || ny < 0 || nx > 49 || ny > 49 {
+                continue;
+            }
+            if terrain.get(nx as u8, ny as u8) != Terrain::Wall {
+                spots += 1;
+            }
+        }
+    }
+    spots
+}
+
+/// Estimates tiles walked from `home` to next to `target` along `route`, by
+/// walking in straight lines to the nearest tile of each exit.
+fn route_distance(home: Position, target: Position, route: &[RouteStep]) -> u32 {
+    let mut distance = 0;
+    let (mut x, mut y) = (home.x(), home.y());
+    for step in route {
+        if let Via::Exit(exit) = step.via {
+            let (exit_x, exit_y, next_x, next_y) = match exit {
+                ExitDirection::Top => (x, 0, x, 49),
+                ExitDirection::Right => (49, y, 0, y),
+                ExitDirection::Bottom => (x, 49, x, 0),
+                ExitDirection::Left => (0, y, 49, y),
+            };
+            let range = (x as i32 - exit_x as i32)
+                .abs()
+                .max((y as i32 - exit_y as i32).abs());
+            // walking onto the exit tile moves to the next room
+            distance += range as u32;
+            x = next_x;
+            y = next_y;
+        }
+    }
+    let range = (x as i32 - target.x() as i32)
+        .abs()
+        .max((y as i32 - target.y() as i32).abs());
+    distance + (range as u32).saturating_sub(1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{harvest_spots, RemoteMining};
+    use crate::{
+        constants::ExitDirection,
+        local::{
+            route::{exits_within, RouteRoom},
+            LocalRoomTerrain, Position, RoomName, WorldBounds,
+        },
+    };
+
+    fn pos(x: u32, y: u32, room: &str) -> Position {
+        Position::new(x, y, RoomName::new(room).unwrap())
+    }
+
+    fn mmo_exits() -> impl FnMut(&RouteRoom) -> Vec<ExitDirection> {
+        exits_within(WorldBounds::new(202))
+    }
+
+    fn plains() -> LocalRoomTerrain {
+        LocalRoomTerrain::new_from_bits(Box::new([0; 2500]))
+    }
+
+    #[test]
+    fn counts_harvest_spots() {
+        let mut bits = Box::new([0; 2500]);
+        // walls above and to the left of (10, 10)
+        for i in 9..=11 {
+            bits[9 * 50 + i] = 1;
+            bits[i * 50 + 9] = 1;
+        }
+        let terrain = LocalRoomTerrain::new_from_bits(bits);
+        assert_eq!(harvest_spots(pos(10, 10, "W1N1"), &terrain), 3);
+        assert_eq!(harvest_spots(pos(0, 0, "W1N1"), &plains()), 3);
+
+        let walled = LocalRoomTerrain::new_from_bits(Box::new([1; 2500]));
+        assert_eq!(
+            RemoteMining::new().estimate(
+                pos(25, 25, "W1N1"),
+                pos(10, 10, "W2N1"),
+                &walled,
+                mmo_exits()
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn estimates_through_rooms() {
+        let estimate = RemoteMining::new()
+            .estimate(
+                pos(25, 25, "W1N1"),
+                pos(25, 10, "W1N3"),
+                &plains(),
+                mmo_exits(),
+            )
+            .unwrap();
+        assert_eq!(estimate.route.len(), 2);
+        // 25 to the top exit, 49 across W1N2, 39 up to the source less one
+        assert_eq!(estimate.distance, 25 + 49 + 38);
+        assert_eq!(estimate.round_trip(), 224);
+        assert_eq!(estimate.work_parts, 5);
+        assert_eq!(estimate.carry_parts, 45);
+
+        let unreserved = RemoteMining::new()
+            .reserved(false)
+            .estimate(
+                pos(25, 25, "W1N1"),
+                pos(25, 10, "W1N3"),
+                &plains(),
+                mmo_exits(),
+            )
+            .unwrap();
+        assert_eq!(unreserved.work_parts, 3);
+        assert_eq!(unreserved.carry_parts, 23);
+        assert!(unreserved.energy_per_tick < estimate.energy_per_tick);
+    }
+}
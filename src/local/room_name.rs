@@ -8,6 +8,8 @@ use std::{
 
 use arrayvec::ArrayString;
 
+use crate::constants::Direction;
+
 use super::{HALF_WORLD_SIZE, VALID_ROOM_NAME_COORDINATES};
 
 /// A structure representing a room name.
@@ -26,6 +28,12 @@ use super::{HALF_WORLD_SIZE, VALID_ROOM_NAME_COORDINATES};
 /// This follows left-to-right reading order when looking at the Screeps map
 /// from above.
 ///
+/// # Simulation room
+///
+/// The simulation room, `sim`, shares its representation with `W127N127`, the
+/// furthest north-west room name. Both parse to the same `RoomName`, which is
+/// displayed as `sim`.
+///
 /// [`BTreeMap`]: std::collections::BTreeMap
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub struct RoomName {
@@ -83,7 +91,15 @@ impl RoomName {
     /// invalid room name.
     ///
     /// The expected format can be represented by the regex
-    /// `[ewEW][0-9]+[nsNS][0-9]+`.
+    /// `[ewEW][0-9]+[nsNS][0-9]+`, or is `sim` in any case. Both numbers must
+    /// be at most 127.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RoomNameParseError::PositionOutOfBounds`] if the name is well
+    /// formed but a number is too large, and
+    /// [`RoomNameParseError::InvalidString`] or
+    /// [`RoomNameParseError::TooLarge`] otherwise.
     #[inline]
     pub fn new<T>(x: &T) -> Result<Self, RoomNameParseError>
     where
@@ -142,6 +158,77 @@ impl RoomName {
         self.packed
     }
 
+    /// Whether this room is a highway room, with no controller or sources.
+    ///
+    /// Highway rooms are the rooms where either coordinate number is a
+    /// multiple of 10, like `W10N5` or `E3S20`.
+    pub fn is_highway(&self) -> bool {
+        coord_number(self.x_coord()) % 10 == 0 || coord_number(self.y_coord()) % 10 == 0
+    }
+
+    /// Whether this room is the center room of its sector, like `W5N5` or
+    /// `E15S25`.
+    pub fn is_center(&self) -> bool {
+        coord_number(self.x_coord()) % 10 == 5 && coord_number(self.y_coord()) % 10 == 5
+    }
+
+    /// Whether this room is a source keeper room, one of the eight rooms
+    /// surrounding the center room of its sector.
+    pub fn is_source_keeper(&self) -> bool {
+        let in_core = |c: i32| (4..=6).contains(&(coord_number(c) % 10));
+
+        in_core(self.x_coord()) && in_core(self.y_coord()) && !self.is_center()
+    }
+
+    /// Gets the highway crossroad room at the corner of this room's sector
+    /// which is nearest to the center of the world.
+    ///
+    /// For example, every room from `W10N10` to `W19N19` is in the sector
+    /// whose corner is `W10N10`. Highway rooms are part of the sector on
+    /// their outer side.
+    pub fn sector(&self) -> RoomName {
+        let round = |c: i32| {
+            let n = coord_number(c);
+            let rounded = n - n % 10;
+            if c >= 0 {
+                rounded
+            } else {
+                -rounded - 1
+            }
+        };
+
+        RoomName::from_coords(round(self.x_coord()), round(self.y_coord()))
+            .expect("expected rounding towards the center to stay within room name bounds")
+    }
+
+    /// Gets the up to 8 rooms around this one and the direction of each,
+    /// starting at the top and going clockwise.
+    ///
+    /// Rooms past the edge of the representable world are left out, but this
+    /// doesn't know the current world's size or which rooms have exits
+    /// between them; see [`Room::accessible_neighbors`] for that.
+    ///
+    /// [`Room::accessible_neighbors`]: crate::objects::Room::accessible_neighbors
+    pub fn neighbors(&self) -> impl Iterator<Item = (Direction, RoomName)> {
+        const OFFSETS: [(Direction, i32, i32); 8] = [
+            (Direction::Top, 0, -1),
+            (Direction::TopRight, 1, -1),
+            (Direction::Right, 1, 0),
+            (Direction::BottomRight, 1, 1),
+            (Direction::Bottom, 0, 1),
+            (Direction::BottomLeft, -1, 1),
+            (Direction::Left, -1, 0),
+            (Direction::TopLeft, -1, -1),
+        ];
+
+        let (x, y) = (self.x_coord(), self.y_coord());
+        OFFSETS.iter().filter_map(move |&(direction, dx, dy)| {
+            RoomName::from_coords(x + dx, y + dy)
+                .ok()
+                .map(|room| (direction, room))
+        })
+    }
+
     /// Converts this RoomName into an efficient, stack-based string.
     ///
     /// This is equivalent to [`ToString::to_string`], but involves no
@@ -153,6 +240,17 @@ impl RoomName {
     }
 }
 
+/// Converts an x or y coordinate into the number shown in the room name, so
+/// that both `W5` and `E5` become `5`.
+#[inline]
+fn coord_number(coord: i32) -> i32 {
+    if coord >= 0 {
+        coord
+    } else {
+        -coord - 1
+    }
+}
+
 impl ops::Add<(i32, i32)> for RoomName {
     type Output = Self;
 
@@ -221,8 +319,16 @@ impl FromStr for RoomName {
     }
 }
 
+/// Parses the number in a room name, which must be only ASCII digits.
+fn parse_coord_number(s: &str) -> Result<i32, ()> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(());
+    }
+    s.parse().map_err(|_| ())
+}
+
 fn parse_to_coords(s: &str) -> Result<(i32, i32), ()> {
-    if s == "sim" {
+    if s.eq_ignore_ascii_case("sim") {
         return Ok((-HALF_WORLD_SIZE, -HALF_WORLD_SIZE));
     }
 
@@ -256,7 +362,7 @@ fn parse_to_coords(s: &str) -> Result<(i32, i32), ()> {
             }
         }
 
-        let x_coord = s[start_index..end_index].parse().map_err(|_| ())?;
+        let x_coord = parse_coord_number(&s[start_index..end_index])?;
 
         (x_coord, south)
     };
@@ -264,7 +370,7 @@ fn parse_to_coords(s: &str) -> Result<(i32, i32), ()> {
     let y_coord: i32 = {
         let (start_index, _) = chars.next().ok_or(())?;
 
-        s[start_index..s.len()].parse().map_err(|_| ())?
+        parse_coord_number(&s[start_index..])?
     };
 
     let room_x = if east { x_coord } else { -x_coord - 1 };
@@ -463,4 +569,122 @@ mod test {
             assert_eq!(&room_name.to_string(), RoomName::new(room_name).unwrap());
         }
     }
+
+    #[test]
+    fn test_parse_edge_cases() {
+        use super::{RoomName, RoomNameParseError};
+
+        for sim in &["sim", "SIM", "Sim"] {
+            assert_eq!(RoomName::new(sim).unwrap().to_string(), "sim");
+        }
+        assert_eq!(RoomName::new("e0s0").unwrap().to_string(), "E0S0");
+        assert_eq!(
+            RoomName::new("W0N0").unwrap() - RoomName::new("E0S0").unwrap(),
+            (-1, -1)
+        );
+        assert_eq!(RoomName::new("E127S127").unwrap().to_string(), "E127S127");
+        assert_eq!(
+            RoomName::new("W127N127").unwrap(),
+            RoomName::new("sim").unwrap()
+        );
+
+        for invalid in &[
+            "", "E", "EN", "E5N", "E5", "N5E5", "E+5N5", "E-5N5", "E5N-5", "E 5N5", "E5N5 ",
+            "E5ÑN5", "sims", "E5N5E5",
+        ] {
+            match RoomName::new(invalid) {
+                Err(RoomNameParseError::InvalidString { .. }) => (),
+                other => panic!("expected {:?} to be invalid, found {:?}", invalid, other),
+            }
+        }
+        assert!(matches!(
+            RoomName::new("E99999999999N5"),
+            Err(RoomNameParseError::TooLarge { length: 14 })
+        ));
+        for out_of_bounds in &["E128N0", "W128N0", "E0S128", "W0N128", "E1234567890N5"] {
+            match RoomName::new(out_of_bounds) {
+                Err(RoomNameParseError::PositionOutOfBounds { .. }) => (),
+                other => panic!(
+                    "expected {:?} to be out of bounds, found {:?}",
+                    out_of_bounds, other
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_room_round_trips() {
+        use super::RoomName;
+
+        for x in -128..128 {
+            for y in -128..128 {
+                let name = RoomName::from_coords(x, y).unwrap();
+                let parsed = RoomName::new(&name.to_array_string()).unwrap();
+                assert_eq!(parsed, name);
+                let lowercase = name.to_string().to_ascii_lowercase();
+                assert_eq!(RoomName::new(&lowercase).unwrap(), name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_neighbors() {
+        use super::RoomName;
+        use crate::constants::Direction;
+        let name = |s: &str| RoomName::new(s).unwrap();
+
+        let neighbors: Vec<_> = name("W0N0").neighbors().collect();
+        assert_eq!(neighbors.len(), 8);
+        assert_eq!(neighbors[0], (Direction::Top, name("W0N1")));
+        assert_eq!(neighbors[2], (Direction::Right, name("E0N0")));
+        assert_eq!(neighbors[3], (Direction::BottomRight, name("E0S0")));
+        assert_eq!(neighbors[7], (Direction::TopLeft, name("W1N1")));
+
+        let corner: Vec<_> = name("E127S127").neighbors().collect();
+        assert_eq!(
+            corner,
+            [
+                (Direction::Top, name("E127S126")),
+                (Direction::Left, name("E126S127")),
+                (Direction::TopLeft, name("E126S126")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sector_classification() {
+        use super::RoomName;
+        let name = |s: &str| RoomName::new(s).unwrap();
+
+        for highway in &["W10N5", "E0S3", "W0N0", "E20S20", "W7N30"] {
+            assert!(name(highway).is_highway(), "{}", highway);
+            assert!(!name(highway).is_source_keeper(), "{}", highway);
+        }
+        for center in &["W5N5", "E15S25", "E5N5"] {
+            assert!(name(center).is_center(), "{}", center);
+            assert!(!name(center).is_source_keeper(), "{}", center);
+            assert!(!name(center).is_highway(), "{}", center);
+        }
+        for sk in &["W4N4", "W6N5", "E14S26", "E16S24", "W5N6"] {
+            assert!(name(sk).is_source_keeper(), "{}", sk);
+            assert!(!name(sk).is_center(), "{}", sk);
+        }
+        for normal in &["W3N5", "E17S25", "W1N1", "E9S9"] {
+            assert!(!name(normal).is_highway(), "{}", normal);
+            assert!(!name(normal).is_center(), "{}", normal);
+            assert!(!name(normal).is_source_keeper(), "{}", normal);
+        }
+    }
+
+    #[test]
+    fn test_sector() {
+        use super::RoomName;
+        let name = |s: &str| RoomName::new(s).unwrap();
+
+        assert_eq!(name("W15N27").sector(), name("W10N20"));
+        assert_eq!(name("E3S9").sector(), name("E0S0"));
+        assert_eq!(name("W0N0").sector(), name("W0N0"));
+        assert_eq!(name("E19S10").sector(), name("E10S10"));
+        assert_eq!(name("W127N127").sector(), name("W120N120"));
+    }
 }
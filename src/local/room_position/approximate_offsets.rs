@@ -57,6 +57,17 @@ impl Position {
 
         target + (new_offset_x, new_offset_y)
     }
+
+    /// Moves this position off of the room's edge tiles, if it's on one.
+    ///
+    /// The resulting position is in the same room with both coordinates
+    /// within `1..=48`. Positions calculated with [`Position::towards`] or
+    /// [`Position::midpoint_between`] across rooms can land on an exit tile,
+    /// which creeps can't rest on without changing rooms.
+    pub fn clamped_within_room(self) -> Position {
+        self.with_x(self.x().clamp(1, 48))
+            .with_y(self.y().clamp(1, 48))
+    }
 }
 
 #[cfg(test)]
@@ -144,4 +155,15 @@ mod test {
             assert_eq!(start.midpoint_between(&pos(room, 1, 3)), pos(room, 5, 6));
         }
     }
+
+    #[test]
+    fn clamped_within_room() {
+        for room in test_rooms() {
+            assert_eq!(pos(room, 10, 10).clamped_within_room(), pos(room, 10, 10));
+            assert_eq!(pos(room, 0, 10).clamped_within_room(), pos(room, 1, 10));
+            assert_eq!(pos(room, 49, 0).clamped_within_room(), pos(room, 48, 1));
+            assert_eq!(pos(room, 1, 49).clamped_within_room(), pos(room, 1, 48));
+            assert_eq!(pos(room, 48, 48).clamped_within_room(), pos(room, 48, 48));
+        }
+    }
 }
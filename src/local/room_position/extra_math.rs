@@ -3,6 +3,7 @@
 use std::ops::{Add, Sub};
 
 use super::Position;
+use crate::{constants::ExitDirection, objects::HasPosition};
 
 impl Position {
     /// Returns a new position offset from this position by the specified x
@@ -39,6 +40,63 @@ impl Position {
     pub fn offset(&mut self, x: i32, y: i32) {
         *self = *self + (x, y);
     }
+
+    /// The edge of the room this position is on, if it's an exit tile.
+    ///
+    /// See [`ExitDirection::from_edge`].
+    #[inline]
+    pub fn exit_direction(self) -> Option<ExitDirection> {
+        ExitDirection::from_edge(self.x(), self.y())
+    }
+
+    /// Finds the closest of some objects by linear range, or `None` if there
+    /// are none.
+    ///
+    /// Like [`Position::find_closest_by_range`], but searches objects which
+    /// have already been fetched rather than calling into JavaScript, and
+    /// works across rooms. Of objects at the same range, the first is
+    /// returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use screeps::{Position, RoomName};
+    ///
+    /// let room = RoomName::new("W1N1").unwrap();
+    /// let pos = Position::new(10, 10, room);
+    /// let targets = [
+    ///     Position::new(20, 20, room),
+    ///     Position::new(13, 8, room),
+    ///     Position::new(5, 5, room),
+    /// ];
+    ///
+    /// assert_eq!(pos.closest_by_range(&targets), Some(&targets[1]));
+    /// assert_eq!(pos.filter_in_range(&targets, 5).count(), 2);
+    /// ```
+    pub fn closest_by_range<'a, T, I>(self, objects: I) -> Option<&'a T>
+    where
+        T: ?Sized + HasPosition + 'a,
+        I: IntoIterator<Item = &'a T>,
+    {
+        objects
+            .into_iter()
+            .min_by_key(|object| self.get_range_to(*object))
+    }
+
+    /// Filters some objects to those within `range` of this position.
+    ///
+    /// Like [`Position::find_in_range`], but searches objects which have
+    /// already been fetched rather than calling into JavaScript, and works
+    /// across rooms.
+    pub fn filter_in_range<'a, T, I>(self, objects: I, range: u32) -> impl Iterator<Item = &'a T>
+    where
+        T: ?Sized + HasPosition + 'a,
+        I: IntoIterator<Item = &'a T>,
+    {
+        objects
+            .into_iter()
+            .filter(move |object| self.in_range_to(*object, range))
+    }
 }
 
 impl Add<(i32, i32)> for Position {
@@ -1,4 +1,6 @@
 //! Game method implementations on `Position`
+use stdweb::Reference;
+
 use crate::{
     constants::{Color, FindConstant, LookConstant, ReturnCode, StructureType},
     game,
@@ -11,16 +13,22 @@ use super::Position;
 
 impl Position {
     pub fn create_construction_site(self, ty: StructureType) -> ReturnCode {
-        js_unwrap!(
-            pos_from_packed(@{self.packed_repr()})
-                .createConstructionSite(__structure_type_num_to_str(@{ty as u32}))
+        let pos: Reference = js_unwrap!(pos_from_packed(@{self.packed_repr()}));
+        record_intent!(
+            &pos,
+            "createConstructionSite",
+            js_unwrap!(@{&pos}.createConstructionSite(__structure_type_num_to_str(@{ty as u32})))
         )
     }
 
     pub fn create_named_construction_site(self, ty: StructureType, name: &str) -> ReturnCode {
-        js_unwrap!(
-            pos_from_packed(@{self.packed_repr()})
-                .createConstructionSite(__structure_type_num_to_str(@{ty as u32}), @{name})
+        let pos: Reference = js_unwrap!(pos_from_packed(@{self.packed_repr()}));
+        record_intent!(
+            &pos,
+            "createConstructionSite",
+            js_unwrap!(
+                @{&pos}.createConstructionSite(__structure_type_num_to_str(@{ty as u32}), @{name})
+            )
         )
     }
 
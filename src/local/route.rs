@@ -0,0 +1,485 @@
+//! Planning routes between rooms in Rust, including through portals.
+//!
+//! `Game.map.findRoute` only knows about room exits. [`RoutePlanner`] moves
+//! between rooms through the exits reported by a callback, plus any portals
+//! it's told about, so routes can jump across the map or to another shard
+//! when that's shorter.
+//!
+//! With [`game_exits`], rooms on the current shard have the same exits as
+//! `Game.map.describeExits` reports, which are the exits `findRoute` uses.
+//! Unlike `findRoute`, novice and respawn areas aren't avoided unless the
+//! room cost callback does so, for instance with
+//! [`RoomStatus::can_travel_to`].
+//!
+//! ```
+//! use screeps::{
+//!     local::{
+//!         route::{exits_within, Portal, RoutePlanner, RouteRoom, Via},
+//!         WorldBounds,
+//!     },
+//!     RoomName,
+//! };
+//!
+//! let name = |s: &str| RoomName::new(s).unwrap();
+//!
+//! // every room in a 60x60 world, with no closed exits; use `game_exits` to
+//! // get the exits from the game instead
+//! let exits = exits_within(WorldBounds::new(60));
+//! let portal = Portal::new(name("E10N10"), name("W20N20"));
+//! let route = RoutePlanner::new(exits)
+//!     .portals(vec![portal])
+//!     .find(name("E9N10"), name("W20N19"))
+//!     .unwrap();
+//!
+//! assert_eq!(route[0].via, Via::Exit(screeps::ExitDirection::Right));
+//! assert_eq!(route[1].room, RouteRoom::from(name("W20N20")));
+//! assert_eq!(route[1].via, Via::Portal);
+//! assert_eq!(route.len(), 3);
+//! ```
+//!
+//! [`RoomStatus::can_travel_to`]: crate::game::map::RoomStatus::can_travel_to
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+};
+
+use crate::{constants::ExitDirection, game};
+
+use super::{RoomName, WorldBounds};
+
+/// Every exit direction, in the order exits are searched.
+const EXIT_DIRECTIONS: [ExitDirection; 4] = [
+    ExitDirection::Top,
+    ExitDirection::Right,
+    ExitDirection::Bottom,
+    ExitDirection::Left,
+];
+
+/// A room on a particular shard.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RouteRoom {
+    /// The room's shard, or `None` for the shard the search starts on.
+    pub shard: Option<String>,
+    pub room: RoomName,
+}
+
+impl RouteRoom {
+    pub fn new(shard: Option<String>, room: RoomName) -> Self {
+        RouteRoom { shard, room }
+    }
+
+    /// Finds the room across an exit, on the same shard, whether or not the
+    /// exit is open.
+    fn across(&self, exit: ExitDirection) -> Option<RouteRoom> {
+        let (dx, dy) = match exit {
+            ExitDirection::Top => (0, -1),
+            ExitDirection::Right => (1, 0),
+            ExitDirection::Bottom => (0, 1),
+            ExitDirection::Left => (-1, 0),
+        };
+        let room =
+            RoomName::from_coords(self.room.x_coord() + dx, self.room.y_coord() + dy).ok()?;
+        Some(RouteRoom {
+            shard: self.shard.clone(),
+            room,
+        })
+    }
+}
+
+impl From<RoomName> for RouteRoom {
+    /// A room on the shard the search starts on.
+    fn from(room: RoomName) -> Self {
+        RouteRoom { shard: None, room }
+    }
+}
+
+/// Exits callback for [`RoutePlanner::new`] using `Game.map.describeExits`,
+/// so routes only use exits which exist and aren't closed.
+///
+/// `Game.map` only describes the current shard, so rooms on other shards
+/// have no exits, and routes through an inter-shard portal can only end in
+/// the room it leads to. Use a callback of your own to route further on
+/// other shards.
+pub fn game_exits(room: &RouteRoom) -> Vec<ExitDirection> {
+    if room.shard.is_some() {
+        return Vec::new();
+    }
+    let exits = game::map::describe_exits(room.room);
+    EXIT_DIRECTIONS
+        .iter()
+        .cloned()
+        .filter(|&exit| exits.contains_key(&exit.into()))
+        .collect()
+}
+
+/// Exits callback for [`RoutePlanner::new`] which opens every side of every
+/// room leading to another room within `bounds`, on every shard.
+///
+/// This doesn't need access to the game, but doesn't know about closed
+/// exits, such as the walls around novice and respawn areas.
+pub fn exits_within(bounds: WorldBounds) -> impl FnMut(&RouteRoom) -> Vec<ExitDirection> {
+    move |room| {
+        EXIT_DIRECTIONS
+            .iter()
+            .cloned()
+            .filter(|&exit| matches!(room.across(exit), Some(next) if bounds.contains(next.room)))
+            .collect()
+    }
+}
+
+/// A portal the planner may route through, in one direction.
+///
+/// Portals which aren't one-way need to be given in both directions.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Portal {
+    pub from: RouteRoom,
+    pub to: RouteRoom,
+}
+
+impl Portal {
+    /// A portal between two rooms on the shard the search starts on.
+    pub fn new(from: RoomName, to: RoomName) -> Self {
+        Portal {
+            from: from.into(),
+            to: to.into(),
+        }
+    }
+
+    /// A portal from a room on the shard the search starts on to a room on
+    /// another shard.
+    pub fn inter_shard(from: RoomName, shard: &str, to: RoomName) -> Self {
+        Portal {
+            from: from.into(),
+            to: RouteRoom::new(Some(shard.to_owned()), to),
+        }
+    }
+}
+
+/// How a route enters a room.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Via {
+    /// Through the previous room's exit in this direction.
+    Exit(ExitDirection),
+    /// Through a portal in the previous room.
+    Portal,
+}
+
+/// One room along a route.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RouteStep {
+    pub room: RouteRoom,
+    pub via: Via,
+}
+
+/// A room waiting to be searched, ordered so that `BinaryHeap` pops the
+/// cheapest first.
+struct Candidate {
+    cost: f64,
+    room: RouteRoom,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cost == other.cost
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // costs are never NaN, as infinite and negative costs are skipped
+        other
+            .cost
+            .partial_cmp(&self.cost)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+/// For each room reached, the cheapest cost found to it and the room and
+/// exit or portal it was entered from.
+type Reached = HashMap<RouteRoom, (f64, Option<(RouteRoom, Via)>)>;
+
+/// Plans routes between rooms, like `Game.map.findRoute`, but with support
+/// for portals.
+///
+/// Without a `room_cost` callback each room costs `1` to enter, and portals
+/// cost nothing extra to use beyond entering the room they lead to.
+pub struct RoutePlanner<F, E>
+where
+    F: FnMut(&RouteRoom) -> f64,
+    E: FnMut(&RouteRoom) -> Vec<ExitDirection>,
+{
+    room_cost: F,
+    exits: E,
+    portals: HashMap<RouteRoom, Vec<RouteRoom>>,
+    max_rooms: usize,
+}
+
+impl<E> RoutePlanner<fn(&RouteRoom) -> f64, E>
+where
+    E: FnMut(&RouteRoom) -> Vec<ExitDirection>,
+{
+    /// Creates a planner which leaves each room through the exits `exits`
+    /// returns for it, such as [`game_exits`] or [`exits_within`].
+    pub fn new(exits: E) -> Self {
+        fn one(_: &RouteRoom) -> f64 {
+            1.0
+        }
+
+        RoutePlanner {
+            room_cost: one,
+            exits,
+            portals: HashMap::new(),
+            max_rooms: 2000,
+        }
+    }
+}
+
+impl<F, E> RoutePlanner<F, E>
+where
+    F: FnMut(&RouteRoom) -> f64,
+    E: FnMut(&RouteRoom) -> Vec<ExitDirection>,
+{
+    /// Sets the cost of entering each room. Rooms with an infinite or
+    /// negative cost are never entered, except for the destination.
+    pub fn room_cost<F2>(self, room_cost: F2) -> RoutePlanner<F2, E>
+    where
+        F2: FnMut(&RouteRoom) -> f64,
+    {
+        let RoutePlanner {
+            exits,
+            portals,
+            max_rooms,
+            ..
+        } = self;
+        RoutePlanner {
+            room_cost,
+            exits,
+            portals,
+            max_rooms,
+        }
+    }
+
+    /// Adds portals which routes may pass through.
+    pub fn portals<I>(mut self, portals: I) -> Self
+    where
+        I: IntoIterator<Item = Portal>,
+    {
+        for portal in portals {
+            self.portals.entry(portal.from).or_default().push(portal.to);
+        }
+        self
+    }
+
+    /// Sets the most rooms searched before giving up - default `2000`.
+    pub fn max_rooms(mut self, max_rooms: usize) -> Self {
+        self.max_rooms = max_rooms;
+        self
+    }
+
+    /// Finds the cheapest route between two rooms on the shard the search
+    /// starts on.
+    ///
+    /// The route lists each room entered after `from`, ending with `to`, or
+    /// is `None` if no route was found.
+    pub fn find(self, from: RoomName, to: RoomName) -> Option<Vec<RouteStep>> {
+        self.find_to(from, to.into())
+    }
+
+    /// Finds the cheapest route from a room to a room on any shard.
+    pub fn find_to(mut self, from: RoomName, to: RouteRoom) -> Option<Vec<RouteStep>> {
+        let from = RouteRoom::from(from);
+        if from == to {
+            return Some(Vec::new());
+        }
+
+        let mut reached = Reached::new();
+        let mut open = BinaryHeap::new();
+        let mut searched = 0;
+
+        reached.insert(from.clone(), (0.0, None));
+        open.push(Candidate {
+            cost: 0.0,
+            room: from,
+        });
+
+        while let Some(Candidate { cost, room }) = open.pop() {
+            if room == to {
+                return Some(Self::collect_route(&reached, to));
+            }
+            if cost > reached[&room].0 {
+                // already searched at a lower cost
+                continue;
+            }
+            searched += 1;
+            if searched > self.max_rooms {
+                break;
+            }
+
+            let mut edges: Vec<(RouteRoom, Via)> = (self.exits)(&room)
+                .into_iter()
+                .filter_map(|exit| Some((room.across(exit)?, Via::Exit(exit))))
+                .collect();
+            if let Some(destinations) = self.portals.get(&room) {
+                edges.extend(destinations.iter().map(|dest| (dest.clone(), Via::Portal)));
+            }
+
+            for (next, via) in edges {
+                let enter_cost = if next == to {
+                    1.0
+                } else {
+                    (self.room_cost)(&next)
+                };
+                if !enter_cost.is_finite() || enter_cost < 0.0 {
+                    continue;
+                }
+                let next_cost = cost + enter_cost;
+                let better = match reached.get(&next) {
+                    Some(&(known, _)) => next_cost < known,
+                    None => true,
+                };
+                if better {
+                    reached.insert(next.clone(), (next_cost, Some((room.clone(), via))));
+                    open.push(Candidate {
+                        cost: next_cost,
+                        room: next,
+                    });
+                }
+            }
+        }
+
+        None
+    }
+
+    fn collect_route(reached: &Reached, to: RouteRoom) -> Vec<RouteStep> {
+        let mut route = Vec::new();
+        let mut room = to;
+        while let Some((previous, via)) = &reached[&room].1 {
+            let previous = previous.clone();
+            route.push(RouteStep { room, via: *via });
+            room = previous;
+        }
+        route.reverse();
+        route
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{exits_within, Portal, RoutePlanner, RouteRoom, Via};
+    use crate::{
+        constants::ExitDirection,
+        local::{RoomName, WorldBounds},
+    };
+
+    fn name(s: &str) -> RoomName {
+        RoomName::new(s).unwrap()
+    }
+
+    fn mmo_exits() -> impl FnMut(&RouteRoom) -> Vec<ExitDirection> {
+        exits_within(WorldBounds::new(202))
+    }
+
+    #[test]
+    fn straight_route() {
+        let route = RoutePlanner::new(mmo_exits())
+            .find(name("W1N1"), name("E1N1"))
+            .unwrap();
+        let rooms: Vec<_> = route.iter().map(|s| s.room.room.to_string()).collect();
+        assert_eq!(rooms, ["W0N1", "E0N1", "E1N1"]);
+        assert!(route
+            .iter()
+            .all(|s| s.via == Via::Exit(ExitDirection::Right)));
+
+        assert_eq!(
+            RoutePlanner::new(mmo_exits()).find(name("W1N1"), name("W1N1")),
+            Some(vec![])
+        );
+    }
+
+    #[test]
+    fn avoids_blocked_rooms() {
+        let blocked = name("W0N1");
+        let route = RoutePlanner::new(mmo_exits())
+            .room_cost(|room| {
+                if room.room == blocked {
+                    f64::INFINITY
+                } else {
+                    1.0
+                }
+            })
+            .find(name("W1N1"), name("E0N1"))
+            .unwrap();
+        assert_eq!(route.len(), 4);
+        assert!(route.iter().all(|s| s.room.room != blocked));
+
+        let walled_in = RoutePlanner::new(mmo_exits())
+            .room_cost(|_| f64::INFINITY)
+            .find(name("W1N1"), name("E5N1"));
+        assert_eq!(walled_in, None);
+    }
+
+    #[test]
+    fn uses_portals() {
+        let to_other_shard = Portal::inter_shard(name("E2N0"), "shard1", name("E30N30"));
+        let goal = RouteRoom::new(Some("shard1".to_owned()), name("E30N31"));
+
+        let route = RoutePlanner::new(mmo_exits())
+            .portals(vec![to_other_shard])
+            .find_to(name("E0N0"), goal.clone())
+            .unwrap();
+        assert_eq!(route.len(), 4);
+        assert_eq!(route[2].via, Via::Portal);
+        assert_eq!(route[2].room.room, name("E30N30"));
+        assert_eq!(route[3].room, goal);
+    }
+
+    #[test]
+    fn stays_within_world_bounds() {
+        // W1N1 and W1S1 are the top and bottom left corners of a world 4
+        // rooms wide, so the route runs straight down the western edge
+        let route = RoutePlanner::new(exits_within(WorldBounds::new(4)))
+            .find(name("W1N1"), name("W1S1"))
+            .unwrap();
+        assert_eq!(route.len(), 3);
+        assert!(route
+            .iter()
+            .all(|s| s.via == Via::Exit(ExitDirection::Bottom)));
+
+        let outside =
+            RoutePlanner::new(exits_within(WorldBounds::new(4))).find(name("W1N1"), name("W2N1"));
+        assert_eq!(outside, None);
+    }
+
+    #[test]
+    fn only_uses_open_exits() {
+        // a wall between W1N1 and W0N1, which is only passable from the south
+        let walled = |room: &RouteRoom| {
+            let mut exits = mmo_exits()(room);
+            if room.room == name("W1N1") {
+                exits.retain(|&exit| exit != ExitDirection::Right);
+            }
+            if room.room == name("W0N1") {
+                exits.retain(|&exit| exit != ExitDirection::Left);
+            }
+            exits
+        };
+        let route = RoutePlanner::new(walled)
+            .find(name("W1N1"), name("W0N1"))
+            .unwrap();
+        let rooms: Vec<_> = route.iter().map(|s| s.room.room.to_string()).collect();
+        assert_eq!(rooms.len(), 3);
+        assert_eq!(rooms.last().unwrap(), "W0N1");
+        assert_ne!(route[0].via, Via::Exit(ExitDirection::Right));
+
+        let closed = RoutePlanner::new(|_: &RouteRoom| Vec::new()).find(name("W1N1"), name("W0N1"));
+        assert_eq!(closed, None);
+    }
+}
@@ -0,0 +1,118 @@
+use num_traits::FromPrimitive;
+
+use crate::{constants::Direction, objects::Step};
+
+/// Offset in x and y of one step in a direction.
+fn direction_offset(direction: Direction) -> (i32, i32) {
+    use Direction::*;
+
+    match direction {
+        Top => (0, -1),
+        TopRight => (1, -1),
+        Right => (1, 0),
+        BottomRight => (1, 1),
+        Bottom => (0, 1),
+        BottomLeft => (-1, 1),
+        Left => (-1, 0),
+        TopLeft => (-1, -1),
+    }
+}
+
+/// Serializes a path in the same format as `Room.serializePath`, without
+/// calling into JavaScript.
+///
+/// The result holds the first step's coordinates followed by each step's
+/// direction, so the other steps' coordinates are assumed to follow from
+/// those.
+///
+/// # Example
+///
+/// ```
+/// use screeps::{
+///     local::{deserialize_path, serialize_path},
+///     Direction, Step,
+/// };
+///
+/// let path = vec![
+///     Step { x: 5, y: 10, dx: 0, dy: -1, direction: Direction::Top },
+///     Step { x: 6, y: 9, dx: 1, dy: -1, direction: Direction::TopRight },
+/// ];
+/// assert_eq!(serialize_path(&path), "051012");
+/// assert_eq!(deserialize_path("051012"), path);
+/// ```
+pub fn serialize_path(path: &[Step]) -> String {
+    let first = match path.first() {
+        Some(step) => step,
+        None => return String::new(),
+    };
+    let mut result = format!("{:02}{:02}", first.x, first.y);
+    for step in path {
+        result.push(char::from(b'0' + step.direction as u8));
+    }
+    result
+}
+
+/// Deserializes a path in the format produced by `Room.serializePath` or
+/// [`serialize_path`], without calling into JavaScript.
+///
+/// Like `Room.deserializePath`, this stops at the first character which isn't
+/// a direction, returning the steps read so far.
+pub fn deserialize_path(path: &str) -> Vec<Step> {
+    let mut result = Vec::new();
+
+    let coord = |range| path.get(range).and_then(|s: &str| s.parse::<u32>().ok());
+    let (mut x, mut y) = match (coord(0..2), coord(2..4)) {
+        (Some(x), Some(y)) => (x, y),
+        _ => return result,
+    };
+
+    for (i, ch) in path[4..].chars().enumerate() {
+        let direction = match ch.to_digit(10).and_then(Direction::from_u32) {
+            Some(direction) => direction,
+            None => break,
+        };
+        let (dx, dy) = direction_offset(direction);
+        if i > 0 {
+            x = (x as i32 + dx) as u32;
+            y = (y as i32 + dy) as u32;
+        }
+        result.push(Step {
+            x,
+            y,
+            dx,
+            dy,
+            direction,
+        });
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::{deserialize_path, serialize_path};
+    use crate::constants::Direction;
+
+    #[test]
+    fn round_trip() {
+        let serialized = "2531345678";
+        let path = deserialize_path(serialized);
+
+        let coords: Vec<_> = path.iter().map(|s| (s.x, s.y)).collect();
+        assert_eq!(
+            coords,
+            [(25, 31), (26, 32), (26, 33), (25, 34), (24, 34), (23, 33)]
+        );
+        assert_eq!(path[0].direction, Direction::Right);
+        assert_eq!(serialize_path(&path), serialized);
+    }
+
+    #[test]
+    fn malformed() {
+        assert!(deserialize_path("").is_empty());
+        assert!(deserialize_path("12").is_empty());
+        assert!(deserialize_path("ab123").is_empty());
+        assert_eq!(deserialize_path("0101339").len(), 2);
+        assert_eq!(serialize_path(&[]), "");
+    }
+}
@@ -0,0 +1,297 @@
+//! Base layout "stamps": groups of structures placed relative to an anchor.
+//!
+//! A [`Stamp`] can be rotated and mirrored, checked against a room's terrain
+//! with [`Stamp::fits`], and turned into a list of construction sites ordered
+//! by the controller level each one becomes available at with
+//! [`Stamp::placements`].
+use crate::{
+    constants::{StructureType, Terrain},
+    local::{LocalRoomTerrain, Position},
+};
+
+/// One structure in a [`Stamp`], at an offset from the stamp's anchor.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct StampEntry {
+    pub dx: i8,
+    pub dy: i8,
+    pub structure_type: StructureType,
+}
+
+/// A clockwise rotation, as seen in the game client.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Rotation {
+    Rotate0,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+}
+
+/// A structure to build as part of a stamp, and the lowest controller level it
+/// can be built at.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Placement {
+    pub pos: Position,
+    pub structure_type: StructureType,
+    pub rcl: u32,
+}
+
+/// A layout of structures, as offsets from an anchor tile.
+///
+/// The order entries are added in is the order structures of the same type are
+/// built in, so list the most important ones first.
+///
+/// # Example
+///
+/// ```
+/// use screeps::{
+///     local::stamps::{Rotation, Stamp},
+///     StructureType,
+/// };
+///
+/// // a spawn with a road on each side
+/// let stamp = Stamp::new()
+///     .with(0, 0, StructureType::Spawn)
+///     .with(0, -1, StructureType::Road)
+///     .with(1, 0, StructureType::Road)
+///     .with(0, 1, StructureType::Road)
+///     .with(-1, 0, StructureType::Road);
+///
+/// assert_eq!(stamp.rotated(Rotation::Rotate90).entries().len(), 5);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stamp {
+    entries: Vec<StampEntry>,
+}
+
+impl Stamp {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a structure at an offset from the anchor.
+    pub fn with(mut self, dx: i8, dy: i8, structure_type: StructureType) -> Self {
+        self.entries.push(StampEntry {
+            dx,
+            dy,
+            structure_type,
+        });
+        self
+    }
+
+    pub fn entries(&self) -> &[StampEntry] {
+        &self.entries
+    }
+
+    fn map_offsets<F>(&self, f: F) -> Stamp
+    where
+        F: Fn(i8, i8) -> (i8, i8),
+    {
+        Stamp {
+            entries: self
+                .entries
+                .iter()
+                .map(|e| {
+                    let (dx, dy) = f(e.dx, e.dy);
+                    StampEntry { dx, dy, ..*e }
+                })
+                .collect(),
+        }
+    }
+
+    /// Rotates the stamp clockwise around its anchor.
+    pub fn rotated(&self, rotation: Rotation) -> Stamp {
+        // y points down in room coordinates, so (1, 0) rotated clockwise is
+        // (0, 1)
+        match rotation {
+            Rotation::Rotate0 => self.clone(),
+            Rotation::Rotate90 => self.map_offsets(|dx, dy| (-dy, dx)),
+            Rotation::Rotate180 => self.map_offsets(|dx, dy| (-dx, -dy)),
+            Rotation::Rotate270 => self.map_offsets(|dx, dy| (dy, -dx)),
+        }
+    }
+
+    /// Mirrors the stamp left-to-right, around the anchor's column.
+    pub fn mirrored_horizontally(&self) -> Stamp {
+        self.map_offsets(|dx, dy| (-dx, dy))
+    }
+
+    /// Mirrors the stamp top-to-bottom, around the anchor's row.
+    pub fn mirrored_vertically(&self) -> Stamp {
+        self.map_offsets(|dx, dy| (dx, -dy))
+    }
+
+    /// Finds the position of an entry placed with the stamp anchored at
+    /// `anchor`, if it's a tile structures can be built on (not on the room's
+    /// edge).
+    fn entry_pos(anchor: Position, entry: &StampEntry) -> Option<Position> {
+        let x = anchor.x() as i32 + i32::from(entry.dx);
+        let y = anchor.y() as i32 + i32::from(entry.dy);
+        if (1..=48).contains(&x) && (1..=48).contains(&y) {
+            Some(Position::new(x as u32, y as u32, anchor.room_name()))
+        } else {
+            None
+        }
+    }
+
+    /// Whether every structure in the stamp can be built with the stamp
+    /// anchored at `anchor`.
+    ///
+    /// Structures can't be built on the room's edge tiles or on walls, except
+    /// for roads, which can be built on walls as tunnels. This only checks
+    /// terrain, not existing structures or sources.
+    pub fn fits(&self, terrain: &LocalRoomTerrain, anchor: Position) -> bool {
+        self.entries
+            .iter()
+            .all(|entry| match Self::entry_pos(anchor, entry) {
+                Some(pos) => {
+                    entry.structure_type == StructureType::Road
+                        || terrain.get(pos.x() as u8, pos.y() as u8) != Terrain::Wall
+                }
+                None => false,
+            })
+    }
+
+    /// Lists the structures to build with the stamp anchored at `anchor`,
+    /// ordered by the controller level each one can first be built at.
+    ///
+    /// The nth structure of each type in the stamp becomes available at the
+    /// first level whose `CONTROLLER_STRUCTURES` allows n of that type.
+    /// Structures which don't fit within the room, or which are over the
+    /// limit even at level 8, are left out. Structures available at the same
+    /// level keep the stamp's order.
+    ///
+    /// This assumes the stamp's structures are the only ones of their types in
+    /// the room.
+    pub fn placements(&self, anchor: Position) -> Vec<Placement> {
+        let mut counts: Vec<(StructureType, u32)> = Vec::new();
+        let mut placements = Vec::with_capacity(self.entries.len());
+
+        for entry in &self.entries {
+            let count = match counts
+                .iter_mut()
+                .find(|(ty, _)| *ty == entry.structure_type)
+            {
+                Some((_, count)) => {
+                    *count += 1;
+                    *count
+                }
+                None => {
+                    counts.push((entry.structure_type, 1));
+                    1
+                }
+            };
+            let rcl = (1..=8).find(|&rcl| entry.structure_type.controller_structures(rcl) >= count);
+
+            if let (Some(pos), Some(rcl)) = (Self::entry_pos(anchor, entry), rcl) {
+                placements.push(Placement {
+                    pos,
+                    structure_type: entry.structure_type,
+                    rcl,
+                });
+            }
+        }
+
+        placements.sort_by_key(|p| p.rcl);
+        placements
+    }
+
+    /// Structures from [`Stamp::placements`] which can be built at the given
+    /// controller level.
+    pub fn placements_for_rcl(&self, anchor: Position, rcl: u32) -> Vec<Placement> {
+        let mut placements = self.placements(anchor);
+        placements.retain(|p| p.rcl <= rcl);
+        placements
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Rotation, Stamp};
+    use crate::{
+        constants::StructureType,
+        local::{LocalRoomTerrain, Position, RoomName},
+    };
+
+    fn anchor(x: u32, y: u32) -> Position {
+        Position::new(x, y, RoomName::new("W1N1").unwrap())
+    }
+
+    fn offsets(stamp: &Stamp) -> Vec<(i8, i8)> {
+        stamp.entries().iter().map(|e| (e.dx, e.dy)).collect()
+    }
+
+    #[test]
+    fn rotation_and_mirroring() {
+        let stamp = Stamp::new()
+            .with(1, 0, StructureType::Road)
+            .with(2, 1, StructureType::Road);
+
+        assert_eq!(offsets(&stamp.rotated(Rotation::Rotate0)), [(1, 0), (2, 1)]);
+        assert_eq!(
+            offsets(&stamp.rotated(Rotation::Rotate90)),
+            [(0, 1), (-1, 2)]
+        );
+        assert_eq!(
+            offsets(&stamp.rotated(Rotation::Rotate180)),
+            [(-1, 0), (-2, -1)]
+        );
+        assert_eq!(
+            offsets(&stamp.rotated(Rotation::Rotate270)),
+            [(0, -1), (1, -2)]
+        );
+        assert_eq!(
+            stamp
+                .rotated(Rotation::Rotate90)
+                .rotated(Rotation::Rotate270),
+            stamp
+        );
+        assert_eq!(offsets(&stamp.mirrored_horizontally()), [(-1, 0), (-2, 1)]);
+        assert_eq!(offsets(&stamp.mirrored_vertically()), [(1, 0), (2, -1)]);
+    }
+
+    #[test]
+    fn fits_checks_walls_and_edges() {
+        let mut bits = Box::new([0; 2500]);
+        // wall at (11, 10)
+        bits[10 * 50 + 11] = 1;
+        let terrain = LocalRoomTerrain::new_from_bits(bits);
+
+        let road = Stamp::new().with(1, 0, StructureType::Road);
+        let tower = Stamp::new().with(1, 0, StructureType::Tower);
+
+        assert!(road.fits(&terrain, anchor(10, 10)));
+        assert!(!tower.fits(&terrain, anchor(10, 10)));
+        assert!(tower.fits(&terrain, anchor(10, 11)));
+        assert!(!tower.fits(&terrain, anchor(48, 11)));
+    }
+
+    #[test]
+    fn placements_ordered_by_rcl() {
+        let stamp = Stamp::new()
+            .with(0, 0, StructureType::Tower)
+            .with(1, 0, StructureType::Tower)
+            .with(2, 0, StructureType::Spawn)
+            .with(3, 0, StructureType::Road)
+            .with(4, 0, StructureType::Observer)
+            .with(5, 0, StructureType::Observer);
+
+        let placements = stamp.placements(anchor(10, 10));
+        let summary: Vec<_> = placements
+            .iter()
+            .map(|p| (p.pos.x(), p.structure_type, p.rcl))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (12, StructureType::Spawn, 1),
+                (13, StructureType::Road, 1),
+                (10, StructureType::Tower, 3),
+                (11, StructureType::Tower, 5),
+                (14, StructureType::Observer, 8),
+            ]
+        );
+
+        let early = stamp.placements_for_rcl(anchor(10, 10), 3);
+        assert_eq!(early.len(), 3);
+    }
+}
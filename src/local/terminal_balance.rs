@@ -0,0 +1,361 @@
+//! Planning `StructureTerminal.send` transfers which even out resources
+//! between owned rooms.
+//!
+//! [`TerminalBalancer`] takes a [`RoomStock`] for each room with a terminal
+//! and a target amount for each resource, and plans transfers from rooms
+//! above the target to rooms below it. Each terminal sends at most once, and
+//! only if it's off cooldown and has the energy to pay for the transfer.
+//!
+//! ```
+//! use screeps::{
+//!     local::terminal_balance::{RoomStock, TerminalBalancer},
+//!     ResourceType, RoomName,
+//! };
+//!
+//! let name = |s: &str| RoomName::new(s).unwrap();
+//!
+//! let mut rich = RoomStock::new(name("W1N1"));
+//! rich.set(ResourceType::Hydrogen, 10_000, 8_000);
+//! rich.set(ResourceType::Energy, 50_000, 20_000);
+//! let poor = RoomStock::new(name("W3N1"));
+//!
+//! let transfers = TerminalBalancer::new()
+//!     .target(ResourceType::Hydrogen, 5_000)
+//!     .plan(&[rich, poor]);
+//!
+//! assert_eq!(transfers.len(), 1);
+//! assert_eq!(transfers[0].from, name("W1N1"));
+//! assert_eq!(transfers[0].amount, 5_000);
+//! assert_eq!(transfers[0].energy_cost, 323);
+//! ```
+use std::{cmp::Reverse, collections::HashMap};
+
+use crate::{
+    constants::{ResourceType, ReturnCode, TERMINAL_CAPACITY, TERMINAL_MIN_SEND},
+    game,
+    objects::{HasCooldown, HasStore, Room},
+};
+
+use super::RoomName;
+
+/// Energy a terminal spends to send `amount` of a resource between two
+/// rooms, like `Game.market.calcTransactionCost`.
+///
+/// This uses the straight-line distance between the rooms, so it can
+/// overestimate the cost between rooms on opposite edges of the world.
+pub fn transfer_cost(amount: u32, from: RoomName, to: RoomName) -> u32 {
+    let distance = (from.x_coord() - to.x_coord())
+        .abs()
+        .max((from.y_coord() - to.y_coord()).abs());
+    (f64::from(amount) * (1.0 - (-f64::from(distance) / 30.0).exp())).ceil() as u32
+}
+
+/// The resources one room has available for balancing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RoomStock {
+    pub room: RoomName,
+    /// Amount of each resource in the room's storage and terminal combined.
+    pub totals: HashMap<ResourceType, u32>,
+    /// Amount of each resource in the terminal, which can be sent.
+    pub terminal: HashMap<ResourceType, u32>,
+    /// Free space in the terminal, for receiving.
+    pub terminal_free: u32,
+    /// Ticks until the terminal can send again.
+    pub cooldown: u32,
+}
+
+impl RoomStock {
+    /// An empty room with an empty terminal which is off cooldown.
+    pub fn new(room: RoomName) -> Self {
+        RoomStock {
+            room,
+            totals: HashMap::new(),
+            terminal: HashMap::new(),
+            terminal_free: TERMINAL_CAPACITY,
+            cooldown: 0,
+        }
+    }
+
+    /// Sets the amount of a resource in the room, and how much of that is
+    /// in the terminal.
+    ///
+    /// This doesn't update [`RoomStock::terminal_free`].
+    pub fn set(&mut self, resource: ResourceType, total: u32, in_terminal: u32) {
+        self.totals.insert(resource, total);
+        self.terminal.insert(resource, in_terminal);
+    }
+
+    /// Reads a visible room's storage and terminal, or returns `None` if it
+    /// has no terminal.
+    pub fn scan(room: &Room) -> Option<RoomStock> {
+        let terminal = room.terminal()?;
+        let mut stock = RoomStock::new(room.name());
+        for resource in terminal.store_types() {
+            let amount = terminal.store_of(resource);
+            stock.terminal.insert(resource, amount);
+            *stock.totals.entry(resource).or_insert(0) += amount;
+        }
+        if let Some(storage) = room.storage() {
+            for resource in storage.store_types() {
+                *stock.totals.entry(resource).or_insert(0) += storage.store_of(resource);
+            }
+        }
+        stock.terminal_free = terminal.store_free_capacity(None).max(0) as u32;
+        stock.cooldown = terminal.cooldown();
+        Some(stock)
+    }
+
+    fn total(&self, resource: ResourceType) -> u32 {
+        self.totals.get(&resource).cloned().unwrap_or(0)
+    }
+
+    fn in_terminal(&self, resource: ResourceType) -> u32 {
+        self.terminal.get(&resource).cloned().unwrap_or(0)
+    }
+
+    fn remove(&mut self, resource: ResourceType, amount: u32) {
+        *self.totals.entry(resource).or_insert(0) -= amount;
+        *self.terminal.entry(resource).or_insert(0) -= amount;
+        self.terminal_free += amount;
+    }
+
+    fn add(&mut self, resource: ResourceType, amount: u32) {
+        *self.totals.entry(resource).or_insert(0) += amount;
+        *self.terminal.entry(resource).or_insert(0) += amount;
+        self.terminal_free -= amount;
+    }
+}
+
+/// A planned `StructureTerminal.send`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TransferOrder {
+    pub from: RoomName,
+    pub to: RoomName,
+    pub resource: ResourceType,
+    pub amount: u32,
+    /// Energy the sending terminal pays for the transfer, as estimated by
+    /// [`transfer_cost`].
+    pub energy_cost: u32,
+}
+
+impl TransferOrder {
+    /// Sends the transfer from the terminal in `from`.
+    ///
+    /// Returns `ReturnCode::NotFound` if `from` isn't visible or has no
+    /// terminal.
+    pub fn execute(&self) -> ReturnCode {
+        match game::rooms::get(self.from).and_then(|room| room.terminal()) {
+            Some(terminal) => terminal.send(self.resource, self.amount, self.to, None),
+            None => ReturnCode::NotFound,
+        }
+    }
+}
+
+/// Plans terminal transfers to bring each room up to a target amount of
+/// each resource.
+///
+/// Resources are balanced in the order their targets were added, so earlier
+/// resources get first use of terminals which are off cooldown. For each
+/// room below a target, starting with the furthest below, the closest room
+/// with a surplus sends as much as it can spare.
+#[derive(Clone, Debug)]
+pub struct TerminalBalancer {
+    targets: Vec<(ResourceType, u32)>,
+    min_transfer: u32,
+}
+
+impl Default for TerminalBalancer {
+    fn default() -> Self {
+        TerminalBalancer {
+            targets: Vec::new(),
+            min_transfer: TERMINAL_MIN_SEND,
+        }
+    }
+}
+
+impl TerminalBalancer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the amount of `resource` each room should have, replacing any
+    /// earlier target for it.
+    pub fn target(mut self, resource: ResourceType, amount: u32) -> Self {
+        match self.targets.iter_mut().find(|(r, _)| *r == resource) {
+            Some(target) => target.1 = amount,
+            None => self.targets.push((resource, amount)),
+        }
+        self
+    }
+
+    /// Sets the smallest transfer worth making - default
+    /// `TERMINAL_MIN_SEND`.
+    pub fn min_transfer(mut self, amount: u32) -> Self {
+        self.min_transfer = amount;
+        self
+    }
+
+    /// Plans transfers between `rooms` for this tick.
+    pub fn plan(&self, rooms: &[RoomStock]) -> Vec<TransferOrder> {
+        let mut rooms = rooms.to_vec();
+        let mut sent = vec![false; rooms.len()];
+        let mut orders = Vec::new();
+
+        for &(resource, target) in &self.targets {
+            let mut skipped = vec![false; rooms.len()];
+            loop {
+                let receiver = (0..rooms.len())
+                    .filter(|&i| !skipped[i] && rooms[i].total(resource) < target)
+                    .max_by_key(|&i| (target - rooms[i].total(resource), Reverse(i)));
+                let receiver = match receiver {
+                    Some(i) => i,
+                    None => break,
+                };
+                let to = rooms[receiver].room;
+                let wanted =
+                    (target - rooms[receiver].total(resource)).min(rooms[receiver].terminal_free);
+
+                let sender = (0..rooms.len())
+                    .filter(|&i| i != receiver && !sent[i] && rooms[i].cooldown == 0)
+                    .filter_map(|i| {
+                        let spare = rooms[i]
+                            .total(resource)
+                            .saturating_sub(target)
+                            .min(rooms[i].in_terminal(resource));
+                        let amount = self.affordable(&rooms[i], resource, spare.min(wanted), to);
+                        if amount >= self.min_transfer {
+                            Some((i, amount))
+                        } else {
+                            None
+                        }
+                    })
+                    .min_by_key(|&(i, amount)| {
+                        (transfer_cost(1000, rooms[i].room, to), Reverse(amount))
+                    });
+                let (sender, amount) = match sender {
+                    Some(found) => found,
+                    None => {
+                        skipped[receiver] = true;
+                        continue;
+                    }
+                };
+
+                let from = rooms[sender].room;
+                let energy_cost = transfer_cost(amount, from, to);
+                rooms[sender].remove(resource, amount);
+                rooms[sender].remove(ResourceType::Energy, energy_cost);
+                rooms[receiver].add(resource, amount);
+                sent[sender] = true;
+                orders.push(TransferOrder {
+                    from,
+                    to,
+                    resource,
+                    amount,
+                    energy_cost,
+                });
+            }
+        }
+
+        orders
+    }
+
+    /// The most of `amount` which `room`'s terminal has the energy to send to
+    /// `to`.
+    fn affordable(
+        &self,
+        room: &RoomStock,
+        resource: ResourceType,
+        amount: u32,
+        to: RoomName,
+    ) -> u32 {
+        let energy = room.in_terminal(ResourceType::Energy);
+        let needed = |amount| {
+            let cost = transfer_cost(amount, room.room, to);
+            if resource == ResourceType::Energy {
+                amount + cost
+            } else {
+                cost
+            }
+        };
+        if needed(amount) <= energy {
+            return amount;
+        }
+        // the cost is nearly proportional to the amount, so scaling down gets
+        // within a few units of the most that fits
+        let mut fits = (f64::from(amount) * f64::from(energy) / f64::from(needed(amount))) as u32;
+        while fits > 0 && needed(fits) > energy {
+            fits -= 1;
+        }
+        fits
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{transfer_cost, RoomStock, TerminalBalancer};
+    use crate::{constants::ResourceType, local::RoomName};
+
+    fn name(s: &str) -> RoomName {
+        RoomName::new(s).unwrap()
+    }
+
+    fn stock(room: &str, resource: ResourceType, amount: u32, energy: u32) -> RoomStock {
+        let mut stock = RoomStock::new(name(room));
+        stock.set(resource, amount, amount);
+        stock.set(ResourceType::Energy, energy, energy);
+        stock
+    }
+
+    #[test]
+    fn costs() {
+        assert_eq!(transfer_cost(1000, name("W1N1"), name("W1N1")), 0);
+        assert_eq!(transfer_cost(1000, name("W1N1"), name("W2N3")), 65);
+        assert_eq!(transfer_cost(1000, name("W1N1"), name("W0N1")), 33);
+    }
+
+    #[test]
+    fn picks_closest_sender_once() {
+        let rooms = [
+            stock("W10N1", ResourceType::Oxygen, 20_000, 10_000),
+            stock("W2N1", ResourceType::Oxygen, 20_000, 10_000),
+            stock("W1N1", ResourceType::Oxygen, 0, 10_000),
+            stock("W1N2", ResourceType::Oxygen, 1_000, 10_000),
+        ];
+        let orders = TerminalBalancer::new()
+            .target(ResourceType::Oxygen, 5_000)
+            .plan(&rooms);
+
+        assert_eq!(orders.len(), 2);
+        assert_eq!(orders[0].to, name("W1N1"));
+        assert_eq!(orders[0].from, name("W2N1"));
+        assert_eq!(orders[0].amount, 5_000);
+        assert_eq!(orders[1].to, name("W1N2"));
+        assert_eq!(orders[1].from, name("W10N1"));
+        assert_eq!(orders[1].amount, 4_000);
+    }
+
+    #[test]
+    fn respects_cooldown_and_energy() {
+        let mut cooling = stock("W2N1", ResourceType::Oxygen, 20_000, 10_000);
+        cooling.cooldown = 3;
+        let poor = stock("W3N1", ResourceType::Oxygen, 20_000, 50);
+        let rooms = [cooling, poor, stock("W1N1", ResourceType::Oxygen, 0, 0)];
+        let orders = TerminalBalancer::new()
+            .target(ResourceType::Oxygen, 5_000)
+            .plan(&rooms);
+
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].from, name("W3N1"));
+        assert!(orders[0].energy_cost <= 50);
+        assert!(orders[0].amount < 5_000);
+
+        let mut rich = RoomStock::new(name("W2N1"));
+        rich.set(ResourceType::Energy, 100_000, 1_000);
+        let energy_rooms = [rich, RoomStock::new(name("W1N1"))];
+        let orders = TerminalBalancer::new()
+            .target(ResourceType::Energy, 50_000)
+            .plan(&energy_rooms);
+        assert_eq!(orders.len(), 1);
+        assert!(orders[0].amount + orders[0].energy_cost <= 1_000);
+    }
+}
@@ -0,0 +1,120 @@
+use std::fmt;
+
+use crate::{
+    constants::{Terrain, TerrainMask},
+    objects::RoomTerrain,
+};
+
+/// A room's terrain, stored in Rust.
+///
+/// This is the same data as [`RoomTerrain`], fetched once with
+/// [`RoomTerrain::get_raw_buffer_to_array`] so that each tile can be looked up
+/// without calling into JavaScript.
+#[derive(Clone)]
+pub struct LocalRoomTerrain {
+    /// Raw terrain masks, indexed by `y * 50 + x` like the game's raw buffer.
+    bits: Box<[u8; 2500]>,
+}
+
+#[inline]
+fn xy_to_terrain_idx(x: u8, y: u8) -> usize {
+    (y as usize) * 50 + (x as usize)
+}
+
+impl LocalRoomTerrain {
+    /// Creates terrain from a buffer in the format returned by
+    /// [`RoomTerrain::get_raw_buffer`], where each tile is a `TERRAIN_MASK_*`
+    /// value at index `y * 50 + x`.
+    pub fn new_from_bits(bits: Box<[u8; 2500]>) -> Self {
+        LocalRoomTerrain { bits }
+    }
+
+    /// Gets the terrain at the given room coordinates.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` or `y` is 50 or greater.
+    #[inline]
+    pub fn get(&self, x: u8, y: u8) -> Terrain {
+        self.get_mask(x, y).into()
+    }
+
+    /// Gets the `TERRAIN_MASK_*` bits at the given room coordinates.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` or `y` is 50 or greater.
+    #[inline]
+    pub fn get_mask(&self, x: u8, y: u8) -> TerrainMask {
+        assert!(
+            x < 50 && y < 50,
+            "out of bounds terrain position: {}, {}",
+            x,
+            y
+        );
+        TerrainMask::from_bits_truncate(self.bits[xy_to_terrain_idx(x, y)])
+    }
+
+    /// Gets the raw terrain buffer, indexed by `y * 50 + x`.
+    pub fn get_bits(&self) -> &[u8; 2500] {
+        &self.bits
+    }
+}
+
+impl From<RoomTerrain> for LocalRoomTerrain {
+    fn from(terrain: RoomTerrain) -> Self {
+        let mut bits = Box::new([0; 2500]);
+        terrain
+            .get_raw_buffer_to_array(&mut bits)
+            .expect("expected RoomTerrain.getRawBuffer to succeed with 2500-byte buffer");
+        LocalRoomTerrain { bits }
+    }
+}
+
+impl fmt::Debug for LocalRoomTerrain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalRoomTerrain").finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::LocalRoomTerrain;
+    use crate::constants::{Terrain, TerrainMask};
+
+    #[test]
+    fn get_uses_raw_buffer_layout() {
+        let mut bits = Box::new([0; 2500]);
+        bits[3 * 50 + 2] = 1;
+        bits[2 * 50 + 3] = 2;
+        // walls inside lava/swamp-flagged tiles are still walls
+        bits[49 * 50 + 49] = 1 | 2 | 4;
+        let terrain = LocalRoomTerrain::new_from_bits(bits);
+
+        assert_eq!(terrain.get(2, 3), Terrain::Wall);
+        assert_eq!(terrain.get(3, 2), Terrain::Swamp);
+        assert_eq!(terrain.get(0, 0), Terrain::Plain);
+        assert_eq!(terrain.get(49, 49), Terrain::Wall);
+    }
+
+    #[test]
+    fn masks() {
+        let mut bits = Box::new([0; 2500]);
+        bits[1] = 1 | 2 | 4;
+        bits[2] = 2 | 4;
+        let terrain = LocalRoomTerrain::new_from_bits(bits);
+
+        let wall = terrain.get_mask(1, 0);
+        assert!(wall.contains(TerrainMask::WALL | TerrainMask::LAVA));
+        assert!(!wall.is_walkable());
+        assert!(!wall.is_swamp());
+
+        let swamp = terrain.get_mask(2, 0);
+        assert!(swamp.is_walkable());
+        assert!(swamp.is_swamp());
+        assert_eq!(Terrain::from(swamp), Terrain::Swamp);
+
+        assert!(terrain.get_mask(0, 0).is_empty());
+        assert_eq!(TerrainMask::from(Terrain::Wall), TerrainMask::WALL);
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::{hash_map::Entry, BTreeMap, HashMap};
+
+use crate::{
+    game,
+    raw_memory::{SegmentError, SegmentManager, MAX_SEGMENT_SIZE},
+};
+
+use super::{LocalRoomTerrain, RoomName};
+
+/// Characters used to encode two tiles' terrain masks each.
+const ENCODING: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes terrain as 1250 characters, with each character holding the three
+/// mask bits of two tiles.
+fn encode_terrain(terrain: &LocalRoomTerrain) -> String {
+    terrain
+        .get_bits()
+        .chunks(2)
+        .map(|pair| {
+            let value = (pair[0] & 0b111) | ((pair[1] & 0b111) << 3);
+            char::from(ENCODING[value as usize])
+        })
+        .collect()
+}
+
+/// Decodes terrain encoded by [`encode_terrain`], or returns `None` if the
+/// string isn't valid.
+fn decode_terrain(encoded: &str) -> Option<LocalRoomTerrain> {
+    if encoded.len() != 1250 {
+        return None;
+    }
+    let mut bits = Box::new([0; 2500]);
+    for (i, byte) in encoded.bytes().enumerate() {
+        let value = ENCODING.iter().position(|&c| c == byte)? as u8;
+        bits[i * 2] = value & 0b111;
+        bits[i * 2 + 1] = value >> 3;
+    }
+    Some(LocalRoomTerrain::new_from_bits(bits))
+}
+
+/// Terrain for any number of rooms, fetched from the game at most once each.
+///
+/// Terrain never changes, so a cache kept for the lifetime of the global can
+/// serve every lookup after the first. It can also be stored in a memory
+/// segment, to survive global resets.
+///
+/// # Example
+///
+/// ```no_run
+/// use screeps::{local::TerrainCache, RoomName, Terrain};
+///
+/// let mut cache = TerrainCache::new();
+/// let room = RoomName::new("W1N1").unwrap();
+///
+/// // calls `Game.map.getRoomTerrain` the first time only
+/// let terrain = cache.get(room).unwrap();
+/// assert_ne!(terrain.get(25, 25), Terrain::Wall);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct TerrainCache {
+    rooms: HashMap<RoomName, LocalRoomTerrain>,
+}
+
+impl TerrainCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets a room's terrain, fetching it with `Game.map.getRoomTerrain` if
+    /// it isn't cached yet.
+    ///
+    /// Returns `None` if the room doesn't exist in this world.
+    pub fn get(&mut self, room_name: RoomName) -> Option<&LocalRoomTerrain> {
+        match self.rooms.entry(room_name) {
+            Entry::Occupied(entry) => Some(entry.into_mut()),
+            Entry::Vacant(entry) => {
+                let terrain = game::map::get_room_terrain(room_name)?;
+                Some(entry.insert(terrain.into()))
+            }
+        }
+    }
+
+    /// Gets a room's terrain only if it's already cached.
+    pub fn get_cached(&self, room_name: RoomName) -> Option<&LocalRoomTerrain> {
+        self.rooms.get(&room_name)
+    }
+
+    /// Adds terrain fetched some other way.
+    pub fn insert(&mut self, room_name: RoomName, terrain: LocalRoomTerrain) {
+        self.rooms.insert(room_name, terrain);
+    }
+
+    /// Number of rooms cached.
+    pub fn len(&self) -> usize {
+        self.rooms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rooms.is_empty()
+    }
+
+    /// Adds the rooms stored by [`TerrainCache::save_to_segment`] in memory
+    /// segment `id`, including a save which hasn't been flushed yet.
+    ///
+    /// Returns `false` if the segment doesn't hold terrain, or isn't active
+    /// this tick, in which case `segments` requests it for the next tick.
+    pub fn load_from_segment(&mut self, segments: &mut SegmentManager, id: u32) -> bool {
+        let stored: BTreeMap<RoomName, String> = match segments.get(id) {
+            Ok(Some(stored)) => stored,
+            _ => return false,
+        };
+        for (room_name, encoded) in stored {
+            if let Some(terrain) = decode_terrain(&encoded) {
+                self.rooms.insert(room_name, terrain);
+            }
+        }
+        true
+    }
+
+    /// Stores cached rooms in memory segment `id`, written by the next
+    /// [`SegmentManager::flush`].
+    ///
+    /// Each room takes up about 1.3KB, so a segment holds around 75 rooms.
+    /// Rooms past that are left out, in [`RoomName`] order. Returns the
+    /// number of rooms stored.
+    pub fn save_to_segment(
+        &self,
+        segments: &mut SegmentManager,
+        id: u32,
+    ) -> Result<usize, SegmentError> {
+        let mut room_names: Vec<RoomName> = self.rooms.keys().cloned().collect();
+        room_names.sort();
+
+        let mut stored = BTreeMap::new();
+        // `{}` plus, for each room, `"W1N1":"<terrain>",`
+        let mut size = 2;
+        for room_name in room_names {
+            let encoded = encode_terrain(&self.rooms[&room_name]);
+            let entry_size = room_name.to_string().len() + encoded.len() + 6;
+            if size + entry_size > MAX_SEGMENT_SIZE {
+                break;
+            }
+            size += entry_size;
+            stored.insert(room_name, encoded);
+        }
+
+        segments.set(id, &stored)?;
+        Ok(stored.len())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{decode_terrain, encode_terrain, TerrainCache};
+    use crate::{
+        local::{LocalRoomTerrain, RoomName},
+        raw_memory::SegmentManager,
+    };
+
+    #[test]
+    fn encoding_round_trip() {
+        let mut bits = Box::new([0; 2500]);
+        for (i, bit) in bits.iter_mut().enumerate() {
+            *bit = ((i % 7) as u8 + 1) & 0b111;
+        }
+        let terrain = LocalRoomTerrain::new_from_bits(bits);
+
+        let encoded = encode_terrain(&terrain);
+        assert_eq!(encoded.len(), 1250);
+        let decoded = decode_terrain(&encoded).unwrap();
+        assert_eq!(decoded.get_bits()[..], terrain.get_bits()[..]);
+
+        assert!(decode_terrain("AAAA").is_none());
+        assert!(decode_terrain(&"!".repeat(1250)).is_none());
+    }
+
+    #[test]
+    fn save_to_segment_leaves_out_rooms_past_the_size_limit() {
+        let mut cache = TerrainCache::new();
+        for x in 0..100 {
+            let room_name = RoomName::new(&format!("W{}N1", x)).unwrap();
+            let mut bits = Box::new([0; 2500]);
+            bits[x] = 1;
+            cache.insert(room_name, LocalRoomTerrain::new_from_bits(bits));
+        }
+
+        let mut segments = SegmentManager::new();
+        let stored = cache.save_to_segment(&mut segments, 3).unwrap();
+        assert!(stored > 70 && stored < 100, "{}", stored);
+        assert!(segments.has_pending_write(3));
+    }
+}
@@ -0,0 +1,86 @@
+use super::{RoomName, HALF_WORLD_SIZE};
+
+/// The rooms which exist in a world, from the size reported by
+/// `Game.map.getWorldSize`.
+///
+/// Worlds are square and centered on the corner between `W0N0`, `E0N0`,
+/// `W0S0` and `E0S0`, so a world of size 22 holds the rooms from `W10N10` to
+/// `E10S10`. The official MMO shards are 202 rooms wide, from `W100N100` to
+/// `E100S100`.
+///
+/// # Example
+///
+/// ```
+/// use screeps::{local::WorldBounds, RoomName};
+///
+/// let bounds = WorldBounds::new(22);
+/// assert!(bounds.contains(RoomName::new("W10N10").unwrap()));
+/// assert!(!bounds.contains(RoomName::new("E11S0").unwrap()));
+/// assert_eq!(bounds.rooms().count(), 22 * 22);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct WorldBounds {
+    half_size: i32,
+}
+
+impl WorldBounds {
+    /// Creates bounds for a world `size` rooms wide and tall.
+    ///
+    /// Sizes too large to be represented by [`RoomName`] are clamped to the
+    /// largest representable world.
+    pub fn new(size: u32) -> Self {
+        let half_size = (size / 2).min(HALF_WORLD_SIZE as u32) as i32;
+        WorldBounds { half_size }
+    }
+
+    /// The number of rooms along each side of the world.
+    pub fn size(&self) -> u32 {
+        self.half_size as u32 * 2
+    }
+
+    /// Whether the room exists in this world.
+    pub fn contains(&self, room_name: RoomName) -> bool {
+        let range = -self.half_size..self.half_size;
+        range.contains(&room_name.x_coord()) && range.contains(&room_name.y_coord())
+    }
+
+    /// Iterates over every room in the world, in [`RoomName`]'s ordering: row
+    /// by row from north to south, and west to east within each row.
+    pub fn rooms(&self) -> impl Iterator<Item = RoomName> {
+        let half_size = self.half_size;
+        (-half_size..half_size).flat_map(move |y| {
+            (-half_size..half_size).map(move |x| {
+                RoomName::from_coords(x, y).expect("expected world bounds to be valid room names")
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::WorldBounds;
+    use crate::local::RoomName;
+
+    #[test]
+    fn mmo_bounds() {
+        let bounds = WorldBounds::new(202);
+        let name = |s: &str| RoomName::new(s).unwrap();
+
+        assert_eq!(bounds.size(), 202);
+        assert!(bounds.contains(name("W100N100")));
+        assert!(bounds.contains(name("E100S100")));
+        assert!(bounds.contains(name("E0N0")));
+        assert!(!bounds.contains(name("W101N0")));
+        assert!(!bounds.contains(name("E0S101")));
+    }
+
+    #[test]
+    fn rooms_in_order() {
+        let rooms: Vec<String> = WorldBounds::new(2).rooms().map(|r| r.to_string()).collect();
+        assert_eq!(rooms, ["W0N0", "E0N0", "W0S0", "E0S0"]);
+
+        let bounds = WorldBounds::new(22);
+        assert!(bounds.rooms().all(|room| bounds.contains(room)));
+        assert_eq!(bounds.rooms().count(), 484);
+    }
+}
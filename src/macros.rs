@@ -265,7 +265,11 @@ macro_rules! creep_simple_generic_action {
                 where
                     T: ?Sized + $trait,
                 {
-                    js_unwrap!(@{self.as_ref()}.$js_name(@{target.as_ref()}))
+                    record_intent!(
+                        self.as_ref(),
+                        stringify!($js_name),
+                        js_unwrap!(@{self.as_ref()}.$js_name(@{target.as_ref()}))
+                    )
                 }
             )*
         }
@@ -303,13 +307,53 @@ macro_rules! creep_simple_concrete_action {
         impl $struct_name {
             $(
                 $vis fn $method(&self, target: &$type) -> ReturnCode {
-                    js_unwrap!(@{self.as_ref()}.$js_name(@{target.as_ref()}))
+                    record_intent!(
+                        self.as_ref(),
+                        stringify!($js_name),
+                        js_unwrap!(@{self.as_ref()}.$js_name(@{target.as_ref()}))
+                    )
                 }
             )*
         }
     )
 }
 
+/// Records an action's intent for the `check-intent-conflicts` and
+/// `track-intents` features, or its failure for the `log-failed-intents`
+/// feature.
+///
+/// Evaluates to the action's `ReturnCode`. When either of the first two
+/// features is enabled and the action returned `ReturnCode::Ok`, the intent is
+/// recorded with `intents::record`. When `log-failed-intents` is enabled and
+/// the action returned anything else, it's logged with `intents::log_failure`.
+/// Otherwise this does nothing.
+///
+/// Macro Syntax:
+/// ```ignore
+/// record_intent!(self.as_ref(), "jsMethodName", js_unwrap!(...))
+/// ```
+macro_rules! record_intent {
+    ($obj:expr, $js_name:expr, $result:expr) => {{
+        let result: ReturnCode = $result;
+        #[cfg(any(feature = "check-intent-conflicts", feature = "track-intents"))]
+        {
+            if result == ReturnCode::Ok {
+                let kind = $js_name
+                    .parse()
+                    .expect("expected action to have a matching IntentKind");
+                $crate::intents::record($obj, kind);
+            }
+        }
+        #[cfg(feature = "log-failed-intents")]
+        {
+            if result != ReturnCode::Ok {
+                $crate::intents::log_failure($obj, $js_name, result);
+            }
+        }
+        result
+    }};
+}
+
 /// Declares an item with a doc attribute computed by some macro expression.
 /// This allows documentation to be dynamically generated based on input.
 /// Necessary to work around https://github.com/rust-lang/rust/issues/52607.
@@ -50,13 +50,23 @@
 //! from those objects will also result in a `MemoryReference` which instead
 //! points at the root of this object's memory.
 //!
+//! # Cleaning up
+//! The game doesn't remove the memory of creeps, flags, spawns or power creeps
+//! when they stop existing. [`cleanup`] deletes all of these entries, and
+//! [`cleanup_creeps_with`] gives a chance to look at each dead creep's memory
+//! first. Run these before spawning any creeps in the tick: see
+//! [`cleanup_creeps_with`].
+//!
 //! [`root`]: crate::memory::root
+//! [`cleanup`]: crate::memory::cleanup
+//! [`cleanup_creeps_with`]: crate::memory::cleanup_creeps_with
 
-use std::fmt;
+use std::{collections::HashSet, fmt};
 
 use stdweb::{JsSerialize, Reference, Value};
 
 use crate::{
+    game,
     traits::{TryFrom, TryInto},
     ConversionError,
 };
@@ -332,3 +342,92 @@ impl TryFrom<Value> for MemoryReference {
 pub fn root() -> MemoryReference {
     js_unwrap!(Memory)
 }
+
+/// Deletes entries in `Memory[section]` whose keys aren't in `live`, calling
+/// `on_delete` with each entry's key and memory before it's deleted.
+fn cleanup_section<F>(section: &str, live: Vec<String>, mut on_delete: F) -> Vec<String>
+where
+    F: FnMut(&str, MemoryReference),
+{
+    let section = match root().dict(section) {
+        Ok(Some(section)) => section,
+        _ => return Vec::new(),
+    };
+    let live: HashSet<String> = live.into_iter().collect();
+
+    let mut deleted = Vec::new();
+    for key in section.keys() {
+        if live.contains(&key) {
+            continue;
+        }
+        if let Ok(Some(memory)) = section.dict(&key) {
+            on_delete(&key, memory);
+        }
+        section.del(&key);
+        deleted.push(key);
+    }
+    deleted
+}
+
+/// Deletes the memory of creeps which no longer exist, returning their names.
+///
+/// `on_delete` is called with each creep's name and memory before it's
+/// deleted, for instance to update counts of creeps in each role.
+///
+/// Creeps which a spawn is spawning are kept. However, memory passed to
+/// [`StructureSpawn::spawn_creep_with_options`][spawn] is stored straight
+/// away, while the creep only appears in `Game.creeps` on the next tick, so
+/// call this before spawning creeps each tick, or their memory will be
+/// deleted.
+///
+/// [spawn]: crate::objects::StructureSpawn::spawn_creep_with_options
+pub fn cleanup_creeps_with<F>(on_delete: F) -> Vec<String>
+where
+    F: FnMut(&str, MemoryReference),
+{
+    let mut live = game::creeps::keys();
+    live.extend(
+        game::spawns::values()
+            .into_iter()
+            .filter_map(|spawn| spawn.spawning())
+            .map(|spawning| spawning.name()),
+    );
+    cleanup_section("creeps", live, on_delete)
+}
+
+/// Deletes the memory of creeps which no longer exist, returning their names.
+///
+/// Like [`cleanup_creeps_with`], this should be called before spawning creeps
+/// each tick.
+pub fn cleanup_creeps() -> Vec<String> {
+    cleanup_creeps_with(|_, _| {})
+}
+
+/// Deletes the memory of flags which no longer exist, returning their names.
+pub fn cleanup_flags() -> Vec<String> {
+    cleanup_section("flags", game::flags::keys(), |_, _| {})
+}
+
+/// Deletes the memory of spawns which no longer exist, returning their names.
+pub fn cleanup_spawns() -> Vec<String> {
+    cleanup_section("spawns", game::spawns::keys(), |_, _| {})
+}
+
+/// Deletes the memory of power creeps which no longer exist on the account,
+/// returning their names.
+pub fn cleanup_power_creeps() -> Vec<String> {
+    cleanup_section("powerCreeps", game::power_creeps::keys(), |_, _| {})
+}
+
+/// Deletes the memory of all creeps, flags, spawns and power creeps which no
+/// longer exist.
+///
+/// Use [`cleanup_creeps_with`] to inspect the memory of dead creeps before
+/// it's deleted. Like it, this should be called before spawning creeps each
+/// tick.
+pub fn cleanup() {
+    cleanup_creeps();
+    cleanup_flags();
+    cleanup_spawns();
+    cleanup_power_creeps();
+}
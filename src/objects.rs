@@ -17,7 +17,7 @@ use stdweb::{Reference, ReferenceType, Value};
 use stdweb_derive::ReferenceType;
 
 use crate::{
-    constants::{ResourceType, ReturnCode, StructureType},
+    constants::{EffectType, ResourceType, ReturnCode, StructureType},
     local::{ObjectId, Position, RawObjectId},
     traits::{IntoExpectedType, TryFrom, TryInto},
     ConversionError,
@@ -30,11 +30,13 @@ mod structure;
 pub use self::{
     creep_shared::{MoveToOptions, SharedCreepProperties},
     impls::{
-        AttackEvent, AttackType, Bodypart, BuildEvent, CircleStyle, Effect, Event, EventType,
-        ExitEvent, FindOptions, FontStyle, HarvestEvent, HealEvent, HealType, LineDrawStyle,
+        AttackEvent, AttackType, Bodypart, BuildEvent, CircleStyle, Effect, EnergyStructure,
+        EnergyStructureOrder, Event, EventIter, EventKind, EventType, ExitEvent, FindOptions,
+        FontStyle, HarvestEvent, HealEvent, HealType, HeatmapMode, HeatmapStyle, LineDrawStyle,
         LineStyle, LookResult, ObjectDestroyedEvent, Path, PolyStyle, PortalDestination,
-        PositionedLookResult, RectStyle, RepairEvent, Reservation, ReserveControllerEvent,
-        RoomVisual, Sign, SpawnOptions, Step, TextAlign, TextStyle, UpgradeControllerEvent, Visual,
+        PositionedLookResult, ProgressBar, RectStyle, RepairEvent, Reservation,
+        ReserveControllerEvent, RoomVisual, Sign, SpawnOptions, Step, Table, TextAlign, TextPanel,
+        TextStyle, UpgradeControllerEvent, UsePower, Visual, Widget, ROOM_VISUAL_SIZE_LIMIT,
     },
     structure::Structure,
 };
@@ -230,6 +232,13 @@ pub unsafe trait RoomObjectProperties: AsRef<Reference> + HasPosition {
     fn effects(&self) -> Vec<Effect> {
         js_unwrap!(@{self.as_ref()}.effects || [])
     }
+
+    /// The effect of the given type currently applied to this object, if any.
+    fn effect(&self, effect_type: EffectType) -> Option<Effect> {
+        self.effects()
+            .into_iter()
+            .find(|effect| effect.effect == effect_type)
+    }
 }
 
 /// Trait representing things that are both `RoomObjectProperties` and `Sized`.
@@ -271,7 +280,11 @@ pub unsafe trait StructureProperties: RoomObjectProperties + HasId {
         js_unwrap!(__structure_type_str_to_num(@{self.as_ref()}.structureType))
     }
     fn destroy(&self) -> ReturnCode {
-        js_unwrap!(@{self.as_ref()}.destroy())
+        record_intent!(
+            self.as_ref(),
+            "destroy",
+            js_unwrap!(@{self.as_ref()}.destroy())
+        )
     }
     fn is_active(&self) -> bool {
         js_unwrap!(@{self.as_ref()}.isActive())
@@ -279,7 +292,11 @@ pub unsafe trait StructureProperties: RoomObjectProperties + HasId {
     /// Usable on either owned structures or neutral structures in owned rooms,
     /// returns `ReturnCode::NotOwner` otherwise.
     fn notify_when_attacked(&self, notify_when_attacked: bool) -> ReturnCode {
-        js_unwrap!(@{self.as_ref()}.notifyWhenAttacked(@{notify_when_attacked}))
+        record_intent!(
+            self.as_ref(),
+            "notifyWhenAttacked",
+            js_unwrap!(@{self.as_ref()}.notifyWhenAttacked(@{notify_when_attacked}))
+        )
     }
     fn as_structure(self) -> Structure
     where
@@ -393,7 +410,18 @@ pub unsafe trait HasStore: RoomObjectProperties {
 ///
 /// The reference returned from `AsRef<Reference>::as_ref` must be able to be
 /// used by a spawner to create a new creep.
-pub unsafe trait HasEnergyForSpawn: HasStore {}
+pub unsafe trait HasEnergyForSpawn: HasStore {
+    /// The most energy this structure can hold, at its current controller
+    /// level.
+    fn energy_capacity(&self) -> u32 {
+        self.store_capacity(Some(ResourceType::Energy))
+    }
+
+    /// Energy needed to fill this structure.
+    fn energy_free_capacity(&self) -> u32 {
+        self.store_free_capacity(Some(ResourceType::Energy)).max(0) as u32
+    }
+}
 
 /// Trait for objects which have to cooldown.
 ///
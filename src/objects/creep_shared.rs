@@ -12,7 +12,6 @@ use crate::{
     },
     pathfinder::{CostMatrix, SearchResults, SingleRoomCostResult},
     traits::TryInto,
-    ConversionError,
 };
 
 /// Trait for all wrappers over Screeps JavaScript objects that are creeps or
@@ -30,18 +29,34 @@ pub unsafe trait SharedCreepProperties: RoomObjectProperties {
     fn drop(&self, ty: ResourceType, amount: Option<u32>) -> ReturnCode {
         match amount {
             Some(v) => {
-                js_unwrap!(@{self.as_ref()}.drop(__resource_type_num_to_str(@{ty as u32}), @{v}))
+                record_intent!(
+                    self.as_ref(),
+                    "drop",
+                    js_unwrap!(@{self.as_ref()}.drop(__resource_type_num_to_str(@{ty as u32}), @{v}))
+                )
             }
-            None => js_unwrap!(@{self.as_ref()}.drop(__resource_type_num_to_str(@{ty as u32}))),
+            None => record_intent!(
+                self.as_ref(),
+                "drop",
+                js_unwrap!(@{self.as_ref()}.drop(__resource_type_num_to_str(@{ty as u32})))
+            ),
         }
     }
 
     fn move_direction(&self, dir: Direction) -> ReturnCode {
-        js_unwrap!(@{self.as_ref()}.move(@{dir as u32}))
+        record_intent!(
+            self.as_ref(),
+            "move",
+            js_unwrap!(@{self.as_ref()}.move(@{dir as u32}))
+        )
     }
 
     fn move_to_xy(&self, x: u32, y: u32) -> ReturnCode {
-        js_unwrap!(@{self.as_ref()}.moveTo(@{x}, @{y}))
+        record_intent!(
+            self.as_ref(),
+            "move",
+            js_unwrap!(@{self.as_ref()}.moveTo(@{x}, @{y}))
+        )
     }
 
     fn move_to_xy_with_options<'a, F>(
@@ -59,7 +74,11 @@ pub unsafe trait SharedCreepProperties: RoomObjectProperties {
 
     fn move_to<T: ?Sized + HasPosition>(&self, target: &T) -> ReturnCode {
         let p = target.pos();
-        js_unwrap!(@{self.as_ref()}.moveTo(pos_from_packed(@{p.packed_repr()})))
+        record_intent!(
+            self.as_ref(),
+            "move",
+            js_unwrap!(@{self.as_ref()}.moveTo(pos_from_packed(@{p.packed_repr()})))
+        )
     }
 
     fn move_to_with_options<'a, F, T>(
@@ -117,44 +136,60 @@ pub unsafe trait SharedCreepProperties: RoomObjectProperties {
             unsafe { mem::transmute(callback_type_erased) };
 
         let rp = target.pos();
-        js!(
-            let cb = @{callback_lifetime_erased};
-            let res = @{ self.as_ref() }.moveTo(
-                pos_from_packed(@{rp.packed_repr()}),
-                {
-                    reusePath: @{reuse_path},
-                    serializeMemory: @{serialize_memory},
-                    noPathFinding: @{no_path_finding},
-                    visualizePathStyle: @{visualize_path_style},
-                    ignoreCreeps: @{ignore_creeps},
-                    ignoreDestructibleStructures: @{ignore_destructible_structures},
-                    costCallback: cb,
-                    maxOps: @{max_ops},
-                    heuristicWeight: @{heuristic_weight},
-                    serialize: @{serialize},
-                    maxRooms: @{max_rooms},
-                    range: @{range},
-                    plainCost: @{plain_cost},
-                    swampCost: @{swamp_cost}
-                }
-            );
-            cb.drop();
-            return res;
+        record_intent!(
+            self.as_ref(),
+            "move",
+            js!(
+                let cb = @{callback_lifetime_erased};
+                let res = @{ self.as_ref() }.moveTo(
+                    pos_from_packed(@{rp.packed_repr()}),
+                    {
+                        reusePath: @{reuse_path},
+                        serializeMemory: @{serialize_memory},
+                        noPathFinding: @{no_path_finding},
+                        visualizePathStyle: @{visualize_path_style},
+                        ignoreCreeps: @{ignore_creeps},
+                        ignoreDestructibleStructures: @{ignore_destructible_structures},
+                        costCallback: cb,
+                        maxOps: @{max_ops},
+                        heuristicWeight: @{heuristic_weight},
+                        serialize: @{serialize},
+                        maxRooms: @{max_rooms},
+                        range: @{range},
+                        plainCost: @{plain_cost},
+                        swampCost: @{swamp_cost}
+                    }
+                );
+                cb.drop();
+                return res;
+            )
+            .try_into()
+            .expect("expected return code from moveTo")
         )
-        .try_into()
-        .expect("expected return code from moveTo")
     }
 
     fn move_by_path_serialized(&self, path: &str) -> ReturnCode {
-        js_unwrap!(@{self.as_ref()}.moveByPath(@{path}))
+        record_intent!(
+            self.as_ref(),
+            "move",
+            js_unwrap!(@{self.as_ref()}.moveByPath(@{path}))
+        )
     }
 
     fn move_by_path_steps(&self, path: &[Step]) -> ReturnCode {
-        js_unwrap!(@{self.as_ref()}.moveByPath(@{path}))
+        record_intent!(
+            self.as_ref(),
+            "move",
+            js_unwrap!(@{self.as_ref()}.moveByPath(@{path}))
+        )
     }
 
     fn move_by_path_search_result(&self, path: &SearchResults) -> ReturnCode {
-        js_unwrap!(@{self.as_ref()}.moveByPath(@{path.opaque_path()}))
+        record_intent!(
+            self.as_ref(),
+            "move",
+            js_unwrap!(@{self.as_ref()}.moveByPath(@{path.opaque_path()}))
+        )
     }
 
     fn memory(&self) -> MemoryReference {
@@ -170,7 +205,11 @@ pub unsafe trait SharedCreepProperties: RoomObjectProperties {
     }
 
     fn notify_when_attacked(&self, notify_when_attacked: bool) -> ReturnCode {
-        js_unwrap!(@{self.as_ref()}.notifyWhenAttacked(@{notify_when_attacked}))
+        record_intent!(
+            self.as_ref(),
+            "notifyWhenAttacked",
+            js_unwrap!(@{self.as_ref()}.notifyWhenAttacked(@{notify_when_attacked}))
+        )
     }
 
     fn owner_name(&self) -> String {
@@ -178,11 +217,19 @@ pub unsafe trait SharedCreepProperties: RoomObjectProperties {
     }
 
     fn pickup(&self, target: &Resource) -> ReturnCode {
-        js_unwrap!(@{self.as_ref()}.pickup(@{target.as_ref()}))
+        record_intent!(
+            self.as_ref(),
+            "pickup",
+            js_unwrap!(@{self.as_ref()}.pickup(@{target.as_ref()}))
+        )
     }
 
     fn say(&self, msg: &str, public: bool) -> ReturnCode {
-        js_unwrap!(@{self.as_ref()}.say(@{msg}, @{public}))
+        record_intent!(
+            self.as_ref(),
+            "say",
+            js_unwrap!(@{self.as_ref()}.say(@{msg}, @{public}))
+        )
     }
 
     fn saying(&self) -> String {
@@ -190,63 +237,100 @@ pub unsafe trait SharedCreepProperties: RoomObjectProperties {
     }
 
     fn suicide(&self) -> ReturnCode {
-        js_unwrap!(@{self.as_ref()}.suicide())
+        record_intent!(
+            self.as_ref(),
+            "suicide",
+            js_unwrap!(@{self.as_ref()}.suicide())
+        )
     }
 
-    fn ticks_to_live(&self) -> Result<u32, ConversionError> {
-        let ttl = crate::traits::TryInto::try_into(js!(
-            return @{self.as_ref()}.ticksToLive;
-        ))?;
-
-        Ok(ttl)
+    /// Ticks until this creep dies of old age, or `None` if it doesn't age
+    /// yet.
+    ///
+    /// Creeps don't age while they're still being spawned; see
+    /// [`Creep::spawning`].
+    fn ticks_to_live(&self) -> Option<u32> {
+        js_unwrap!(@{self.as_ref()}.ticksToLive)
     }
 
     fn transfer_amount<T>(&self, target: &T, ty: ResourceType, amount: u32) -> ReturnCode
     where
         T: ?Sized + Transferable,
     {
-        js_unwrap!(@{self.as_ref()}.transfer(
-            @{target.as_ref()},
-            __resource_type_num_to_str(@{ty as u32}),
-            @{amount}
-        ))
+        record_intent!(
+            self.as_ref(),
+            "transfer",
+            js_unwrap!(@{self.as_ref()}.transfer(
+                @{target.as_ref()},
+                __resource_type_num_to_str(@{ty as u32}),
+                @{amount}
+            ))
+        )
     }
 
     fn transfer_all<T>(&self, target: &T, ty: ResourceType) -> ReturnCode
     where
         T: ?Sized + Transferable,
     {
-        js_unwrap!(@{self.as_ref()}.transfer(
-            @{target.as_ref()},
-            __resource_type_num_to_str(@{ty as u32})
-        ))
+        record_intent!(
+            self.as_ref(),
+            "transfer",
+            js_unwrap!(@{self.as_ref()}.transfer(
+                @{target.as_ref()},
+                __resource_type_num_to_str(@{ty as u32})
+            ))
+        )
     }
 
     fn withdraw_amount<T>(&self, target: &T, ty: ResourceType, amount: u32) -> ReturnCode
     where
         T: ?Sized + Withdrawable,
     {
-        js_unwrap!(@{self.as_ref()}.withdraw(
-            @{target.as_ref()},
-            __resource_type_num_to_str(@{ty as u32}),
-            @{amount}
-        ))
+        record_intent!(
+            self.as_ref(),
+            "withdraw",
+            js_unwrap!(@{self.as_ref()}.withdraw(
+                @{target.as_ref()},
+                __resource_type_num_to_str(@{ty as u32}),
+                @{amount}
+            ))
+        )
     }
 
     fn withdraw_all<T>(&self, target: &T, ty: ResourceType) -> ReturnCode
     where
         T: ?Sized + Withdrawable,
     {
-        js_unwrap!(@{self.as_ref()}.withdraw(
-            @{target.as_ref()},
-            __resource_type_num_to_str(@{ty as u32})
-        ))
+        record_intent!(
+            self.as_ref(),
+            "withdraw",
+            js_unwrap!(@{self.as_ref()}.withdraw(
+                @{target.as_ref()},
+                __resource_type_num_to_str(@{ty as u32})
+            ))
+        )
     }
 }
 
 unsafe impl SharedCreepProperties for Creep {}
 unsafe impl SharedCreepProperties for PowerCreep {}
 
+/// Options for [`SharedCreepProperties::move_to_with_options`], translating
+/// the options object accepted by `Creep.moveTo`.
+///
+/// # Example
+///
+/// ```no_run
+/// use screeps::{Creep, MoveToOptions, SharedCreepProperties, StructureController};
+///
+/// # let creep: Creep = unimplemented!();
+/// # let controller: StructureController = unimplemented!();
+/// // stop within range 3 of the controller, reusing the path for 20 ticks
+/// creep.move_to_with_options(
+///     &controller,
+///     MoveToOptions::new().range(3).reuse_path(20),
+/// );
+/// ```
 pub struct MoveToOptions<'a, F>
 where
     F: FnMut(RoomName, CostMatrix<'a>) -> SingleRoomCostResult<'a>,
@@ -363,6 +447,7 @@ where
         self
     }
 
+    /// Sets how close the creep needs to get to the target - default `0`.
     pub fn range(mut self, k: u32) -> Self {
         self.find_options.range = k;
         self
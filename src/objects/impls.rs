@@ -9,6 +9,7 @@ mod resource;
 mod room;
 mod room_terrain;
 mod room_visual;
+mod room_visual_widgets;
 mod ruin;
 mod source;
 mod structure_controller;
@@ -26,20 +27,24 @@ mod structure_rampart;
 mod structure_spawn;
 mod structure_terminal;
 mod structure_tower;
+mod structure_wall;
 mod tombstone;
 
 pub use self::{
     creep::Bodypart,
+    power_creep::UsePower,
     room::{
-        AttackEvent, AttackType, BuildEvent, Effect, Event, EventType, ExitEvent, FindOptions,
-        HarvestEvent, HealEvent, HealType, LookResult, ObjectDestroyedEvent, Path,
-        PositionedLookResult, RepairEvent, ReserveControllerEvent, Step, UpgradeControllerEvent,
+        AttackEvent, AttackType, BuildEvent, Effect, Event, EventIter, EventKind, EventType,
+        ExitEvent, FindOptions, HarvestEvent, HealEvent, HealType, LookResult,
+        ObjectDestroyedEvent, Path, PositionedLookResult, RepairEvent, ReserveControllerEvent,
+        Step, UpgradeControllerEvent,
     },
     room_visual::{
-        CircleStyle, FontStyle, LineDrawStyle, LineStyle, PolyStyle, RectStyle, RoomVisual,
-        TextAlign, TextStyle, Visual,
+        CircleStyle, FontStyle, HeatmapMode, HeatmapStyle, LineDrawStyle, LineStyle, PolyStyle,
+        RectStyle, RoomVisual, TextAlign, TextStyle, Visual, ROOM_VISUAL_SIZE_LIMIT,
     },
+    room_visual_widgets::{ProgressBar, Table, TextPanel, Widget},
     structure_controller::{Reservation, Sign},
     structure_portal::PortalDestination,
-    structure_spawn::SpawnOptions,
+    structure_spawn::{EnergyStructure, EnergyStructureOrder, SpawnOptions},
 };
@@ -1,9 +1,12 @@
+use std::collections::HashMap;
+
+use enum_iterator::IntoEnumIterator;
 use stdweb::Value;
 
 use crate::{
     constants::{Part, ResourceType, ReturnCode},
     objects::{
-        Attackable, ConstructionSite, Creep, Harvestable, SharedCreepProperties,
+        Attackable, ConstructionSite, Creep, Harvestable, HasPosition, SharedCreepProperties,
         StructureController, StructureProperties, Transferable, Withdrawable,
     },
     traits::TryFrom,
@@ -37,57 +40,132 @@ impl Creep {
     }
 
     pub fn sign_controller(&self, target: &StructureController, text: &str) -> ReturnCode {
-        js_unwrap!(@{self.as_ref()}.signController(@{target.as_ref()}, @{text}))
+        record_intent!(
+            self.as_ref(),
+            "signController",
+            js_unwrap!(@{self.as_ref()}.signController(@{target.as_ref()}, @{text}))
+        )
     }
 
     pub fn get_active_bodyparts(&self, ty: Part) -> u32 {
         js_unwrap!(@{self.as_ref()}.getActiveBodyparts(__part_num_to_str(@{ty as u32})))
     }
 
+    /// Whether this creep has at least one part of the given type with hits
+    /// left.
+    ///
+    /// Stops at the first matching part, so this is cheaper than checking
+    /// `get_active_bodyparts(ty) > 0`.
+    pub fn has_active_bodypart(&self, ty: Part) -> bool {
+        js_unwrap! {
+            const ty = __part_num_to_str(@{ty as u32});
+            return @{self.as_ref()}.body.some((part) => part.hits > 0 && part.type === ty);
+        }
+    }
+
+    /// Harvests a `Source`, `Mineral` or `Deposit`, first checking that this
+    /// creep has an active `WORK` part and is next to the target.
+    ///
+    /// Returns [`ReturnCode::NoBodypart`] or [`ReturnCode::NotInRange`]
+    /// without issuing the intent when either check fails. Use
+    /// [`Creep::harvest`] to skip the checks.
+    pub fn harvest_checked<T>(&self, target: &T) -> ReturnCode
+    where
+        T: ?Sized + Harvestable,
+    {
+        // checked in the same order as the engine's `Creep.harvest`
+        if !self.has_active_bodypart(Part::Work) {
+            return ReturnCode::NoBodypart;
+        }
+        if !self.pos().is_near_to(target) {
+            return ReturnCode::NotInRange;
+        }
+        self.harvest(target)
+    }
+
+    /// Counts the active parts of every type, in a single pass over the body.
+    ///
+    /// Types this creep has no active parts of are left out.
+    pub fn active_bodypart_counts(&self) -> HashMap<Part, u32> {
+        let counts: Vec<u32> = js_unwrap! {
+            const counts = [0, 0, 0, 0, 0, 0, 0, 0];
+            for (const part of @{self.as_ref()}.body) {
+                if (part.hits > 0) {
+                    counts[__part_str_to_num(part.type)] += 1;
+                }
+            }
+            return counts;
+        };
+        Part::into_enum_iter()
+            .zip(counts)
+            .filter(|&(_, count)| count > 0)
+            .collect()
+    }
+
     pub fn ranged_mass_attack(&self) -> ReturnCode {
-        js_unwrap!(@{self.as_ref()}.rangedMassAttack())
+        record_intent!(
+            self.as_ref(),
+            "rangedMassAttack",
+            js_unwrap!(@{self.as_ref()}.rangedMassAttack())
+        )
     }
 
     pub fn transfer_amount<T>(&self, target: &T, ty: ResourceType, amount: u32) -> ReturnCode
     where
         T: ?Sized + Transferable,
     {
-        js_unwrap!(@{self.as_ref()}.transfer(
-            @{target.as_ref()},
-            __resource_type_num_to_str(@{ty as u32}),
-            @{amount}
-        ))
+        record_intent!(
+            self.as_ref(),
+            "transfer",
+            js_unwrap!(@{self.as_ref()}.transfer(
+                @{target.as_ref()},
+                __resource_type_num_to_str(@{ty as u32}),
+                @{amount}
+            ))
+        )
     }
 
     pub fn transfer_all<T>(&self, target: &T, ty: ResourceType) -> ReturnCode
     where
         T: ?Sized + Transferable,
     {
-        js_unwrap!(@{self.as_ref()}.transfer(
-            @{target.as_ref()},
-            __resource_type_num_to_str(@{ty as u32})
-        ))
+        record_intent!(
+            self.as_ref(),
+            "transfer",
+            js_unwrap!(@{self.as_ref()}.transfer(
+                @{target.as_ref()},
+                __resource_type_num_to_str(@{ty as u32})
+            ))
+        )
     }
 
     pub fn withdraw_amount<T>(&self, target: &T, ty: ResourceType, amount: u32) -> ReturnCode
     where
         T: ?Sized + Withdrawable,
     {
-        js_unwrap!(@{self.as_ref()}.withdraw(
-            @{target.as_ref()},
-            __resource_type_num_to_str(@{ty as u32}),
-            @{amount}
-        ))
+        record_intent!(
+            self.as_ref(),
+            "withdraw",
+            js_unwrap!(@{self.as_ref()}.withdraw(
+                @{target.as_ref()},
+                __resource_type_num_to_str(@{ty as u32}),
+                @{amount}
+            ))
+        )
     }
 
     pub fn withdraw_all<T>(&self, target: &T, ty: ResourceType) -> ReturnCode
     where
         T: ?Sized + Withdrawable,
     {
-        js_unwrap!(@{self.as_ref()}.withdraw(
-            @{target.as_ref()},
-            __resource_type_num_to_str(@{ty as u32})
-        ))
+        record_intent!(
+            self.as_ref(),
+            "withdraw",
+            js_unwrap!(@{self.as_ref()}.withdraw(
+                @{target.as_ref()},
+                __resource_type_num_to_str(@{ty as u32})
+            ))
+        )
     }
 }
 
@@ -1,4 +1,9 @@
-use crate::{local::RoomName, objects::Nuke};
+use crate::{
+    constants::nuke_damage,
+    game,
+    local::RoomName,
+    objects::{HasPosition, Nuke},
+};
 
 simple_accessors! {
     impl Nuke {
@@ -7,3 +12,25 @@ simple_accessors! {
         pub fn time_to_land() -> u32 = timeToLand;
     }
 }
+
+impl Nuke {
+    /// Game tick this nuke lands on.
+    pub fn land_tick(&self) -> u32 {
+        game::time() + self.time_to_land()
+    }
+
+    /// Damage this nuke will do to a structure at `pos`.
+    ///
+    /// See [`nuke_damage`] for details.
+    pub fn damage_at<T>(&self, pos: &T) -> u32
+    where
+        T: ?Sized + HasPosition,
+    {
+        let target = pos.pos();
+        let landing = self.pos();
+        if target.room_name() != landing.room_name() {
+            return 0;
+        }
+        nuke_damage(landing.get_range_to(&target))
+    }
+}
@@ -1,12 +1,116 @@
 use crate::{
     constants::{PowerCreepClass, PowerType, ReturnCode},
     objects::{
-        AccountPowerCreep, PowerCreep, RoomObjectProperties, StructureController,
-        StructurePowerSpawn, StructureProperties,
+        AccountPowerCreep, HasPosition, Mineral, PowerCreep, RoomObjectProperties, Source,
+        Structure, StructureController, StructureFactory, StructureLab, StructureObserver,
+        StructurePowerSpawn, StructureProperties, StructureSpawn, StructureStorage,
+        StructureTerminal, StructureTower,
     },
     traits::TryInto,
 };
 
+/// A power for a [`PowerCreep`] to use, along with its target if it has one.
+///
+/// Structure targets which can be one of several types are given as a
+/// [`Structure`], and checked by [`PowerCreep::use_power`].
+///
+/// ```no_run
+/// use screeps::{PowerCreep, StructureSpawn, UsePower};
+///
+/// # let power_creep: PowerCreep = unimplemented!();
+/// # let spawn: StructureSpawn = unimplemented!();
+/// power_creep.use_power(UsePower::OperateSpawn(&spawn));
+/// ```
+#[derive(Clone, Copy)]
+pub enum UsePower<'a> {
+    GenerateOps,
+    OperateSpawn(&'a StructureSpawn),
+    OperateTower(&'a StructureTower),
+    OperateStorage(&'a StructureStorage),
+    OperateLab(&'a StructureLab),
+    /// Targets a `StructureStorage`, `StructureTerminal`,
+    /// `StructureContainer` or `StructureFactory` to fill extensions from.
+    OperateExtension(&'a Structure),
+    OperateObserver(&'a StructureObserver),
+    OperateTerminal(&'a StructureTerminal),
+    DisruptSpawn(&'a StructureSpawn),
+    DisruptTower(&'a StructureTower),
+    DisruptSource(&'a Source),
+    Shield,
+    RegenSource(&'a Source),
+    RegenMineral(&'a Mineral),
+    DisruptTerminal(&'a StructureTerminal),
+    OperatePower(&'a StructurePowerSpawn),
+    /// Targets a `StructureRampart` or `StructureWall`.
+    Fortify(&'a Structure),
+    OperateController(&'a StructureController),
+    OperateFactory(&'a StructureFactory),
+}
+
+impl<'a> UsePower<'a> {
+    pub fn power_type(&self) -> PowerType {
+        match self {
+            UsePower::GenerateOps => PowerType::GenerateOps,
+            UsePower::OperateSpawn(_) => PowerType::OperateSpawn,
+            UsePower::OperateTower(_) => PowerType::OperateTower,
+            UsePower::OperateStorage(_) => PowerType::OperateStorage,
+            UsePower::OperateLab(_) => PowerType::OperateLab,
+            UsePower::OperateExtension(_) => PowerType::OperateExtension,
+            UsePower::OperateObserver(_) => PowerType::OperateObserver,
+            UsePower::OperateTerminal(_) => PowerType::OperateTerminal,
+            UsePower::DisruptSpawn(_) => PowerType::DisruptSpawn,
+            UsePower::DisruptTower(_) => PowerType::DisruptTower,
+            UsePower::DisruptSource(_) => PowerType::DisruptSource,
+            UsePower::Shield => PowerType::Shield,
+            UsePower::RegenSource(_) => PowerType::RegenSource,
+            UsePower::RegenMineral(_) => PowerType::RegenMineral,
+            UsePower::DisruptTerminal(_) => PowerType::DisruptTerminal,
+            UsePower::OperatePower(_) => PowerType::OperatePower,
+            UsePower::Fortify(_) => PowerType::Fortify,
+            UsePower::OperateController(_) => PowerType::OperateController,
+            UsePower::OperateFactory(_) => PowerType::OperateFactory,
+        }
+    }
+
+    /// The power's target, or `None` for powers without one.
+    pub fn target(&self) -> Option<&'a dyn RoomObjectProperties> {
+        match *self {
+            UsePower::GenerateOps | UsePower::Shield => None,
+            UsePower::OperateSpawn(v) | UsePower::DisruptSpawn(v) => Some(v),
+            UsePower::OperateTower(v) | UsePower::DisruptTower(v) => Some(v),
+            UsePower::OperateStorage(v) => Some(v),
+            UsePower::OperateLab(v) => Some(v),
+            UsePower::OperateExtension(v) | UsePower::Fortify(v) => Some(v),
+            UsePower::OperateObserver(v) => Some(v),
+            UsePower::OperateTerminal(v) | UsePower::DisruptTerminal(v) => Some(v),
+            UsePower::DisruptSource(v) | UsePower::RegenSource(v) => Some(v),
+            UsePower::RegenMineral(v) => Some(v),
+            UsePower::OperatePower(v) => Some(v),
+            UsePower::OperateController(v) => Some(v),
+            UsePower::OperateFactory(v) => Some(v),
+        }
+    }
+
+    /// Whether the target is a valid type for this power.
+    fn has_valid_target(&self) -> bool {
+        match self {
+            UsePower::OperateExtension(structure) => matches!(
+                structure,
+                Structure::Storage(_)
+                    | Structure::Terminal(_)
+                    | Structure::Container(_)
+                    | Structure::Factory(_)
+            ),
+            UsePower::Fortify(structure) => match structure {
+                Structure::Rampart(_) => true,
+                Structure::Wall(wall) => !wall.is_novice_wall(),
+                _ => false,
+            },
+            _ => true,
+        }
+    }
+}
+
 impl PowerCreep {
     pub fn create(name: &str, class: PowerCreepClass) -> ReturnCode {
         js_unwrap!(PowerCreep.create(@{name}, __power_creep_class_num_to_str(@{class as u32})))
@@ -28,13 +132,34 @@ impl PowerCreep {
         js_unwrap!((@{self.as_ref()}.powers[@{power_type as u32}] || {}).level)
     }
 
-    pub fn use_power<T>(&self, power_type: PowerType, target: Option<&T>) -> ReturnCode
-    where
-        T: ?Sized + RoomObjectProperties,
-    {
-        match target {
-            Some(v) => js_unwrap!(@{self.as_ref()}.usePower(@{power_type as u32}, @{v.as_ref()})),
-            None => js_unwrap!(@{self.as_ref()}.usePower(@{power_type as u32})),
+    /// Uses a power, after checking that its target is of a type the power
+    /// accepts and is within the power's range.
+    ///
+    /// Returns `ReturnCode::InvalidTarget` or `ReturnCode::NotInRange` without
+    /// calling into JavaScript if those checks fail.
+    pub fn use_power(&self, power: UsePower<'_>) -> ReturnCode {
+        if !power.has_valid_target() {
+            return ReturnCode::InvalidTarget;
+        }
+        let power_type = power.power_type() as u32;
+        match power.target() {
+            Some(target) => {
+                if let Some(range) = power.power_type().info().range {
+                    if !self.pos().in_range_to(target, range) {
+                        return ReturnCode::NotInRange;
+                    }
+                }
+                record_intent!(
+                    self.as_ref(),
+                    "usePower",
+                    js_unwrap!(@{self.as_ref()}.usePower(@{power_type}, @{target.as_ref()}))
+                )
+            }
+            None => record_intent!(
+                self.as_ref(),
+                "usePower",
+                js_unwrap!(@{self.as_ref()}.usePower(@{power_type}))
+            ),
         }
     }
 
@@ -1,9 +1,40 @@
-use crate::{constants::ResourceType, objects::Resource};
+use crate::{
+    constants::{ResourceType, ENERGY_DECAY},
+    objects::Resource,
+};
 
 impl Resource {
     pub fn resource_type(&self) -> ResourceType {
         js_unwrap!(__resource_type_str_to_num(@{self.as_ref()}.resourceType))
     }
+
+    /// Amount this resource will lose at the end of this tick, if it isn't
+    /// picked up.
+    pub fn decay_per_tick(&self) -> u32 {
+        decay_amount(self.amount())
+    }
+
+    /// Ticks until this resource has fully decayed, if it isn't picked up or
+    /// added to.
+    ///
+    /// Dropped resources lose `ceil(amount / ENERGY_DECAY)` each tick, so
+    /// large piles shrink quickly at first and then more slowly.
+    pub fn ticks_to_decay(&self) -> u32 {
+        decay_ticks(self.amount())
+    }
+}
+
+fn decay_amount(amount: u32) -> u32 {
+    (f64::from(amount) / f64::from(ENERGY_DECAY)).ceil() as u32
+}
+
+fn decay_ticks(mut amount: u32) -> u32 {
+    let mut ticks = 0;
+    while amount > 0 {
+        amount -= decay_amount(amount);
+        ticks += 1;
+    }
+    ticks
 }
 
 simple_accessors! {
@@ -11,3 +42,30 @@ simple_accessors! {
         pub fn amount() -> u32 = amount;
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{decay_amount, decay_ticks};
+
+    #[test]
+    fn decay_amount_rounds_up() {
+        assert_eq!(decay_amount(0), 0);
+        assert_eq!(decay_amount(1), 1);
+        assert_eq!(decay_amount(999), 1);
+        assert_eq!(decay_amount(1000), 1);
+        assert_eq!(decay_amount(1001), 2);
+        assert_eq!(decay_amount(2500), 3);
+    }
+
+    #[test]
+    fn decay_ticks_small_and_large_piles() {
+        assert_eq!(decay_ticks(0), 0);
+        assert_eq!(decay_ticks(1), 1);
+        assert_eq!(decay_ticks(50), 50);
+        assert_eq!(decay_ticks(1000), 1000);
+        // 1001 loses 2 in the first tick, then 1 each tick
+        assert_eq!(decay_ticks(1001), 1000);
+        // 2000 loses 2 each tick down to 1000
+        assert_eq!(decay_ticks(2000), 1500);
+    }
+}
@@ -1,4 +1,4 @@
-use std::{fmt, marker::PhantomData, mem, ops::Range};
+use std::{cell::RefCell, collections::HashMap, fmt, marker::PhantomData, mem, ops::Range};
 
 use num_traits::FromPrimitive;
 use serde::{
@@ -8,25 +8,47 @@ use serde::{
 };
 use serde_json;
 use serde_repr::{Deserialize_repr, Serialize_repr};
-use stdweb::{Reference, Value};
+use stdweb::{Array, Reference, Value};
 
 use crate::{
     constants::{
-        Color, Direction, EffectType, ExitDirection, FindConstant, Look, LookConstant, PowerType,
-        ResourceType, ReturnCode, StructureType, Terrain,
+        find, Color, Direction, EffectType, ExitDirection, FindConstant, Look, LookConstant,
+        PowerType, ResourceType, ReturnCode, StructureType, Terrain,
     },
+    game,
     local::{Position, RoomName},
     memory::MemoryReference,
     objects::{
-        ConstructionSite, Creep, Deposit, Flag, HasPosition, Mineral, Nuke, PowerCreep, Resource,
-        Room, RoomTerrain, RoomVisual, Ruin, Source, Structure, StructureController,
-        StructureStorage, StructureTerminal, Tombstone,
+        ConstructionSite, Creep, Deposit, EnergyStructure, EnergyStructureOrder, Flag, HasPosition,
+        Mineral, Nuke, PowerCreep, Resource, Room, RoomTerrain, RoomVisual, Ruin, Source,
+        Structure, StructureController, StructureFactory, StructureNuker, StructureObserver,
+        StructurePowerSpawn, StructureProperties, StructureStorage, StructureTerminal, Tombstone,
     },
     pathfinder::{CostMatrix, RoomCostResult, SingleRoomCostResult},
     traits::{TryFrom, TryInto},
     ConversionError,
 };
 
+/// Structures a room can have at most one of, which the game doesn't already
+/// provide accessors for.
+#[derive(Clone, Default)]
+struct RoomSingletons {
+    factory: Option<StructureFactory>,
+    nuker: Option<StructureNuker>,
+    observer: Option<StructureObserver>,
+    power_spawn: Option<StructurePowerSpawn>,
+}
+
+#[derive(Default)]
+struct SingletonCache {
+    tick: u32,
+    rooms: HashMap<RoomName, RoomSingletons>,
+}
+
+thread_local! {
+    static SINGLETON_CACHE: RefCell<SingletonCache> = RefCell::new(SingletonCache::default());
+}
+
 simple_accessors! {
     impl Room {
         pub fn controller() -> Option<StructureController> = controller;
@@ -39,12 +61,18 @@ simple_accessors! {
 }
 
 impl Room {
+    /// Same as [`local::serialize_path`], which this calls.
+    ///
+    /// [`local::serialize_path`]: crate::local::serialize_path
     pub fn serialize_path(path: &[Step]) -> String {
-        js_unwrap! {Room.serializePath(@{path})}
+        crate::local::serialize_path(path)
     }
 
+    /// Same as [`local::deserialize_path`], which this calls.
+    ///
+    /// [`local::deserialize_path`]: crate::local::deserialize_path
     pub fn deserialize_path(path: &str) -> Vec<Step> {
-        js_unwrap! {Room.deserializePath(@{path})}
+        crate::local::deserialize_path(path)
     }
 
     pub fn create_construction_site<T>(&self, at: &T, ty: StructureType) -> ReturnCode
@@ -52,10 +80,14 @@ impl Room {
         T: ?Sized + HasPosition,
     {
         let pos = at.pos();
-        js_unwrap!(@{self.as_ref()}.createConstructionSite(
-            pos_from_packed(@{pos.packed_repr()}),
-            __structure_type_num_to_str(@{ty as u32})
-        ))
+        record_intent!(
+            self.as_ref(),
+            "createConstructionSite",
+            js_unwrap!(@{self.as_ref()}.createConstructionSite(
+                pos_from_packed(@{pos.packed_repr()}),
+                __structure_type_num_to_str(@{ty as u32})
+            ))
+        )
     }
 
     pub fn create_named_construction_site<T>(
@@ -68,15 +100,19 @@ impl Room {
         T: ?Sized + HasPosition,
     {
         let pos = at.pos();
-        js_unwrap!(@{self.as_ref()}.createConstructionSite(
-            // pos_from_packed(@{pos.packed_repr()}),
-            // workaround - passing with a position and a name
-            // currently broken, use x,y instead
-            @{pos.x()},
-            @{pos.y()},
-            __structure_type_num_to_str(@{ty as u32}),
-            @{name}
-        ))
+        record_intent!(
+            self.as_ref(),
+            "createConstructionSite",
+            js_unwrap!(@{self.as_ref()}.createConstructionSite(
+                // pos_from_packed(@{pos.packed_repr()}),
+                // workaround - passing with a position and a name
+                // currently broken, use x,y instead
+                @{pos.x()},
+                @{pos.y()},
+                __structure_type_num_to_str(@{ty as u32}),
+                @{name}
+            ))
+        )
     }
 
     pub fn create_flag<T>(
@@ -108,6 +144,123 @@ impl Room {
         js_unwrap_ref!(@{self.as_ref()}.find(@{ty.find_code()}))
     }
 
+    /// Like [`Room::find`], but leaves out objects owned by any of `owners`,
+    /// such as allies when finding hostile objects.
+    ///
+    /// Objects are filtered in JavaScript before being converted, so this is
+    /// cheaper than checking each object's owner afterwards. Objects without
+    /// an owner are always included.
+    pub fn find_excluding_owners<T>(&self, ty: T, owners: &[&str]) -> Vec<T::Item>
+    where
+        T: FindConstant,
+    {
+        if owners.is_empty() {
+            return self.find(ty);
+        }
+        js_unwrap_ref! {
+            @{self.as_ref()}.find(@{ty.find_code()}, {
+                filter: (obj) => !obj.owner || !@{owners}.includes(obj.owner.username)
+            })
+        }
+    }
+
+    /// Finds hostile creeps in this room which aren't owned by any of
+    /// `allies`.
+    ///
+    /// See [`Room::find_excluding_owners`].
+    pub fn find_hostile_creeps_excluding(&self, allies: &[&str]) -> Vec<Creep> {
+        self.find_excluding_owners(find::HOSTILE_CREEPS, allies)
+    }
+
+    /// Finds this room's spawns and extensions which are owned by you, sorted
+    /// in the order spawns should draw energy from them.
+    ///
+    /// The result can be passed to [`SpawnOptions::energy_structures`].
+    ///
+    /// [`SpawnOptions::energy_structures`]: crate::objects::SpawnOptions::energy_structures
+    pub fn energy_structures(&self, order: EnergyStructureOrder) -> Vec<EnergyStructure> {
+        let mut structures: Vec<EnergyStructure> = self
+            .find(find::MY_STRUCTURES)
+            .into_iter()
+            .filter_map(|s| match s.as_structure() {
+                Structure::Spawn(s) => Some(EnergyStructure::Spawn(s)),
+                Structure::Extension(e) => Some(EnergyStructure::Extension(e)),
+                _ => None,
+            })
+            .collect();
+        order.sort(&mut structures);
+        structures
+    }
+
+    /// Finds this room's singleton structures, or reuses the ones found
+    /// earlier this tick.
+    fn singletons(&self) -> RoomSingletons {
+        let time = game::time();
+        let name = self.name();
+        SINGLETON_CACHE.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            if cache.tick != time {
+                cache.tick = time;
+                cache.rooms.clear();
+            }
+            cache
+                .rooms
+                .entry(name)
+                .or_insert_with(|| {
+                    let found: Vec<Structure> = js_unwrap_ref!(
+                        @{self.as_ref()}.find(FIND_STRUCTURES, {filter: (s) =>
+                            s.structureType === STRUCTURE_FACTORY
+                                || s.structureType === STRUCTURE_NUKER
+                                || s.structureType === STRUCTURE_OBSERVER
+                                || s.structureType === STRUCTURE_POWER_SPAWN
+                        })
+                    );
+                    let mut singletons = RoomSingletons::default();
+                    for structure in found {
+                        match structure {
+                            Structure::Factory(s) => singletons.factory = Some(s),
+                            Structure::Nuker(s) => singletons.nuker = Some(s),
+                            Structure::Observer(s) => singletons.observer = Some(s),
+                            Structure::PowerSpawn(s) => singletons.power_spawn = Some(s),
+                            _ => {}
+                        }
+                    }
+                    singletons
+                })
+                .clone()
+        })
+    }
+
+    /// This room's factory, if it has one.
+    ///
+    /// Like [`Room::storage`] and [`Room::terminal`], this includes structures
+    /// owned by other players. The room's structures are searched once per
+    /// tick for this and the other singleton accessors.
+    pub fn factory(&self) -> Option<StructureFactory> {
+        self.singletons().factory
+    }
+
+    /// This room's nuker, if it has one.
+    ///
+    /// See [`Room::factory`] for caching details.
+    pub fn nuker(&self) -> Option<StructureNuker> {
+        self.singletons().nuker
+    }
+
+    /// This room's observer, if it has one.
+    ///
+    /// See [`Room::factory`] for caching details.
+    pub fn observer(&self) -> Option<StructureObserver> {
+        self.singletons().observer
+    }
+
+    /// This room's power spawn, if it has one.
+    ///
+    /// See [`Room::factory`] for caching details.
+    pub fn power_spawn(&self) -> Option<StructurePowerSpawn> {
+        self.singletons().power_spawn
+    }
+
     pub fn find_exit_to(&self, room: &Room) -> Result<ExitDirection, ReturnCode> {
         let code_val = js! {return @{self.as_ref()}.findExitTo(@{room.as_ref()});};
         let code_int: i32 = code_val.try_into().unwrap();
@@ -129,6 +282,27 @@ impl Room {
         js_unwrap! {@{self.as_ref()}.getEventLog(true)}
     }
 
+    /// Lazily iterates over this tick's event log.
+    ///
+    /// Unlike [`Room::get_event_log`], each event is only converted into Rust
+    /// when the iterator reaches it.
+    pub fn events(&self) -> EventIter {
+        EventIter::new(js_unwrap!(@{self.as_ref()}.getEventLog()))
+    }
+
+    /// Lazily iterates over events in this tick's event log which have one of
+    /// the given kinds.
+    ///
+    /// Filtering happens in JavaScript, so events of other kinds are never
+    /// converted.
+    pub fn events_of_kind(&self, kinds: &[EventKind]) -> EventIter {
+        let codes: Vec<u32> = kinds.iter().map(|k| *k as u32).collect();
+        EventIter::new(js_unwrap! {
+            const codes = @{codes};
+            return @{self.as_ref()}.getEventLog().filter((e) => codes.includes(e.event));
+        })
+    }
+
     pub fn get_position_at(&self, x: u32, y: u32) -> Option<Position> {
         let v = js! {
             let value = @{self.as_ref()}.getPositionAt(@{x}, @{y});
@@ -152,6 +326,17 @@ impl Room {
         }
     }
 
+    /// Gets the rooms this room has exits to and the direction of each, in
+    /// [`Direction`] order, using `Game.map.describeExits`.
+    ///
+    /// See [`RoomName::neighbors`] to get every surrounding room without
+    /// calling into JavaScript.
+    pub fn accessible_neighbors(&self) -> Vec<(Direction, RoomName)> {
+        let mut neighbors: Vec<_> = game::map::describe_exits(self.name()).into_iter().collect();
+        neighbors.sort_by_key(|&(direction, _)| direction as u8);
+        neighbors
+    }
+
     pub fn get_terrain(&self) -> RoomTerrain {
         js_unwrap!(@{self.as_ref()}.getTerrain())
     }
@@ -175,6 +360,10 @@ impl Room {
         js_unwrap!(@{self.as_ref()}.lookAtArea(@{top}, @{left}, @{bottom}, @{right}, true))
     }
 
+    /// Finds a path between two positions in this room.
+    ///
+    /// The resulting steps are converted from JavaScript in a single serde
+    /// pass, or kept as one string with [`FindOptions::serialize`].
     pub fn find_path<'a, 's, O, T, F>(
         &'s self,
         from_pos: &O,
@@ -470,6 +659,7 @@ where
         self
     }
 
+    /// Sets the range to the target the path should end at - default `0`.
     pub fn range(mut self, k: u32) -> Self {
         self.range = k;
         self
@@ -488,7 +678,7 @@ where
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Step {
     pub x: u32,
     pub y: u32,
@@ -651,6 +841,57 @@ impl<'de> Deserialize<'de> for Event {
     }
 }
 
+/// Lazy iterator over a room's event log, created by [`Room::events`] or
+/// [`Room::events_of_kind`].
+pub struct EventIter {
+    log: Array,
+    index: usize,
+}
+
+impl EventIter {
+    fn new(log: Array) -> Self {
+        EventIter { log, index: 0 }
+    }
+}
+
+impl Iterator for EventIter {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        if self.index >= self.log.len() {
+            return None;
+        }
+        let raw: String = js_unwrap!(JSON.stringify(@{self.log.as_ref()}[@{self.index as u32}]));
+        self.index += 1;
+        Some(serde_json::from_str(&raw).expect("Malformed Event Log"))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.log.len() - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for EventIter {}
+
+/// Translates the `EVENT_*` constants, without any event data.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize_repr, Serialize_repr)]
+#[repr(u8)]
+pub enum EventKind {
+    Attack = 1,
+    ObjectDestroyed = 2,
+    AttackController = 3,
+    Build = 4,
+    Harvest = 5,
+    Heal = 6,
+    Repair = 7,
+    ReserveController = 8,
+    UpgradeController = 9,
+    Exit = 10,
+    Power = 11,
+    Transfer = 12,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum EventType {
     Attack(AttackEvent),
@@ -667,6 +908,26 @@ pub enum EventType {
     Transfer(TransferEvent),
 }
 
+impl EventType {
+    /// Gets the `EVENT_*` constant this event was reported with.
+    pub fn kind(&self) -> EventKind {
+        match self {
+            EventType::Attack(_) => EventKind::Attack,
+            EventType::ObjectDestroyed(_) => EventKind::ObjectDestroyed,
+            EventType::AttackController => EventKind::AttackController,
+            EventType::Build(_) => EventKind::Build,
+            EventType::Harvest(_) => EventKind::Harvest,
+            EventType::Heal(_) => EventKind::Heal,
+            EventType::Repair(_) => EventKind::Repair,
+            EventType::ReserveController(_) => EventKind::ReserveController,
+            EventType::UpgradeController(_) => EventKind::UpgradeController,
+            EventType::Exit(_) => EventKind::Exit,
+            EventType::Power(_) => EventKind::Power,
+            EventType::Transfer(_) => EventKind::Transfer,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AttackEvent {
@@ -791,16 +1052,20 @@ pub enum LookResult {
     Tombstone(Tombstone),
     PowerCreep(PowerCreep),
     Ruin(Ruin),
+    /// A result with a look type, or terrain, this crate doesn't know about
+    /// yet, holding the whole JavaScript look result.
+    Unknown(Value),
 }
 
 impl TryFrom<Value> for LookResult {
     type Error = ConversionError;
 
     fn try_from(v: Value) -> Result<LookResult, Self::Error> {
-        let look_type = js! (
-            return __look_str_to_num(@{&v}.type);
-        )
-        .try_into()?;
+        let look_type: String = js!(return @{&v}.type;).try_into()?;
+        let look_type = match look_type.parse() {
+            Ok(look_type) => look_type,
+            Err(_) => return Ok(LookResult::Unknown(v)),
+        };
 
         let lr = match look_type {
             Look::Creeps => LookResult::Creep(js_unwrap_ref!(@{v}.creep)),
@@ -815,7 +1080,13 @@ impl TryFrom<Value> for LookResult {
                 LookResult::ConstructionSite(js_unwrap_ref!(@{v}.constructionSite))
             }
             Look::Nukes => LookResult::Nuke(js_unwrap_ref!(@{v}.nuke)),
-            Look::Terrain => LookResult::Terrain(js_unwrap!(__terrain_str_to_num(@{v}.terrain))),
+            Look::Terrain => {
+                let terrain: String = js!(return @{&v}.terrain;).try_into()?;
+                match terrain.parse() {
+                    Ok(terrain) => LookResult::Terrain(terrain),
+                    Err(_) => LookResult::Unknown(v),
+                }
+            }
             Look::Tombstones => LookResult::Tombstone(js_unwrap_ref!(@{v}.tombstone)),
             Look::PowerCreeps => LookResult::PowerCreep(js_unwrap_ref!(@{v}.powerCreep)),
             Look::Ruins => LookResult::Ruin(js_unwrap_ref!(@{v}.ruin)),
@@ -1,7 +1,7 @@
 use stdweb::UnsafeTypedArray;
 
 use crate::{
-    constants::{ReturnCode, Terrain},
+    constants::{ReturnCode, Terrain, TerrainMask},
     local::RoomName,
     objects::RoomTerrain,
     traits::TryInto,
@@ -12,8 +12,26 @@ impl RoomTerrain {
         js_unwrap!(new Room.Terrain(@{room_name}))
     }
 
+    /// Gets the terrain at the given room coordinates.
+    ///
+    /// Like [`RoomTerrain::get_mask`], each call goes through JavaScript.
     pub fn get(&self, x: u32, y: u32) -> Terrain {
-        js_unwrap!(@{self.as_ref()}.get(@{x}, @{y}))
+        self.get_mask(x, y).into()
+    }
+
+    /// Gets the `TERRAIN_MASK_*` bits at the given room coordinates.
+    ///
+    /// Each call goes through JavaScript. To read more than a few tiles,
+    /// convert this into a [`LocalRoomTerrain`], which copies the whole room
+    /// with one call, or keep rooms fetched with
+    /// [`game::map::get_room_terrain`] in a [`TerrainCache`].
+    ///
+    /// [`LocalRoomTerrain`]: crate::local::LocalRoomTerrain
+    /// [`TerrainCache`]: crate::local::TerrainCache
+    /// [`game::map::get_room_terrain`]: crate::game::map::get_room_terrain
+    pub fn get_mask(&self, x: u32, y: u32) -> TerrainMask {
+        let bits: u8 = js_unwrap!(@{self.as_ref()}.get(@{x}, @{y}));
+        TerrainMask::from_bits_truncate(bits)
     }
 
     pub fn get_raw_buffer(&self) -> Vec<u8> {
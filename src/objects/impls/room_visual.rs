@@ -1,6 +1,12 @@
-use crate::local::RoomName;
 use serde::Serialize;
 
+use crate::{local::RoomName, memory, pathfinder::LocalCostMatrix};
+
+/// Largest size, in bytes, of the visuals drawn in one room each tick.
+///
+/// Visuals past this limit are dropped by the game.
+pub const ROOM_VISUAL_SIZE_LIMIT: u32 = 500 * 1024;
+
 #[derive(Clone, Default, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CircleStyle {
@@ -392,11 +398,28 @@ impl Visual {
     }
 }
 
+/// Draws shapes and text over a room, visible only to you, for the current
+/// tick.
+///
+/// Coordinates are room coordinates, with `(0.0, 0.0)` the centre of the
+/// top-left tile.
+///
+/// # Example
+///
+/// ```no_run
+/// use screeps::{CircleStyle, LineStyle, RoomName, RoomVisual, TextStyle};
+///
+/// let visual = RoomVisual::new(Some(RoomName::new("W1N1").unwrap()));
+/// visual.circle(10.0, 10.0, Some(CircleStyle::default().radius(0.5).fill("#ff0000")));
+/// visual.line((10.0, 10.0), (20.0, 15.0), Some(LineStyle::default().width(0.1)));
+/// visual.text(20.0, 15.0, "target".to_owned(), Some(TextStyle::default().font(0.6)));
+/// ```
 pub struct RoomVisual {
     room_name: Option<RoomName>,
 }
 
 impl RoomVisual {
+    /// Visuals for one room, or for every room if `room_name` is `None`.
     pub fn new(room_name: Option<RoomName>) -> RoomVisual {
         RoomVisual { room_name }
     }
@@ -430,4 +453,195 @@ impl RoomVisual {
     pub fn text(&self, x: f32, y: f32, text: String, style: Option<TextStyle>) {
         self.draw(&Visual::text(x, y, text, style));
     }
+
+    /// Removes the visuals drawn so far this tick.
+    pub fn clear(&self) {
+        js! { @(no_return) console.clearVisual(@{self.room_name}); }
+    }
+
+    /// Size in bytes of the visuals drawn so far this tick.
+    pub fn size(&self) -> u32 {
+        js_unwrap!(console.getVisualSize(@{self.room_name}))
+    }
+
+    /// Gets the visuals drawn so far this tick, in a compact format which can
+    /// be drawn again with [`RoomVisual::import`].
+    pub fn export(&self) -> String {
+        js_unwrap!(console.getVisual(@{self.room_name}) || "")
+    }
+
+    /// Draws visuals previously returned by [`RoomVisual::export`].
+    ///
+    /// Importing what was exported draws the same visuals again:
+    ///
+    /// ```no_run
+    /// use screeps::{RoomName, RoomVisual};
+    ///
+    /// let visual = RoomVisual::new(Some(RoomName::new("W1N1").unwrap()));
+    /// visual.circle(10.0, 10.0, None);
+    /// let (data, size) = (visual.export(), visual.size());
+    ///
+    /// visual.clear();
+    /// visual.import(&data);
+    /// assert_eq!(visual.size(), size);
+    /// ```
+    pub fn import(&self, data: &str) {
+        js! { @(no_return) new RoomVisual(@{self.room_name}).import(@{data}); }
+    }
+
+    /// Stores the visuals drawn so far this tick in `Memory` at `path`, to be
+    /// drawn again on later ticks with [`RoomVisual::replay_from_memory`].
+    ///
+    /// This lets static overlays, such as base plans, be built once and then
+    /// replayed for almost no CPU.
+    pub fn save_to_memory(&self, path: &str) {
+        memory::root().path_set(path, self.export());
+    }
+
+    /// Draws visuals stored by [`RoomVisual::save_to_memory`].
+    ///
+    /// Returns `false` without drawing anything if nothing is stored at
+    /// `path`, or if drawing the stored visuals would take this room's
+    /// visuals over [`ROOM_VISUAL_SIZE_LIMIT`].
+    pub fn replay_from_memory(&self, path: &str) -> bool {
+        let data = match memory::root().path_string(path) {
+            Ok(Some(data)) => data,
+            _ => return false,
+        };
+        if self.size() + data.len() as u32 > ROOM_VISUAL_SIZE_LIMIT {
+            return false;
+        }
+        self.import(&data);
+        true
+    }
+}
+
+/// How values are displayed by [`RoomVisual::heatmap`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HeatmapMode {
+    /// Colored squares only.
+    Colors,
+    /// Numbers only, colored by value.
+    Text,
+    /// Colored squares with the numbers drawn on top.
+    ColorsAndText,
+}
+
+/// Style for [`RoomVisual::heatmap`] and [`RoomVisual::cost_matrix`].
+///
+/// Values are colored on a linear scale from `low_color` at 0 to
+/// `high_color` at `max_value`; larger values are drawn with `high_color`.
+#[derive(Clone, Debug)]
+pub struct HeatmapStyle {
+    mode: HeatmapMode,
+    low_color: (u8, u8, u8),
+    high_color: (u8, u8, u8),
+    max_value: u32,
+    opacity: f32,
+}
+
+impl Default for HeatmapStyle {
+    fn default() -> HeatmapStyle {
+        HeatmapStyle {
+            mode: HeatmapMode::Colors,
+            low_color: (0, 0, 255),
+            high_color: (255, 0, 0),
+            max_value: 255,
+            opacity: 0.4,
+        }
+    }
+}
+
+impl HeatmapStyle {
+    pub fn mode(mut self, val: HeatmapMode) -> HeatmapStyle {
+        self.mode = val;
+        self
+    }
+
+    pub fn low_color(mut self, red: u8, green: u8, blue: u8) -> HeatmapStyle {
+        self.low_color = (red, green, blue);
+        self
+    }
+
+    pub fn high_color(mut self, red: u8, green: u8, blue: u8) -> HeatmapStyle {
+        self.high_color = (red, green, blue);
+        self
+    }
+
+    pub fn max_value(mut self, val: u32) -> HeatmapStyle {
+        self.max_value = val;
+        self
+    }
+
+    pub fn opacity(mut self, val: f32) -> HeatmapStyle {
+        self.opacity = val;
+        self
+    }
+
+    /// Interpolates the color for a value, as a `#rrggbb` string.
+    fn color_for(&self, value: u32) -> String {
+        let ratio = if self.max_value == 0 {
+            1.0
+        } else {
+            (value.min(self.max_value) as f32) / (self.max_value as f32)
+        };
+        let lerp = |low: u8, high: u8| (low as f32 + (high as f32 - low as f32) * ratio) as u8;
+
+        format!(
+            "#{:02x}{:02x}{:02x}",
+            lerp(self.low_color.0, self.high_color.0),
+            lerp(self.low_color.1, self.high_color.1),
+            lerp(self.low_color.2, self.high_color.2),
+        )
+    }
+}
+
+impl RoomVisual {
+    /// Draws a value for each of the given room tiles, such as the results of
+    /// a flood fill, in a single call into JavaScript.
+    pub fn heatmap<I>(&self, values: I, style: &HeatmapStyle)
+    where
+        I: IntoIterator<Item = (u8, u8, u32)>,
+    {
+        let mut visuals = Vec::new();
+        for (x, y, value) in values {
+            let color = style.color_for(value);
+            let (x, y) = (x as f32, y as f32);
+            if style.mode != HeatmapMode::Text {
+                visuals.push(Visual::rect(
+                    x - 0.5,
+                    y - 0.5,
+                    1.0,
+                    1.0,
+                    Some(RectStyle::default().fill(&color).opacity(style.opacity)),
+                ));
+            }
+            if style.mode != HeatmapMode::Colors {
+                let text_color = match style.mode {
+                    HeatmapMode::Text => color.as_str(),
+                    _ => "#ffffff",
+                };
+                visuals.push(Visual::text(
+                    x,
+                    y + 0.15,
+                    value.to_string(),
+                    Some(TextStyle::default().color(text_color).font(0.4)),
+                ));
+            }
+        }
+        self.draw_multi(&visuals);
+    }
+
+    /// Draws every non-zero tile of a cost matrix.
+    ///
+    /// See [`RoomVisual::heatmap`].
+    pub fn cost_matrix(&self, matrix: &LocalCostMatrix, style: &HeatmapStyle) {
+        self.heatmap(
+            matrix
+                .iter()
+                .filter(|&(_, cost)| cost != 0)
+                .map(|((x, y), cost)| (x, y, cost as u32)),
+            style,
+        );
+    }
 }
@@ -0,0 +1,503 @@
+//! Higher-level room visuals built out of the basic shapes.
+use super::room_visual::{RectStyle, RoomVisual, TextAlign, TextStyle, Visual};
+
+/// Something which can be drawn with [`RoomVisual::widget`].
+pub trait Widget {
+    /// Converts this widget into the shapes which draw it.
+    fn to_visuals(&self) -> Vec<Visual>;
+}
+
+impl RoomVisual {
+    /// Draws a widget, with all of its shapes sent to JavaScript at once.
+    pub fn widget<W>(&self, widget: &W)
+    where
+        W: Widget + ?Sized,
+    {
+        self.draw_multi(&widget.to_visuals());
+    }
+}
+
+/// Approximate width of a character, relative to font size, used to size
+/// widgets around text.
+const CHAR_WIDTH_RATIO: f32 = 0.5;
+
+/// A horizontal bar showing progress towards a goal, like controller upgrade
+/// progress or storage fill level.
+///
+/// The bar's top-left corner is at `(x, y)`.
+#[derive(Clone, Debug)]
+pub struct ProgressBar {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    progress: f32,
+    label: Option<String>,
+    fill_color: String,
+    background_color: String,
+    text_color: String,
+}
+
+impl ProgressBar {
+    /// Creates a progress bar. `progress` is clamped to `0.0..=1.0`.
+    pub fn new(x: f32, y: f32, width: f32, progress: f32) -> ProgressBar {
+        ProgressBar {
+            x,
+            y,
+            width,
+            height: 0.8,
+            progress: progress.clamp(0.0, 1.0),
+            label: None,
+            fill_color: "#44bb44".to_string(),
+            background_color: "#333333".to_string(),
+            text_color: "#ffffff".to_string(),
+        }
+    }
+
+    /// Text drawn in the center of the bar.
+    pub fn label(mut self, val: &str) -> ProgressBar {
+        self.label = Some(val.to_string());
+        self
+    }
+
+    pub fn height(mut self, val: f32) -> ProgressBar {
+        self.height = val;
+        self
+    }
+
+    pub fn fill_color(mut self, val: &str) -> ProgressBar {
+        self.fill_color = val.to_string();
+        self
+    }
+
+    pub fn background_color(mut self, val: &str) -> ProgressBar {
+        self.background_color = val.to_string();
+        self
+    }
+
+    pub fn text_color(mut self, val: &str) -> ProgressBar {
+        self.text_color = val.to_string();
+        self
+    }
+}
+
+impl Widget for ProgressBar {
+    fn to_visuals(&self) -> Vec<Visual> {
+        let mut visuals = vec![Visual::rect(
+            self.x,
+            self.y,
+            self.width,
+            self.height,
+            Some(
+                RectStyle::default()
+                    .fill(&self.background_color)
+                    .opacity(0.8),
+            ),
+        )];
+        if self.progress > 0.0 {
+            visuals.push(Visual::rect(
+                self.x,
+                self.y,
+                self.width * self.progress,
+                self.height,
+                Some(RectStyle::default().fill(&self.fill_color).opacity(0.8)),
+            ));
+        }
+        if let Some(label) = &self.label {
+            let font_size = self.height * 0.7;
+            visuals.push(Visual::text(
+                self.x + self.width / 2.0,
+                self.y + self.height / 2.0 + font_size * 0.35,
+                label.clone(),
+                Some(TextStyle::default().color(&self.text_color).font(font_size)),
+            ));
+        }
+        visuals
+    }
+}
+
+/// A table of text with aligned columns and an optional header row.
+///
+/// The table's top-left corner is at `(x, y)`. Column widths are calculated
+/// from the longest text in each column.
+#[derive(Clone, Debug)]
+pub struct Table {
+    x: f32,
+    y: f32,
+    header: Option<Vec<String>>,
+    rows: Vec<Vec<String>>,
+    font_size: f32,
+    text_color: String,
+    header_color: String,
+    background_color: Option<String>,
+}
+
+impl Table {
+    pub fn new(x: f32, y: f32) -> Table {
+        Table {
+            x,
+            y,
+            header: None,
+            rows: Vec::new(),
+            font_size: 0.5,
+            text_color: "#ffffff".to_string(),
+            header_color: "#ffff88".to_string(),
+            background_color: Some("#000000".to_string()),
+        }
+    }
+
+    pub fn header<I, S>(mut self, cells: I) -> Table
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        self.header = Some(cells.into_iter().map(|c| c.to_string()).collect());
+        self
+    }
+
+    pub fn row<I, S>(mut self, cells: I) -> Table
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        self.rows
+            .push(cells.into_iter().map(|c| c.to_string()).collect());
+        self
+    }
+
+    pub fn font_size(mut self, val: f32) -> Table {
+        self.font_size = val;
+        self
+    }
+
+    pub fn text_color(mut self, val: &str) -> Table {
+        self.text_color = val.to_string();
+        self
+    }
+
+    pub fn header_color(mut self, val: &str) -> Table {
+        self.header_color = val.to_string();
+        self
+    }
+
+    /// Background drawn behind the whole table, or `None` for no background.
+    pub fn background_color(mut self, val: Option<&str>) -> Table {
+        self.background_color = val.map(str::to_string);
+        self
+    }
+
+    fn column_widths(&self) -> Vec<f32> {
+        let mut widths: Vec<usize> = Vec::new();
+        for row in self.header.iter().chain(self.rows.iter()) {
+            for (idx, cell) in row.iter().enumerate() {
+                let len = cell.chars().count();
+                match widths.get_mut(idx) {
+                    Some(w) => *w = (*w).max(len),
+                    None => widths.push(len),
+                }
+            }
+        }
+        widths
+            .into_iter()
+            .map(|w| (w as f32 + 1.0) * self.font_size * CHAR_WIDTH_RATIO)
+            .collect()
+    }
+}
+
+impl Widget for Table {
+    fn to_visuals(&self) -> Vec<Visual> {
+        let widths = self.column_widths();
+        let row_height = self.font_size * 1.4;
+        let row_count = self.rows.len() + self.header.is_some() as usize;
+
+        let mut visuals = Vec::new();
+        if let Some(color) = &self.background_color {
+            visuals.push(Visual::rect(
+                self.x,
+                self.y,
+                widths.iter().sum(),
+                row_height * row_count as f32,
+                Some(RectStyle::default().fill(color).opacity(0.5)),
+            ));
+        }
+
+        let rows = self
+            .header
+            .iter()
+            .map(|row| (row, &self.header_color))
+            .chain(self.rows.iter().map(|row| (row, &self.text_color)));
+        for (row_idx, (row, color)) in rows.enumerate() {
+            let y = self.y + row_height * (row_idx as f32 + 0.75);
+            let mut x = self.x + self.font_size * CHAR_WIDTH_RATIO / 2.0;
+            for (cell, width) in row.iter().zip(&widths) {
+                visuals.push(Visual::text(
+                    x,
+                    y,
+                    cell.clone(),
+                    Some(
+                        TextStyle::default()
+                            .color(color)
+                            .font(self.font_size)
+                            .align(TextAlign::Left),
+                    ),
+                ));
+                x += width;
+            }
+        }
+        visuals
+    }
+}
+
+/// Lines of text inside a frame, with an optional title.
+///
+/// The panel's top-left corner is at `(x, y)`. The panel is sized to fit its
+/// text unless a width is set.
+#[derive(Clone, Debug)]
+pub struct TextPanel {
+    x: f32,
+    y: f32,
+    title: Option<String>,
+    lines: Vec<String>,
+    width: Option<f32>,
+    font_size: f32,
+    text_color: String,
+    frame_color: String,
+    background_color: String,
+}
+
+impl TextPanel {
+    pub fn new(x: f32, y: f32) -> TextPanel {
+        TextPanel {
+            x,
+            y,
+            title: None,
+            lines: Vec::new(),
+            width: None,
+            font_size: 0.5,
+            text_color: "#ffffff".to_string(),
+            frame_color: "#aaaaaa".to_string(),
+            background_color: "#000000".to_string(),
+        }
+    }
+
+    pub fn title(mut self, val: &str) -> TextPanel {
+        self.title = Some(val.to_string());
+        self
+    }
+
+    pub fn line<S: ToString>(mut self, val: S) -> TextPanel {
+        self.lines.push(val.to_string());
+        self
+    }
+
+    pub fn lines<I, S>(mut self, vals: I) -> TextPanel
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        self.lines.extend(vals.into_iter().map(|l| l.to_string()));
+        self
+    }
+
+    pub fn width(mut self, val: f32) -> TextPanel {
+        self.width = Some(val);
+        self
+    }
+
+    pub fn font_size(mut self, val: f32) -> TextPanel {
+        self.font_size = val;
+        self
+    }
+
+    pub fn text_color(mut self, val: &str) -> TextPanel {
+        self.text_color = val.to_string();
+        self
+    }
+
+    pub fn frame_color(mut self, val: &str) -> TextPanel {
+        self.frame_color = val.to_string();
+        self
+    }
+
+    pub fn background_color(mut self, val: &str) -> TextPanel {
+        self.background_color = val.to_string();
+        self
+    }
+}
+
+impl Widget for TextPanel {
+    fn to_visuals(&self) -> Vec<Visual> {
+        let line_height = self.font_size * 1.4;
+        let padding = self.font_size * 0.5;
+        let width = self.width.unwrap_or_else(|| {
+            let longest = self
+                .title
+                .iter()
+                .chain(self.lines.iter())
+                .map(|l| l.chars().count())
+                .max()
+                .unwrap_or(0);
+            longest as f32 * self.font_size * CHAR_WIDTH_RATIO + padding * 2.0
+        });
+        let line_count = self.lines.len() + self.title.is_some() as usize;
+        let height = line_height * line_count as f32 + padding * 2.0;
+
+        let mut visuals = vec![Visual::rect(
+            self.x,
+            self.y,
+            width,
+            height,
+            Some(
+                RectStyle::default()
+                    .fill(&self.background_color)
+                    .opacity(0.6)
+                    .stroke(&self.frame_color)
+                    .stroke_width(0.05),
+            ),
+        )];
+
+        let mut y = self.y + padding + line_height * 0.75;
+        if let Some(title) = &self.title {
+            visuals.push(Visual::text(
+                self.x + width / 2.0,
+                y,
+                title.clone(),
+                Some(
+                    TextStyle::default()
+                        .color(&self.frame_color)
+                        .font(self.font_size),
+                ),
+            ));
+            y += line_height;
+        }
+        for line in &self.lines {
+            visuals.push(Visual::text(
+                self.x + padding,
+                y,
+                line.clone(),
+                Some(
+                    TextStyle::default()
+                        .color(&self.text_color)
+                        .font(self.font_size)
+                        .align(TextAlign::Left),
+                ),
+            ));
+            y += line_height;
+        }
+        visuals
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::Value;
+
+    use super::{ProgressBar, Table, TextPanel, Widget};
+
+    /// Each visual's type tag and position, read back from its serialized
+    /// form.
+    fn shapes<W: Widget>(widget: &W) -> Vec<(String, f64, f64)> {
+        widget
+            .to_visuals()
+            .iter()
+            .map(|visual| {
+                let value = serde_json::to_value(visual).unwrap();
+                let get = |key: &str| value[key].as_f64().unwrap();
+                (value["t"].as_str().unwrap().to_owned(), get("x"), get("y"))
+            })
+            .collect()
+    }
+
+    fn field<W: Widget>(widget: &W, idx: usize, key: &str) -> Value {
+        serde_json::to_value(&widget.to_visuals()[idx]).unwrap()[key].clone()
+    }
+
+    fn assert_shapes(actual: Vec<(String, f64, f64)>, expected: &[(&str, f64, f64)]) {
+        assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+        for (a, e) in actual.iter().zip(expected) {
+            assert_eq!(a.0, e.0, "{:?}", actual);
+            assert!(
+                (a.1 - e.1).abs() < 1e-5 && (a.2 - e.2).abs() < 1e-5,
+                "{:?} != {:?}",
+                actual,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn progress_bar() {
+        let bar = ProgressBar::new(10.0, 5.0, 4.0, 0.25)
+            .height(1.0)
+            .label("25%");
+        assert_shapes(
+            shapes(&bar),
+            &[("r", 10.0, 5.0), ("r", 10.0, 5.0), ("t", 12.0, 5.745)],
+        );
+        assert_eq!(field(&bar, 0, "w"), 4.0);
+        assert_eq!(field(&bar, 1, "w"), 1.0);
+        assert_eq!(field(&bar, 2, "text"), "25%");
+
+        // no fill when empty, and progress is clamped
+        assert_shapes(
+            shapes(&ProgressBar::new(1.0, 2.0, 4.0, -1.0)),
+            &[("r", 1.0, 2.0)],
+        );
+        assert_eq!(field(&ProgressBar::new(1.0, 2.0, 4.0, 3.0), 1, "w"), 4.0);
+    }
+
+    #[test]
+    fn table() {
+        let table = Table::new(1.0, 2.0)
+            .font_size(1.0)
+            .header(vec!["a", "bbb"])
+            .row(vec!["cc", "d"]);
+        // columns are 2 and 3 characters wide, plus one character of spacing
+        assert_shapes(
+            shapes(&table),
+            &[
+                ("r", 1.0, 2.0),
+                ("t", 1.25, 3.05),
+                ("t", 2.75, 3.05),
+                ("t", 1.25, 4.45),
+                ("t", 2.75, 4.45),
+            ],
+        );
+        assert_eq!(field(&table, 0, "w"), 3.5);
+        assert!((field(&table, 0, "h").as_f64().unwrap() - 2.8).abs() < 1e-5);
+        assert_eq!(field(&table, 4, "text"), "d");
+
+        assert_eq!(table.background_color(None).to_visuals().len(), 4);
+    }
+
+    #[test]
+    fn text_panel() {
+        let panel = TextPanel::new(0.0, 0.0)
+            .font_size(1.0)
+            .title("Stats")
+            .line("cpu 12")
+            .line("gcl 3");
+        // sized to fit "cpu 12", with the title centered
+        assert_shapes(
+            shapes(&panel),
+            &[
+                ("r", 0.0, 0.0),
+                ("t", 2.0, 1.55),
+                ("t", 0.5, 2.95),
+                ("t", 0.5, 4.35),
+            ],
+        );
+        assert_eq!(field(&panel, 0, "w"), 4.0);
+        assert!((field(&panel, 0, "h").as_f64().unwrap() - 5.2).abs() < 1e-5);
+
+        assert_shapes(
+            shapes(&panel.width(10.0)),
+            &[
+                ("r", 0.0, 0.0),
+                ("t", 5.0, 1.55),
+                ("t", 0.5, 2.95),
+                ("t", 0.5, 4.35),
+            ],
+        );
+    }
+}
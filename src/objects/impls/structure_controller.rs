@@ -32,7 +32,11 @@ pub struct Sign {
 
 impl StructureController {
     pub fn activate_safe_mode(&self) -> ReturnCode {
-        js_unwrap! {@{self.as_ref()}.activateSafeMode()}
+        record_intent!(
+            self.as_ref(),
+            "activateSafeMode",
+            js_unwrap! {@{self.as_ref()}.activateSafeMode()}
+        )
     }
 
     pub fn reservation(&self) -> Option<Reservation> {
@@ -60,6 +64,10 @@ impl StructureController {
     }
 
     pub fn unclaim(&self) -> ReturnCode {
-        js_unwrap! {@{self.as_ref()}.unclaim()}
+        record_intent!(
+            self.as_ref(),
+            "unclaim",
+            js_unwrap! {@{self.as_ref()}.unclaim()}
+        )
     }
 }
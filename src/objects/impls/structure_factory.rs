@@ -1,6 +1,6 @@
 use crate::{
-    constants::{ResourceType, ReturnCode},
-    objects::StructureFactory,
+    constants::{EffectType, PowerType, ResourceType, ReturnCode},
+    objects::{Effect, HasCooldown, HasStore, RoomObjectProperties, StructureFactory},
 };
 
 simple_accessors! {
@@ -11,6 +11,66 @@ simple_accessors! {
 
 impl StructureFactory {
     pub fn produce(&self, ty: ResourceType) -> ReturnCode {
-        js_unwrap! {@{self.as_ref()}.produce(__resource_type_num_to_str(@{ty as u32}))}
+        record_intent!(
+            self.as_ref(),
+            "produce",
+            js_unwrap! {@{self.as_ref()}.produce(__resource_type_num_to_str(@{ty as u32}))}
+        )
+    }
+
+    /// The active `PWR_OPERATE_FACTORY` effect on this factory, if any.
+    pub fn operate_factory_effect(&self) -> Option<Effect> {
+        self.effects()
+            .into_iter()
+            .find(|e| e.effect == EffectType::PowerEffect(PowerType::OperateFactory))
+    }
+
+    /// Whether this factory has an active `PWR_OPERATE_FACTORY` effect, which
+    /// is needed to produce commodities with a level.
+    pub fn has_operate_factory_effect(&self) -> bool {
+        self.operate_factory_effect().is_some()
+    }
+
+    /// Produces a commodity, first checking it against the commodity recipe
+    /// table and this factory's state.
+    ///
+    /// Returns the code the game would return without issuing the intent
+    /// when:
+    ///
+    /// - `ty` has no recipe: [`ReturnCode::InvalidArgs`]
+    /// - the factory is on cooldown: [`ReturnCode::Tired`]
+    /// - the recipe needs a different factory level: [`ReturnCode::InvalidTarget`]
+    /// - the recipe has a level, but there's no `PWR_OPERATE_FACTORY` effect:
+    ///   [`ReturnCode::Busy`]
+    /// - components are missing: [`ReturnCode::NotEnough`]
+    /// - there's no room for the product: [`ReturnCode::Full`]
+    pub fn produce_checked(&self, ty: ResourceType) -> ReturnCode {
+        let recipe = match ty.commodity_recipe() {
+            Some(recipe) => recipe,
+            None => return ReturnCode::InvalidArgs,
+        };
+        if self.cooldown() > 0 {
+            return ReturnCode::Tired;
+        }
+        if let Some(level) = recipe.level {
+            if self.level() != Some(level) {
+                return ReturnCode::InvalidTarget;
+            }
+            if !self.has_operate_factory_effect() {
+                return ReturnCode::Busy;
+            }
+        }
+        if recipe
+            .components
+            .iter()
+            .any(|(&component, &amount)| self.store_of(component) < amount)
+        {
+            return ReturnCode::NotEnough;
+        }
+        let consumed: u32 = recipe.components.values().sum();
+        if self.store_free_capacity(None) + (consumed as i32) < recipe.amount as i32 {
+            return ReturnCode::Full;
+        }
+        self.produce(ty)
     }
 }
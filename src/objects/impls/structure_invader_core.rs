@@ -1,4 +1,10 @@
-use crate::objects::StructureInvaderCore;
+use crate::{
+    constants::{find, EffectType, NaturalEffectType},
+    objects::{
+        OwnedStructureProperties, RoomObjectProperties, Structure, StructureInvaderCore,
+        StructureRampart,
+    },
+};
 
 simple_accessors! {
     impl StructureInvaderCore {
@@ -6,3 +12,51 @@ simple_accessors! {
         pub fn ticks_to_deploy() -> Option<u32> = ticksToDeploy;
     }
 }
+
+impl StructureInvaderCore {
+    /// Ticks until this core's stronghold collapses, from its
+    /// `EFFECT_COLLAPSE_TIMER` effect.
+    ///
+    /// Returns `None` while the stronghold is still deploying, as the timer
+    /// only starts once it's deployed.
+    pub fn collapse_ticks(&self) -> Option<u32> {
+        collapse_ticks(self)
+    }
+
+    /// Whether the stronghold is still being deployed, as opposed to fully
+    /// built with its collapse timer running.
+    pub fn is_deploying(&self) -> bool {
+        self.ticks_to_deploy().is_some()
+    }
+
+    /// The stronghold's ramparts: ramparts in this core's room with the same
+    /// owner.
+    pub fn ramparts(&self) -> Vec<StructureRampart> {
+        let room = match self.room() {
+            Some(room) => room,
+            None => return Vec::new(),
+        };
+        let owner = self.owner_name();
+        room.find(find::STRUCTURES)
+            .into_iter()
+            .filter_map(|structure| match structure {
+                Structure::Rampart(rampart) if rampart.owner_name() == owner => Some(rampart),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl StructureRampart {
+    /// Ticks until this rampart collapses, for ramparts which are part of a
+    /// stronghold, from their `EFFECT_COLLAPSE_TIMER` effect.
+    pub fn collapse_ticks(&self) -> Option<u32> {
+        collapse_ticks(self)
+    }
+}
+
+fn collapse_ticks<T: RoomObjectProperties>(object: &T) -> Option<u32> {
+    object
+        .effect(EffectType::NaturalEffect(NaturalEffectType::CollapseTimer))
+        .map(|effect| effect.ticks_remaining)
+}
@@ -24,20 +24,40 @@ impl StructureLab {
 
     pub fn boost_creep(&self, creep: &Creep, body_part_count: Option<u32>) -> ReturnCode {
         match body_part_count {
-            None => js_unwrap! {@{self.as_ref()}.boostCreep(@{creep.as_ref()})},
-            Some(count) => js_unwrap! {@{self.as_ref()}.boostCreep(@{creep.as_ref()}, @{count})},
+            None => record_intent!(
+                self.as_ref(),
+                "boostCreep",
+                js_unwrap! {@{self.as_ref()}.boostCreep(@{creep.as_ref()})}
+            ),
+            Some(count) => record_intent!(
+                self.as_ref(),
+                "boostCreep",
+                js_unwrap! {@{self.as_ref()}.boostCreep(@{creep.as_ref()}, @{count})}
+            ),
         }
     }
 
     pub fn run_reaction(&self, lab1: &StructureLab, lab2: &StructureLab) -> ReturnCode {
-        js_unwrap! {@{self.as_ref()}.runReaction(@{lab1.as_ref()}, @{lab2.as_ref()})}
+        record_intent!(
+            self.as_ref(),
+            "runReaction",
+            js_unwrap! {@{self.as_ref()}.runReaction(@{lab1.as_ref()}, @{lab2.as_ref()})}
+        )
     }
 
     pub fn reverse_reaction(&self, lab1: &StructureLab, lab2: &StructureLab) -> ReturnCode {
-        js_unwrap! {@{self.as_ref()}.reverseReaction(@{lab1.as_ref()}, @{lab2.as_ref()})}
+        record_intent!(
+            self.as_ref(),
+            "reverseReaction",
+            js_unwrap! {@{self.as_ref()}.reverseReaction(@{lab1.as_ref()}, @{lab2.as_ref()})}
+        )
     }
 
     pub fn unboost_creep(&self, creep: &Creep) -> ReturnCode {
-        js_unwrap!(@{self.as_ref()}.unboostCreep(@{creep.as_ref()}))
+        record_intent!(
+            self.as_ref(),
+            "unboostCreep",
+            js_unwrap!(@{self.as_ref()}.unboostCreep(@{creep.as_ref()}))
+        )
     }
 }
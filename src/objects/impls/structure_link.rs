@@ -3,9 +3,17 @@ use crate::{constants::ReturnCode, objects::StructureLink};
 impl StructureLink {
     pub fn transfer_energy(&self, target: &StructureLink, amount: Option<u32>) -> ReturnCode {
         match amount {
-            None => js_unwrap! {@{self.as_ref()}.transferEnergy(@{target.as_ref()})},
+            None => record_intent!(
+                self.as_ref(),
+                "transferEnergy",
+                js_unwrap! {@{self.as_ref()}.transferEnergy(@{target.as_ref()})}
+            ),
             Some(amount) => {
-                js_unwrap! {@{self.as_ref()}.transferEnergy(@{target.as_ref()}, @{amount})}
+                record_intent!(
+                    self.as_ref(),
+                    "transferEnergy",
+                    js_unwrap! {@{self.as_ref()}.transferEnergy(@{target.as_ref()}, @{amount})}
+                )
             }
         }
     }
@@ -1,11 +1,85 @@
 use crate::{
-    constants::ReturnCode,
-    objects::{HasPosition, StructureNuker},
+    constants::{
+        ResourceType, ReturnCode, NUKER_ENERGY_CAPACITY, NUKER_GHODIUM_CAPACITY, NUKE_RANGE,
+    },
+    local::RoomName,
+    objects::{HasCooldown, HasPosition, HasStore, StructureNuker},
 };
 
 impl StructureNuker {
     pub fn launch_nuke<T: HasPosition + ?Sized>(&self, target: &T) -> ReturnCode {
         let pos = target.pos();
-        js_unwrap! {@{self.as_ref()}.launchNuke(pos_from_packed(@{pos.packed_repr()}))}
+        record_intent!(
+            self.as_ref(),
+            "launchNuke",
+            js_unwrap! {@{self.as_ref()}.launchNuke(pos_from_packed(@{pos.packed_repr()}))}
+        )
+    }
+
+    /// Energy still needed to load the nuker.
+    pub fn energy_to_load(&self) -> u32 {
+        NUKER_ENERGY_CAPACITY.saturating_sub(self.store_of(ResourceType::Energy))
+    }
+
+    /// Ghodium still needed to load the nuker.
+    pub fn ghodium_to_load(&self) -> u32 {
+        NUKER_GHODIUM_CAPACITY.saturating_sub(self.store_of(ResourceType::Ghodium))
+    }
+
+    /// Whether the nuker is fully loaded and off cooldown.
+    pub fn is_ready(&self) -> bool {
+        self.cooldown() == 0 && self.energy_to_load() == 0 && self.ghodium_to_load() == 0
+    }
+
+    /// Whether `room_name` is within `NUKE_RANGE` of this nuker.
+    pub fn in_range(&self, room_name: RoomName) -> bool {
+        rooms_in_nuke_range(self.pos().room_name(), room_name)
+    }
+}
+
+/// Whether a nuker in room `from` can reach room `to`.
+///
+/// Like the game, this measures the linear distance between the rooms
+/// without wrapping around the edges of the world.
+fn rooms_in_nuke_range(from: RoomName, to: RoomName) -> bool {
+    let (dx, dy) = to - from;
+    dx.abs().max(dy.abs()) as u32 <= NUKE_RANGE
+}
+
+#[cfg(test)]
+mod test {
+    use super::rooms_in_nuke_range;
+    use crate::{
+        constants::{nuke_damage, NUKE_DAMAGE_RANGE_0, NUKE_DAMAGE_RANGE_2},
+        local::{Position, RoomName},
+    };
+
+    fn name(s: &str) -> RoomName {
+        RoomName::new(s).unwrap()
+    }
+
+    #[test]
+    fn nuke_range() {
+        assert!(rooms_in_nuke_range(name("W1N1"), name("W1N1")));
+        assert!(rooms_in_nuke_range(name("W1N1"), name("W11N11")));
+        assert!(!rooms_in_nuke_range(name("W1N1"), name("W12N1")));
+        assert!(!rooms_in_nuke_range(name("W1N1"), name("W1N12")));
+        // across the middle of the world, where W0 and E0 are neighbors
+        assert!(rooms_in_nuke_range(name("W4N0"), name("E5S9")));
+        assert!(!rooms_in_nuke_range(name("W5N0"), name("E5S0")));
+        assert!(!rooms_in_nuke_range(name("E5N0"), name("E5S10")));
+    }
+
+    #[test]
+    fn damage_by_range() {
+        let landing = Position::new(25, 25, name("W1N1"));
+        let damage = |x, y| nuke_damage(landing.get_range_to(&Position::new(x, y, name("W1N1"))));
+        assert_eq!(damage(25, 25), NUKE_DAMAGE_RANGE_0);
+        assert_eq!(damage(26, 24), NUKE_DAMAGE_RANGE_2);
+        assert_eq!(damage(23, 27), NUKE_DAMAGE_RANGE_2);
+        assert_eq!(damage(27, 22), 0);
+        assert_eq!(damage(0, 0), 0);
+        assert_eq!(NUKE_DAMAGE_RANGE_0, 10_000_000);
+        assert_eq!(NUKE_DAMAGE_RANGE_2, 5_000_000);
     }
 }
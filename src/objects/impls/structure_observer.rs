@@ -1,7 +1,41 @@
-use crate::{constants::ReturnCode, local::RoomName, objects::StructureObserver};
+use crate::{
+    constants::{EffectType, PowerType, ReturnCode, OBSERVER_RANGE},
+    local::RoomName,
+    objects::{HasPosition, RoomObjectProperties, StructureObserver},
+};
 
 impl StructureObserver {
     pub fn observe_room(&self, room_name: RoomName) -> ReturnCode {
-        js_unwrap! {@{self.as_ref()}.observeRoom(@{room_name})}
+        record_intent!(
+            self.as_ref(),
+            "observeRoom",
+            js_unwrap! {@{self.as_ref()}.observeRoom(@{room_name})}
+        )
+    }
+
+    /// Observes a room, first checking that it's within [`OBSERVER_RANGE`] of
+    /// this observer.
+    ///
+    /// The range check is skipped while the observer has an active
+    /// `PWR_OPERATE_OBSERVER` effect, which gives it unlimited range. Returns
+    /// [`ReturnCode::NotInRange`] without issuing the intent when the room is
+    /// too far away.
+    pub fn observe_room_checked(&self, room_name: RoomName) -> ReturnCode {
+        if !self.is_in_observe_range(room_name) {
+            return ReturnCode::NotInRange;
+        }
+        self.observe_room(room_name)
+    }
+
+    /// Whether `room_name` can currently be observed by this observer.
+    pub fn is_in_observe_range(&self, room_name: RoomName) -> bool {
+        let (dx, dy) = room_name - self.pos().room_name();
+        let distance = dx.abs().max(dy.abs()) as u32;
+
+        distance <= OBSERVER_RANGE
+            || self
+                .effects()
+                .iter()
+                .any(|e| e.effect == EffectType::PowerEffect(PowerType::OperateObserver))
     }
 }
@@ -2,6 +2,10 @@ use crate::{constants::ReturnCode, objects::StructurePowerSpawn};
 
 impl StructurePowerSpawn {
     pub fn process_power(&self) -> ReturnCode {
-        js_unwrap! {@{self.as_ref()}.processPower()}
+        record_intent!(
+            self.as_ref(),
+            "processPower",
+            js_unwrap! {@{self.as_ref()}.processPower()}
+        )
     }
 }
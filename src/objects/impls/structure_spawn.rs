@@ -1,9 +1,15 @@
+use std::cmp::Reverse;
+
 use stdweb::Reference;
 
 use crate::{
     constants::{Direction, Part, ReturnCode},
+    local::Position,
     memory::MemoryReference,
-    objects::{Creep, HasEnergyForSpawn, SizedRoomObject, Spawning, StructureSpawn},
+    objects::{
+        Creep, HasEnergyForSpawn, HasStore, RoomObjectProperties, Spawning, StructureExtension,
+        StructureSpawn,
+    },
     traits::TryInto,
 };
 
@@ -21,13 +27,17 @@ impl StructureSpawn {
 
     pub fn spawn_creep(&self, body: &[Part], name: &str) -> ReturnCode {
         let ints = body.iter().map(|p| *p as u32).collect::<Vec<u32>>();
-        (js! {
-            var body = (@{ints}).map(__part_num_to_str);
-
-            return @{self.as_ref()}.spawnCreep(body, @{name});
-        })
-        .try_into()
-        .expect("expected StructureSpawn::spawnCreep to return an integer return code")
+        record_intent!(
+            self.as_ref(),
+            "spawnCreep",
+            (js! {
+                var body = (@{ints}).map(__part_num_to_str);
+
+                return @{self.as_ref()}.spawnCreep(body, @{name});
+            })
+            .try_into()
+            .expect("expected StructureSpawn::spawnCreep to return an integer return code")
+        )
     }
 
     pub fn spawn_creep_with_options(
@@ -51,17 +61,24 @@ impl StructureSpawn {
             }
         }
         if !opts.directions.is_empty() {
+            let int_dirs: Vec<u32> = opts.directions.iter().map(|d| *d as u32).collect();
             js! { @(no_return)
-                @{&js_opts}.directions = @{&opts.directions};
+                @{&js_opts}.directions = @{int_dirs};
             }
         }
-        (js! {
+        let result = (js! {
             var body = (@{body_ints}).map(__part_num_to_str);
 
             return @{self.as_ref()}.spawnCreep(body, @{name}, @{js_opts});
         })
         .try_into()
-        .expect("expected StructureSpawn::spawnCreep to return an integer return code")
+        .expect("expected StructureSpawn::spawnCreep to return an integer return code");
+
+        if opts.dry_run {
+            result
+        } else {
+            record_intent!(self.as_ref(), "spawnCreep", result)
+        }
     }
 
     // TODO: support actually using Spawning properties.
@@ -70,11 +87,19 @@ impl StructureSpawn {
     }
 
     pub fn recycle_creep(&self, target: &Creep) -> ReturnCode {
-        js_unwrap! {@{self.as_ref()}.recycleCreep(@{target.as_ref()})}
+        record_intent!(
+            self.as_ref(),
+            "recycleCreep",
+            js_unwrap! {@{self.as_ref()}.recycleCreep(@{target.as_ref()})}
+        )
     }
 
     pub fn renew_creep(&self, target: &Creep) -> ReturnCode {
-        js_unwrap! {@{self.as_ref()}.renewCreep(@{target.as_ref()})}
+        record_intent!(
+            self.as_ref(),
+            "renewCreep",
+            js_unwrap! {@{self.as_ref()}.renewCreep(@{target.as_ref()})}
+        )
     }
 }
 
@@ -83,7 +108,7 @@ pub struct SpawnOptions {
     memory: Option<MemoryReference>,
     energy_structures: Vec<Reference>,
     dry_run: bool,
-    directions: Vec<u32>,
+    directions: Vec<Direction>,
 }
 
 impl SpawnOptions {
@@ -100,7 +125,7 @@ impl SpawnOptions {
     pub fn energy_structures<T>(mut self, structures: T) -> Self
     where
         T: IntoIterator,
-        <T as IntoIterator>::Item: HasEnergyForSpawn + SizedRoomObject,
+        <T as IntoIterator>::Item: HasEnergyForSpawn + Into<Reference>,
     {
         self.energy_structures = structures.into_iter().map(|s| s.into()).collect();
         self
@@ -111,15 +136,87 @@ impl SpawnOptions {
         self
     }
 
+    /// Directions the new creep may move out of the spawn in, in order of
+    /// preference. By default, all directions are allowed.
     pub fn directions(mut self, directions: &[Direction]) -> Self {
-        self.directions = directions.iter().map(|d| *d as u32).collect();
+        self.directions = directions.to_vec();
         self
     }
 }
 
+/// A spawn or extension: the structures spawns can draw energy from.
+///
+/// Returned by [`Room::energy_structures`], and can be passed straight to
+/// [`SpawnOptions::energy_structures`].
+///
+/// [`Room::energy_structures`]: crate::objects::Room::energy_structures
+#[derive(Clone)]
+pub enum EnergyStructure {
+    Spawn(StructureSpawn),
+    Extension(StructureExtension),
+}
+
+impl AsRef<Reference> for EnergyStructure {
+    fn as_ref(&self) -> &Reference {
+        match self {
+            EnergyStructure::Spawn(s) => s.as_ref(),
+            EnergyStructure::Extension(e) => e.as_ref(),
+        }
+    }
+}
+
+impl From<EnergyStructure> for Reference {
+    fn from(wrapper: EnergyStructure) -> Reference {
+        match wrapper {
+            EnergyStructure::Spawn(s) => s.into(),
+            EnergyStructure::Extension(e) => e.into(),
+        }
+    }
+}
+
+unsafe impl RoomObjectProperties for EnergyStructure {}
+unsafe impl HasStore for EnergyStructure {}
+unsafe impl HasEnergyForSpawn for EnergyStructure {}
+
+/// The order spawns should take energy from a room's spawns and extensions in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EnergyStructureOrder {
+    /// Closest to a position first, usually the spawn, so the structures which
+    /// are quickest to refill are emptied first.
+    ClosestTo(Position),
+    /// Farthest from a position first, keeping the structures near it full.
+    FarthestFrom(Position),
+    /// Structures holding the most energy first, emptying as few structures as
+    /// possible.
+    FullestFirst,
+    /// Structures holding the least energy first, finishing off partly empty
+    /// structures.
+    EmptiestFirst,
+}
+
+impl EnergyStructureOrder {
+    /// Sorts structures into this order. Ties keep their existing order.
+    pub fn sort<T>(self, structures: &mut [T])
+    where
+        T: HasStore,
+    {
+        match self {
+            EnergyStructureOrder::ClosestTo(pos) => {
+                structures.sort_by_cached_key(|s| pos.get_range_to(&s.pos()))
+            }
+            EnergyStructureOrder::FarthestFrom(pos) => {
+                structures.sort_by_cached_key(|s| Reverse(pos.get_range_to(&s.pos())))
+            }
+            EnergyStructureOrder::FullestFirst => {
+                structures.sort_by_cached_key(|s| Reverse(s.energy()))
+            }
+            EnergyStructureOrder::EmptiestFirst => structures.sort_by_cached_key(|s| s.energy()),
+        }
+    }
+}
+
 simple_accessors! {
     impl Spawning {
-        pub fn directions() -> Vec<Direction> = directions;
         pub fn name() -> String = name;
         pub fn need_time() -> u32 = needTime;
         pub fn remaining_time() -> u32 = remainingTime;
@@ -128,12 +225,26 @@ simple_accessors! {
 }
 
 impl Spawning {
+    /// Directions the creep may move out of the spawn in, in order of
+    /// preference. Empty if no directions were set, allowing any direction.
+    pub fn directions(&self) -> Vec<Direction> {
+        js_unwrap!(@{self.as_ref()}.directions || [])
+    }
+
     pub fn cancel(&self) -> ReturnCode {
-        js_unwrap!(@{self.as_ref()}.cancel())
+        record_intent!(
+            self.as_ref(),
+            "cancel",
+            js_unwrap!(@{self.as_ref()}.cancel())
+        )
     }
 
     pub fn set_directions(&self, directions: &[Direction]) -> ReturnCode {
         let int_dirs: Vec<u32> = directions.iter().map(|d| *d as u32).collect();
-        js_unwrap!(@{self.as_ref()}.setDirections(@{int_dirs}))
+        record_intent!(
+            self.as_ref(),
+            "setDirections",
+            js_unwrap!(@{self.as_ref()}.setDirections(@{int_dirs}))
+        )
     }
 }
@@ -12,11 +12,15 @@ impl StructureTerminal {
         destination: RoomName,
         description: Option<&str>,
     ) -> ReturnCode {
-        js_unwrap! {
-            @{self.as_ref()}.send(__resource_type_num_to_str(@{resource_type as u32}),
-                                  @{amount},
-                                  @{destination},
-                                  @{description} || undefined)
-        }
+        record_intent!(
+            self.as_ref(),
+            "send",
+            js_unwrap! {
+                @{self.as_ref()}.send(__resource_type_num_to_str(@{resource_type as u32}),
+                                      @{amount},
+                                      @{destination},
+                                      @{description} || undefined)
+            }
+        )
     }
 }
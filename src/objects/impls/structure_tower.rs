@@ -8,20 +8,32 @@ impl StructureTower {
     where
         T: Attackable,
     {
-        js_unwrap! { @{self.as_ref()}.attack( @{target.as_ref()} ) }
+        record_intent!(
+            self.as_ref(),
+            "attack",
+            js_unwrap! { @{self.as_ref()}.attack( @{target.as_ref()} ) }
+        )
     }
 
     pub fn heal<T>(&self, target: &T) -> ReturnCode
     where
         T: SharedCreepProperties,
     {
-        js_unwrap! { @{self.as_ref()}.heal( @{target.as_ref()} ) }
+        record_intent!(
+            self.as_ref(),
+            "heal",
+            js_unwrap! { @{self.as_ref()}.heal( @{target.as_ref()} ) }
+        )
     }
 
     pub fn repair<T>(&self, target: &T) -> ReturnCode
     where
         T: StructureProperties,
     {
-        js_unwrap! { @{self.as_ref()}.repair( @{target.as_ref()} ) }
+        record_intent!(
+            self.as_ref(),
+            "repair",
+            js_unwrap! { @{self.as_ref()}.repair( @{target.as_ref()} ) }
+        )
     }
 }
@@ -0,0 +1,17 @@
+use crate::objects::StructureWall;
+
+impl StructureWall {
+    /// Whether this wall is part of a novice or respawn area border.
+    ///
+    /// These walls have no hits and can't be attacked or dismantled, so they
+    /// block movement until they disappear, like terrain walls.
+    pub fn is_novice_wall(&self) -> bool {
+        js_unwrap!(@{self.as_ref()}.hits === undefined)
+    }
+
+    /// Ticks until this wall disappears, for novice or respawn area border
+    /// walls, or `None` for walls built by players.
+    pub fn ticks_to_live(&self) -> Option<u32> {
+        js_unwrap!(@{self.as_ref()}.ticksToLive)
+    }
+}
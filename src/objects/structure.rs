@@ -115,7 +115,9 @@ impl Structure {
 
     /// Cast this as something which can be attacked and has hit points.
     ///
-    /// The only Structure which cannot be attacked is `StructureController`.
+    /// Returns `None` for structures which cannot be attacked:
+    /// `StructureController`, `StructurePortal`, and `StructureWall`s which are
+    /// part of a novice area border (see [`StructureWall::is_novice_wall`]).
     pub fn as_attackable(&self) -> Option<&dyn Attackable> {
         // We're not using `match_some_structure_variants!` here or in `as_owned` so we
         // won't have a `_ => None` branch and instead we'll be forced to add
@@ -142,6 +144,7 @@ impl Structure {
             Structure::Storage(v) => Some(v),
             Structure::Terminal(v) => Some(v),
             Structure::Tower(v) => Some(v),
+            Structure::Wall(v) if v.is_novice_wall() => None,
             Structure::Wall(v) => Some(v),
         }
     }
@@ -10,13 +10,21 @@
 //!
 //! [1]: crate::objects::Room::find_path
 //! [`PathFinder`]: https://docs.screeps.com/api/#PathFinder
-use std::{borrow::Borrow, f64, marker::PhantomData, mem};
+use std::{borrow::Borrow, collections::BTreeMap, f64, marker::PhantomData, mem, ops::Range};
 
 use stdweb::{web::TypedArray, Array, Object, Reference, UnsafeTypedArray, Value};
 
-use crate::{local::Position, objects::HasPosition, traits::TryInto, RoomName};
-
-#[derive(Clone, Debug)]
+use crate::{
+    constants::{StructureType, Terrain},
+    local::{LocalRoomTerrain, Position},
+    objects::{
+        ConstructionSite, HasPosition, OwnedStructureProperties, Structure, StructureProperties,
+    },
+    traits::TryInto,
+    RoomName,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct LocalCostMatrix {
     /// Length should be 2500.
     bits: Vec<u8>,
@@ -41,6 +49,86 @@ impl LocalCostMatrix {
         }
     }
 
+    /// Builds a matrix of movement costs for a room in one pass.
+    ///
+    /// Every tile gets an explicit cost, so the matrix can be used with
+    /// `plain_cost` and `swamp_cost` left at their defaults, or without the
+    /// game's terrain at all:
+    ///
+    /// - roads cost 1, plains 2 and swamps 10. Roads built through walls
+    ///   (tunnels) cost 1 too.
+    /// - walls, obstacle structures (see [`StructureType::is_obstacle`]) and
+    ///   ramparts which are neither yours nor public cost 255, even if
+    ///   there's also a road on the tile
+    /// - your own construction sites for obstacle structures cost 255, since
+    ///   creeps can't move onto those either. Other construction sites are
+    ///   ignored.
+    pub fn new_for_movement<'a, S, C>(
+        terrain: &LocalRoomTerrain,
+        structures: S,
+        construction_sites: C,
+    ) -> Self
+    where
+        S: IntoIterator<Item = &'a Structure>,
+        C: IntoIterator<Item = &'a ConstructionSite>,
+    {
+        let mut obstacles = Vec::new();
+        let mut roads = Vec::new();
+        for structure in structures {
+            let structure_type = structure.structure_type();
+            let blocks = match structure {
+                Structure::Rampart(rampart) => !rampart.my() && !rampart.is_public(),
+                _ => structure_type.is_obstacle(),
+            };
+            let pos = structure.pos();
+            let coords = (pos.x() as u8, pos.y() as u8);
+            if blocks {
+                obstacles.push(coords);
+            } else if structure_type == StructureType::Road {
+                roads.push(coords);
+            }
+        }
+
+        for site in construction_sites {
+            if site.my() && site.structure_type().is_obstacle() {
+                let pos = site.pos();
+                obstacles.push((pos.x() as u8, pos.y() as u8));
+            }
+        }
+
+        Self::movement_costs(terrain, obstacles, roads)
+    }
+
+    /// The costs [`LocalCostMatrix::new_for_movement`] gives each tile, once
+    /// structures have been sorted into obstacles and roads.
+    fn movement_costs<O, R>(terrain: &LocalRoomTerrain, obstacles: O, roads: R) -> Self
+    where
+        O: IntoIterator<Item = (u8, u8)>,
+        R: IntoIterator<Item = (u8, u8)>,
+    {
+        let mut matrix = LocalCostMatrix::new();
+        for x in 0..50 {
+            for y in 0..50 {
+                let cost = match terrain.get(x, y) {
+                    Terrain::Plain => 2,
+                    Terrain::Swamp => 10,
+                    Terrain::Wall => 255,
+                };
+                matrix.set(x, y, cost);
+            }
+        }
+
+        // roads override terrain, including walls, but never obstacles
+        for (x, y) in roads {
+            matrix.set(x, y, 1);
+        }
+        for (x, y) in obstacles {
+            matrix.set(x, y, 255);
+        }
+
+        matrix
+    }
+
     #[inline]
     pub fn set(&mut self, x: u8, y: u8, val: u8) {
         self.bits[pos_as_idx(x, y)] = val;
@@ -51,6 +139,55 @@ impl LocalCostMatrix {
         self.bits[pos_as_idx(x, y)]
     }
 
+    /// Iterates over all tiles in this matrix, as `((x, y), cost)`.
+    pub fn iter(&self) -> impl Iterator<Item = ((u8, u8), u8)> + '_ {
+        self.bits
+            .iter()
+            .enumerate()
+            .map(|(idx, &cost)| (((idx / 50) as u8, (idx % 50) as u8), cost))
+    }
+
+    /// Finds the tiles whose costs differ in `other`, with their costs in
+    /// `other`.
+    ///
+    /// Applying the result to this matrix with
+    /// [`LocalCostMatrix::apply_patch`] makes it equal to `other`, so a
+    /// matrix built once can be kept alongside just the tiles which change
+    /// each tick.
+    ///
+    /// ```
+    /// use screeps::pathfinder::LocalCostMatrix;
+    ///
+    /// let base = LocalCostMatrix::new();
+    /// let mut with_creeps = base.clone();
+    /// with_creeps.set(10, 12, 255);
+    /// with_creeps.set(11, 12, 255);
+    ///
+    /// let patch = base.diff(&with_creeps);
+    /// assert_eq!(patch.len(), 2);
+    ///
+    /// let mut patched = base.clone();
+    /// patched.apply_patch(&patch);
+    /// assert_eq!(patched, with_creeps);
+    /// ```
+    pub fn diff(&self, other: &LocalCostMatrix) -> SparseCostMatrix {
+        let mut patch = SparseCostMatrix::new();
+        for ((pos, cost), &other_cost) in self.iter().zip(other.bits.iter()) {
+            if cost != other_cost {
+                patch.set(pos.0, pos.1, other_cost);
+            }
+        }
+        patch
+    }
+
+    /// Sets every tile in `patch` to its cost there, leaving other tiles
+    /// unchanged.
+    pub fn apply_patch(&mut self, patch: &SparseCostMatrix) {
+        for ((x, y), cost) in patch.iter() {
+            self.set(x, y, cost);
+        }
+    }
+
     /// Copies all data into an JavaScript CostMatrix for use.
     ///
     /// This is slower than [`as_uploaded`], but much safer.
@@ -137,6 +274,105 @@ impl<'a> CostMatrixSet for LocalCostMatrix {
     }
 }
 
+/// Costs for only some tiles of a room, such as the changes between two
+/// [`LocalCostMatrix`]es from [`LocalCostMatrix::diff`].
+///
+/// Tiles are kept sorted by `x`, then `y`, the same order as
+/// [`LocalCostMatrix::iter`], so iterating and serializing always give the
+/// same result for the same costs. Serialized matrices are lists of
+/// `[x, y, cost]`.
+///
+/// ```
+/// use screeps::pathfinder::SparseCostMatrix;
+///
+/// let mut overlay = SparseCostMatrix::new();
+/// overlay.set(30, 5, 255);
+/// overlay.set(2, 40, 1);
+/// overlay.set(2, 3, 10);
+/// assert_eq!(
+///     serde_json::to_string(&overlay).unwrap(),
+///     "[[2,3,10],[2,40,1],[30,5,255]]"
+/// );
+///
+/// overlay.clear_region(0..10, 0..10);
+/// overlay.retain(|_, cost| cost < 255);
+/// assert_eq!(overlay.iter().collect::<Vec<_>>(), [((2, 40), 1)]);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SparseCostMatrix {
+    costs: BTreeMap<(u8, u8), u8>,
+}
+
+impl SparseCostMatrix {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn set(&mut self, x: u8, y: u8, val: u8) {
+        self.costs.insert((x, y), val);
+    }
+
+    /// Gets a tile's cost, or `None` if it isn't set.
+    #[inline]
+    pub fn get(&self, x: u8, y: u8) -> Option<u8> {
+        self.costs.get(&(x, y)).cloned()
+    }
+
+    /// Unsets a tile's cost, returning the cost it had.
+    #[inline]
+    pub fn remove(&mut self, x: u8, y: u8) -> Option<u8> {
+        self.costs.remove(&(x, y))
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.costs.is_empty()
+    }
+
+    /// Number of tiles with a cost set.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.costs.len()
+    }
+
+    /// Iterates over the tiles with a cost set, as `((x, y), cost)`, sorted
+    /// by `x` and then `y`.
+    pub fn iter(&self) -> impl Iterator<Item = ((u8, u8), u8)> + '_ {
+        self.costs.iter().map(|(&pos, &cost)| (pos, cost))
+    }
+
+    /// Unsets the costs of every tile with `x` in `xs` and `y` in `ys`.
+    pub fn clear_region(&mut self, xs: Range<u8>, ys: Range<u8>) {
+        self.retain(|(x, y), _| !(xs.contains(&x) && ys.contains(&y)));
+    }
+
+    /// Keeps only the tiles for which `keep((x, y), cost)` returns `true`.
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut((u8, u8), u8) -> bool,
+    {
+        self.costs.retain(|&pos, &mut cost| keep(pos, cost));
+    }
+}
+
+impl CostMatrixSet for SparseCostMatrix {
+    fn set_multi<D, B, P, V>(&mut self, data: D)
+    where
+        D: IntoIterator<Item = B>,
+        B: Borrow<(P, V)>,
+        P: HasLocalPosition,
+        V: Borrow<u8>,
+    {
+        for entry in data {
+            let (pos, cost) = entry.borrow();
+
+            self.set(pos.x(), pos.y(), *cost.borrow());
+        }
+    }
+}
+
 /// A `CostMatrix` that's valid to pass as a result from a `PathFinder.search`
 /// room callback.
 ///
@@ -233,7 +469,7 @@ impl<'a> CostMatrixSet for CostMatrix<'a> {
 mod serde_impls {
     use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
 
-    use super::LocalCostMatrix;
+    use super::{LocalCostMatrix, SparseCostMatrix};
 
     impl Serialize for LocalCostMatrix {
         fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
@@ -261,6 +497,36 @@ mod serde_impls {
             Ok(LocalCostMatrix { bits })
         }
     }
+
+    impl Serialize for SparseCostMatrix {
+        fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            s.collect_seq(self.iter().map(|((x, y), cost)| [x, y, cost]))
+        }
+    }
+
+    impl<'de> Deserialize<'de> for SparseCostMatrix {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let entries: Vec<[u8; 3]> = Vec::deserialize(deserializer)?;
+
+            let mut matrix = SparseCostMatrix::new();
+            for [x, y, cost] in entries {
+                if x >= 50 || y >= 50 {
+                    return Err(D::Error::custom(format!(
+                        "expected room coordinates under 50, found ({}, {})",
+                        x, y
+                    )));
+                }
+                matrix.set(x, y, cost);
+            }
+            Ok(matrix)
+        }
+    }
 }
 
 pub trait RoomCostResult: Into<Value> {}
@@ -435,10 +701,42 @@ where
     }
 }
 
+/// A position to search for a path to, and how close the path needs to get
+/// to it.
+///
+/// Tuples of anything with a position and a range convert into goals, so
+/// `(source, 1)` can be passed straight to [`search_many`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SearchGoal {
+    pub pos: Position,
+    pub range: u32,
+}
+
+impl SearchGoal {
+    #[inline]
+    pub fn new(pos: Position, range: u32) -> Self {
+        SearchGoal { pos, range }
+    }
+}
+
+impl<T: HasPosition> From<(T, u32)> for SearchGoal {
+    #[inline]
+    fn from((target, range): (T, u32)) -> Self {
+        SearchGoal::new(target.pos(), range)
+    }
+}
+
+/// The result of a [`search`] or [`search_many`].
 pub struct SearchResults {
     path: Array,
+    reached_goal: Option<usize>,
+    /// Number of tiles the search visited.
     pub ops: u32,
+    /// Total cost of the path found.
     pub cost: u32,
+    /// Whether the search stopped before reaching a goal, because it ran out
+    /// of operations or exceeded `max_cost`. The path then leads to the
+    /// closest position found instead.
     pub incomplete: bool,
 }
 
@@ -447,11 +745,58 @@ impl SearchResults {
     pub fn opaque_path(&self) -> &Array {
         &self.path
     }
+
+    /// The index of the goal the path reached, in the order the goals were
+    /// given to [`search_many`].
+    ///
+    /// `None` for incomplete and flee searches. If the path ends in range of
+    /// multiple goals, this is the first of them.
+    #[inline]
+    pub fn reached_goal(&self) -> Option<usize> {
+        self.reached_goal
+    }
+
+    /// Number of steps in the path.
+    #[inline]
+    pub fn path_len(&self) -> usize {
+        self.path.len()
+    }
+
+    /// Iterates over the path's positions, not including the origin.
+    ///
+    /// Each position is converted from JavaScript only when it's reached, so
+    /// this is cheaper than [`SearchResults::load_local_path`] when only the
+    /// first few steps are needed.
+    pub fn path(&self) -> impl Iterator<Item = Position> + '_ {
+        (0..self.path.len()).map(move |i| js_unwrap!(@{&self.path}[@{i as u32}]))
+    }
+
+    /// Converts the whole path at once.
+    ///
+    /// On servers providing packed positions, this copies them all out in a
+    /// single typed array rather than converting each position separately.
     pub fn load_local_path(&self) -> Vec<Position> {
-        self.path
-            .clone()
-            .try_into()
-            .expect("expected PathFinder.search path result to be an array of RoomPositions")
+        let packed: Option<TypedArray<i32>> = js! {
+            const path = @{&self.path};
+            if (path.length > 0 && path[0].__packedPos === undefined) {
+                return null;
+            }
+            return Int32Array.from(path, (pos) => pos.__packedPos);
+        }
+        .try_into()
+        .expect("expected packed PathFinder.search path to be an Int32Array");
+        match packed {
+            Some(packed) => packed
+                .to_vec()
+                .into_iter()
+                .map(Position::from_packed)
+                .collect(),
+            None => {
+                self.path.clone().try_into().expect(
+                    "expected PathFinder.search path result to be an array of RoomPositions",
+                )
+            }
+        }
     }
 }
 
@@ -467,44 +812,59 @@ where
     G: ?Sized + HasPosition,
     F: FnMut(RoomName) -> MultiRoomCostResult<'a> + 'a,
 {
-    let pos = goal.pos();
-    search_real(
-        origin.pos(),
-        &js_unwrap!({pos: pos_from_packed(@{pos.packed_repr()}), range: @{range}}),
-        opts,
-    )
+    search_real(origin.pos(), &[SearchGoal::new(goal.pos(), range)], opts)
 }
 
-/// Searches between a single origin and multiple goals.
-pub fn search_many<'a, O, G, I, F>(origin: &O, goal: G, opts: SearchOptions<'a, F>) -> SearchResults
+/// Searches between a single origin and multiple goals, stopping at whichever
+/// is cheapest to reach.
+///
+/// [`SearchResults::reached_goal`] tells which goal the path leads to.
+pub fn search_many<'a, O, G, F>(origin: &O, goal: G, opts: SearchOptions<'a, F>) -> SearchResults
 where
     O: HasPosition,
-    G: IntoIterator<Item = (I, u32)>,
-    I: HasPosition,
+    G: IntoIterator,
+    G::Item: Into<SearchGoal>,
     F: FnMut(RoomName) -> MultiRoomCostResult<'a> + 'a,
 {
-    let goals: Vec<Object> = goal
-        .into_iter()
-        .map(|(target, range)| {
-            let pos = target.pos();
-            js_unwrap!({pos: pos_from_packed(@{pos.packed_repr()}), range: @{range}})
-        })
-        .collect();
+    let goals: Vec<SearchGoal> = goal.into_iter().map(Into::into).collect();
     if goals.is_empty() {
         return SearchResults {
             cost: 0,
             incomplete: true,
             ops: 0,
             path: js_unwrap!([]),
+            reached_goal: None,
         };
     }
-    let goals_js: Reference = js_unwrap!(@{goals});
-    search_real(origin.pos(), &goals_js, opts)
+    search_real(origin.pos(), &goals, opts)
+}
+
+/// Searches for a path leading at least `range` tiles away from every threat.
+///
+/// This is [`search_many`] with [`SearchOptions::flee`] set, using the same
+/// range for every threat.
+pub fn find_flee_path<'a, O, T, I, F>(
+    origin: &O,
+    threats: T,
+    range: u32,
+    opts: SearchOptions<'a, F>,
+) -> SearchResults
+where
+    O: HasPosition,
+    T: IntoIterator<Item = I>,
+    I: HasPosition,
+    F: FnMut(RoomName) -> MultiRoomCostResult<'a> + 'a,
+{
+    search_many(
+        origin,
+        threats.into_iter().map(|threat| (threat, range)),
+        opts.flee(true),
+    )
 }
 
 fn search_real<'a, F>(
     origin: Position,
-    goal: &Reference,
+    goals: &[SearchGoal],
     opts: SearchOptions<'a, F>,
 ) -> SearchResults
 where
@@ -537,9 +897,16 @@ where
     let callback_lifetime_erased: &'static mut dyn FnMut(RoomName) -> Value =
         unsafe { mem::transmute(callback_type_erased) };
 
+    let goals_js: Vec<Object> = goals
+        .iter()
+        .map(|goal| {
+            js_unwrap!({pos: pos_from_packed(@{goal.pos.packed_repr()}), range: @{goal.range}})
+        })
+        .collect();
+
     let res: ::stdweb::Reference = js!(
         let cb = @{callback_lifetime_erased};
-        let res = PathFinder.search(pos_from_packed(@{origin.packed_repr()}), @{goal}, {
+        let res = PathFinder.search(pos_from_packed(@{origin.packed_repr()}), @{goals_js}, {
             roomCallback: cb,
             plainCost: @{plain_cost},
             swampCost: @{swamp_cost},
@@ -555,10 +922,175 @@ where
     .try_into()
     .expect("expected reference from search");
 
+    let path: Array = js_unwrap!(@{&res}.path);
+    let incomplete = js_unwrap!(@{&res}.incomplete);
+    let reached_goal = if flee || incomplete {
+        None
+    } else {
+        let end = if path.len() == 0 {
+            origin
+        } else {
+            js_unwrap!(@{&path}[@{&path}.length - 1])
+        };
+        goals
+            .iter()
+            .position(|goal| end.in_range_to(&goal.pos, goal.range))
+    };
+
     SearchResults {
-        path: js_unwrap!(@{&res}.path),
+        path,
         ops: js_unwrap!(@{&res}.ops),
         cost: js_unwrap!(@{&res}.cost),
-        incomplete: js_unwrap!(@{&res}.incomplete),
+        incomplete,
+        reached_goal,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{LocalCostMatrix, SparseCostMatrix};
+    use crate::local::LocalRoomTerrain;
+
+    #[test]
+    fn roads_tunnel_through_walls_but_not_obstacles() {
+        let mut bits = Box::new([0; 2500]);
+        // walls at (5, 5) and (6, 5); swamp at (7, 5)
+        bits[5 * 50 + 5] = 1;
+        bits[5 * 50 + 6] = 1;
+        bits[5 * 50 + 7] = 2;
+        let terrain = LocalRoomTerrain::new_from_bits(bits);
+
+        let matrix = LocalCostMatrix::movement_costs(
+            &terrain,
+            vec![(8, 5), (9, 5)],
+            vec![(5, 5), (7, 5), (9, 5), (10, 5)],
+        );
+        // tunnel
+        assert_eq!(matrix.get(5, 5), 1);
+        // wall with no road
+        assert_eq!(matrix.get(6, 5), 255);
+        // road on swamp and plain
+        assert_eq!(matrix.get(7, 5), 1);
+        assert_eq!(matrix.get(10, 5), 1);
+        // obstacles, with and without a road beneath
+        assert_eq!(matrix.get(8, 5), 255);
+        assert_eq!(matrix.get(9, 5), 255);
+        // untouched terrain
+        assert_eq!(matrix.get(0, 0), 2);
+    }
+
+    #[test]
+    fn diff_and_patch() {
+        let mut base = LocalCostMatrix::new();
+        base.set(0, 0, 5);
+        base.set(10, 12, 20);
+        base.set(49, 49, 255);
+
+        let mut changed = base.clone();
+        // new costs, changed costs and costs going back to 0
+        changed.set(3, 4, 255);
+        changed.set(10, 12, 21);
+        changed.set(0, 0, 0);
+        changed.set(49, 49, 0);
+
+        let patch = base.diff(&changed);
+        assert_eq!(
+            patch.iter().collect::<Vec<_>>(),
+            [((0, 0), 0), ((3, 4), 255), ((10, 12), 21), ((49, 49), 0)]
+        );
+        let mut patched = base.clone();
+        patched.apply_patch(&patch);
+        assert_eq!(patched, changed);
+
+        // and back again
+        let undo = changed.diff(&base);
+        assert_eq!(undo.len(), 4);
+        patched.apply_patch(&undo);
+        assert_eq!(patched, base);
+
+        assert!(base.diff(&base).is_empty());
+        let mut unchanged = base.clone();
+        unchanged.apply_patch(&SparseCostMatrix::new());
+        assert_eq!(unchanged, base);
+    }
+
+    fn sparse(costs: &[((u8, u8), u8)]) -> SparseCostMatrix {
+        let mut matrix = SparseCostMatrix::new();
+        for &((x, y), cost) in costs {
+            matrix.set(x, y, cost);
+        }
+        matrix
+    }
+
+    #[test]
+    fn sparse_iter_is_sorted() {
+        let matrix = sparse(&[((30, 1), 1), ((2, 40), 2), ((2, 3), 3), ((0, 49), 4)]);
+        assert_eq!(
+            matrix.iter().collect::<Vec<_>>(),
+            [((0, 49), 4), ((2, 3), 3), ((2, 40), 2), ((30, 1), 1)]
+        );
+    }
+
+    #[test]
+    fn sparse_clear_region_and_retain() {
+        let corners = [((4, 4), 1), ((4, 8), 2), ((9, 4), 3), ((9, 8), 4)];
+        let mut matrix = sparse(&corners);
+        matrix.set(3, 5, 5);
+        matrix.set(5, 3, 6);
+
+        // ranges include their start and exclude their end
+        matrix.clear_region(4..9, 4..8);
+        assert_eq!(
+            matrix.iter().collect::<Vec<_>>(),
+            [
+                ((3, 5), 5),
+                ((4, 8), 2),
+                ((5, 3), 6),
+                ((9, 4), 3),
+                ((9, 8), 4)
+            ]
+        );
+        matrix.clear_region(0..50, 8..9);
+        assert_eq!(
+            matrix.iter().collect::<Vec<_>>(),
+            [((3, 5), 5), ((5, 3), 6), ((9, 4), 3)]
+        );
+        let mut unchanged = sparse(&corners);
+        unchanged.clear_region(5..5, 0..50);
+        assert_eq!(unchanged, sparse(&corners));
+
+        matrix.retain(|(x, y), cost| x != 9 && y != 9 && cost > 5);
+        assert_eq!(matrix.iter().collect::<Vec<_>>(), [((5, 3), 6)]);
+        matrix.retain(|_, _| false);
+        assert!(matrix.is_empty());
+    }
+
+    #[test]
+    fn sparse_serde() {
+        let matrix = sparse(&[((49, 0), 255), ((0, 49), 1), ((7, 7), 0)]);
+        let json = serde_json::to_string(&matrix).unwrap();
+        assert_eq!(json, "[[0,49,1],[7,7,0],[49,0,255]]");
+        assert_eq!(
+            serde_json::from_str::<SparseCostMatrix>(&json).unwrap(),
+            matrix
+        );
+        assert_eq!(
+            serde_json::from_str::<SparseCostMatrix>("[]").unwrap(),
+            SparseCostMatrix::new()
+        );
+
+        // later entries for the same tile win
+        assert_eq!(
+            serde_json::from_str::<SparseCostMatrix>("[[1,2,3],[1,2,4]]").unwrap(),
+            sparse(&[((1, 2), 4)])
+        );
+
+        for bad in &["[[50,0,1]]", "[[0,50,1]]", "[[1,2]]", "[[1,2,256]]", "{}"] {
+            assert!(
+                serde_json::from_str::<SparseCostMatrix>(bad).is_err(),
+                "{}",
+                bad
+            );
+        }
     }
 }
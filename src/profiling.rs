@@ -0,0 +1,204 @@
+//! Measuring CPU used by sections of code.
+//!
+//! Time is measured with [`cpu::get_used`], and totals are kept per label for
+//! the current tick. Nested measurements each include the CPU used by the
+//! measurements inside them.
+//!
+//! ```no_run
+//! use screeps::{console_log, measure, profiling};
+//!
+//! let creep_count = measure!("count creeps", { screeps::game::creeps::keys().len() });
+//!
+//! for (label, stats) in profiling::tick_stats() {
+//!     console_log!("{}: {:.2} over {} calls", label, stats.total, stats.calls);
+//! }
+//! # let _ = creep_count;
+//! ```
+//!
+//! [`cpu::get_used`]: crate::game::cpu::get_used
+use std::{cell::RefCell, collections::HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{game, raw_memory};
+
+/// CPU used under one label during a tick.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct LabelStats {
+    /// Number of measurements taken.
+    pub calls: u32,
+    /// Total CPU used over all measurements.
+    pub total: f64,
+}
+
+impl LabelStats {
+    /// Average CPU used per measurement.
+    pub fn average(&self) -> f64 {
+        if self.calls == 0 {
+            0.0
+        } else {
+            self.total / f64::from(self.calls)
+        }
+    }
+}
+
+#[derive(Default)]
+struct TickProfile {
+    tick: u32,
+    labels: HashMap<String, LabelStats>,
+}
+
+thread_local! {
+    static TICK_PROFILE: RefCell<TickProfile> = RefCell::new(TickProfile::default());
+}
+
+/// Runs `f` with the current tick's stats, forgetting stats from previous
+/// ticks.
+fn with_tick_profile<F, R>(f: F) -> R
+where
+    F: FnOnce(&mut HashMap<String, LabelStats>) -> R,
+{
+    let time = game::time();
+    TICK_PROFILE.with(|profile| {
+        let mut profile = profile.borrow_mut();
+        if profile.tick != time {
+            profile.tick = time;
+            profile.labels.clear();
+        }
+        f(&mut profile.labels)
+    })
+}
+
+/// Adds a measurement of `cpu` used to `label`'s stats for this tick.
+pub fn record(label: &str, cpu: f64) {
+    with_tick_profile(|labels| {
+        let stats = match labels.get_mut(label) {
+            Some(stats) => stats,
+            None => labels.entry(label.to_owned()).or_default(),
+        };
+        stats.calls += 1;
+        stats.total += cpu;
+    });
+}
+
+/// Stats for each label measured so far this tick.
+pub fn tick_stats() -> HashMap<String, LabelStats> {
+    with_tick_profile(|labels| labels.clone())
+}
+
+/// Measures CPU used from creation until it's dropped, recording it under
+/// `label`.
+///
+/// The [`measure!`] macro wraps a block with one of these.
+///
+/// [`measure!`]: crate::measure
+#[derive(Debug)]
+pub struct ScopedTimer<'a> {
+    label: &'a str,
+    start: f64,
+}
+
+impl<'a> ScopedTimer<'a> {
+    pub fn start(label: &'a str) -> ScopedTimer<'a> {
+        ScopedTimer {
+            label,
+            start: game::cpu::get_used(),
+        }
+    }
+}
+
+impl Drop for ScopedTimer<'_> {
+    fn drop(&mut self) {
+        record(self.label, game::cpu::get_used() - self.start);
+    }
+}
+
+/// Measures the CPU used by a block, recording it under a label in this
+/// tick's [`tick_stats`], and evaluates to the block's value.
+///
+/// ```no_run
+/// # use screeps::measure;
+/// let total = measure!("sum", { (0..100).sum::<u32>() });
+/// ```
+///
+/// [`tick_stats`]: crate::profiling::tick_stats
+#[macro_export]
+macro_rules! measure {
+    ($label:expr, $body:block) => {{
+        let _timer = $crate::profiling::ScopedTimer::start($label);
+        $body
+    }};
+}
+
+/// CPU used under one label over a number of recent ticks, as stored by
+/// [`save_rolling_stats`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RollingStats {
+    /// Total CPU used in each tick, oldest first.
+    pub ticks: Vec<f64>,
+}
+
+impl RollingStats {
+    /// Average CPU used per tick.
+    pub fn average(&self) -> f64 {
+        if self.ticks.is_empty() {
+            0.0
+        } else {
+            self.ticks.iter().sum::<f64>() / self.ticks.len() as f64
+        }
+    }
+
+    /// Most CPU used in a single tick.
+    pub fn max(&self) -> f64 {
+        self.ticks.iter().cloned().fold(0.0, f64::max)
+    }
+}
+
+/// Reads the rolling stats stored in memory segment `id`.
+///
+/// Returns `None` if the segment isn't active, and an empty map if it's empty
+/// or doesn't hold stats.
+pub fn load_rolling_stats(id: u32) -> Option<HashMap<String, RollingStats>> {
+    let data = raw_memory::get_segment(id)?;
+    Some(serde_json::from_str(&data).unwrap_or_default())
+}
+
+/// Adds this tick's totals to the rolling stats stored in memory segment `id`,
+/// keeping the last `window` ticks for each label.
+///
+/// Labels which weren't measured this tick record `0.0`, and are removed once
+/// they haven't been measured for `window` ticks. Should be called once at
+/// the end of each tick.
+///
+/// Returns `false` if the segment isn't active, or if the stats would be
+/// larger than [`MAX_SEGMENT_SIZE`], in which case the segment is left
+/// unchanged; use a smaller `window` or fewer labels.
+///
+/// [`MAX_SEGMENT_SIZE`]: crate::raw_memory::MAX_SEGMENT_SIZE
+pub fn save_rolling_stats(id: u32, window: usize) -> bool {
+    let mut rolling = match load_rolling_stats(id) {
+        Some(rolling) => rolling,
+        None => return false,
+    };
+    let tick = tick_stats();
+
+    for label in tick.keys() {
+        rolling.entry(label.clone()).or_default();
+    }
+    for (label, stats) in rolling.iter_mut() {
+        let total = tick.get(label).map(|s| s.total).unwrap_or(0.0);
+        stats.ticks.push(total);
+        if stats.ticks.len() > window {
+            let excess = stats.ticks.len() - window;
+            stats.ticks.drain(..excess);
+        }
+    }
+    rolling.retain(|_, stats| stats.ticks.iter().any(|&cpu| cpu > 0.0));
+
+    let data = serde_json::to_string(&rolling).expect("expected rolling stats to serialize");
+    if data.len() > raw_memory::MAX_SEGMENT_SIZE {
+        return false;
+    }
+    raw_memory::set_segment(id, &data);
+    true
+}
@@ -2,13 +2,30 @@
 //!
 //! [`RawMemory`]: https://docs.screeps.com/api/#RawMemory
 
-use serde::Deserialize;
+use serde::{de::DeserializeOwned, Deserialize};
+use stdweb::Value;
 
-#[derive(Deserialize, Debug)]
+use crate::traits::TryInto;
+
+mod blob_store;
+mod segment_manager;
+
+pub use self::{blob_store::*, segment_manager::*};
+
+/// Another player's public segment, requested with
+/// [`set_active_foreign_segment`].
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
 pub struct ForeignSegment {
-    username: String,
-    id: String,
-    data: String,
+    pub username: String,
+    pub id: u32,
+    pub data: String,
+}
+
+impl ForeignSegment {
+    /// Deserializes the segment's data from JSON.
+    pub fn parse<T: DeserializeOwned>(&self) -> serde_json::Result<T> {
+        serde_json::from_str(&self.data)
+    }
 }
 
 js_deserializable!(ForeignSegment);
@@ -51,8 +68,35 @@ pub fn drop_segment(id: u32) {
     }
 }
 
-pub fn get_foreign_segment() -> ForeignSegment {
-    js_unwrap!(RawMemory.foreignSegment)
+/// Gets the foreign segment requested with [`set_active_foreign_segment`] on
+/// the previous tick, or `None` if none was requested or it isn't public.
+///
+/// ```no_run
+/// use screeps::raw_memory;
+///
+/// if let Some(segment) = raw_memory::get_foreign_segment() {
+///     let allies: Vec<String> = segment.parse().unwrap_or_default();
+/// }
+///
+/// raw_memory::set_active_foreign_segment("ally", Some(90));
+/// ```
+pub fn get_foreign_segment() -> Option<ForeignSegment> {
+    let segment = js! {
+        const segment = RawMemory.foreignSegment;
+        if (segment) {
+            return { username: segment.username, id: Number(segment.id), data: segment.data };
+        } else {
+            return null;
+        }
+    };
+    match segment {
+        Value::Null => None,
+        segment => Some(
+            segment
+                .try_into()
+                .expect("expected RawMemory.foreignSegment to have a username, id and data"),
+        ),
+    }
 }
 
 /// Implements `RawMemory.setActiveForeignSegment`
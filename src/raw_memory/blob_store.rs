@@ -0,0 +1,299 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    error, fmt,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::segment_manager::{SegmentError, SegmentManager, MAX_SEGMENT_SIZE};
+
+/// Error from [`BlobStore::set`] or [`BlobStore::remove`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BlobStoreError {
+    /// The index segment isn't readable yet; it's been requested, so try again
+    /// next tick.
+    IndexPending,
+    /// There aren't enough unused data segments to hold the value.
+    OutOfSegments { needed: usize, free: usize },
+    /// A segment couldn't be written.
+    Segment(SegmentError),
+}
+
+impl fmt::Display for BlobStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlobStoreError::IndexPending => {
+                write!(f, "the blob store index segment isn't loaded yet")
+            }
+            BlobStoreError::OutOfSegments { needed, free } => write!(
+                f,
+                "value needs {} segments, but only {} are free",
+                needed, free
+            ),
+            BlobStoreError::Segment(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl error::Error for BlobStoreError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            BlobStoreError::Segment(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<SegmentError> for BlobStoreError {
+    fn from(e: SegmentError) -> Self {
+        BlobStoreError::Segment(e)
+    }
+}
+
+/// Result of [`BlobStore::get`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BlobRead {
+    /// The whole value has been read.
+    Ready(String),
+    /// Some segments holding the value (or the index) weren't active this
+    /// tick, and have been requested. Call `get` again next tick.
+    Pending,
+    /// No value is stored under the key.
+    Missing,
+}
+
+/// Index of which segments hold each value, stored as JSON in the index
+/// segment.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct BlobIndex {
+    blobs: BTreeMap<String, Vec<u32>>,
+}
+
+/// Stores string values under string keys, splitting values which are larger
+/// than a segment across multiple segments.
+///
+/// One segment holds the index of keys, and values are stored in whole
+/// segments out of a pool of data segments given to the store. All segment
+/// access goes through a [`SegmentManager`], so the manager's limits on active
+/// segments apply: values spread over more segments than can be active at once
+/// are read over multiple ticks, with [`BlobStore::get`] returning
+/// [`BlobRead::Pending`] until every part has been seen.
+///
+/// Like the manager, the store must be kept between ticks.
+#[derive(Clone, Debug)]
+pub struct BlobStore {
+    index_segment: u32,
+    data_segments: BTreeSet<u32>,
+    index: Option<BlobIndex>,
+    partial_reads: HashMap<String, Vec<Option<String>>>,
+}
+
+impl BlobStore {
+    /// Creates a store using `index_segment` for its index, and
+    /// `data_segments` for values.
+    pub fn new<I>(index_segment: u32, data_segments: I) -> BlobStore
+    where
+        I: IntoIterator<Item = u32>,
+    {
+        let mut data_segments: BTreeSet<u32> = data_segments.into_iter().collect();
+        data_segments.remove(&index_segment);
+        BlobStore {
+            index_segment,
+            data_segments,
+            index: None,
+            partial_reads: HashMap::new(),
+        }
+    }
+
+    fn load_index(&mut self, segments: &mut SegmentManager) -> Option<&mut BlobIndex> {
+        if self.index.is_none() {
+            let data = segments.read(self.index_segment)?;
+            self.index = Some(serde_json::from_str(&data).unwrap_or_default());
+        }
+        self.index.as_mut()
+    }
+
+    fn save_index(&mut self, segments: &mut SegmentManager) -> Result<(), BlobStoreError> {
+        let index = self.index.as_ref().ok_or(BlobStoreError::IndexPending)?;
+        let data = serde_json::to_string(index).expect("expected blob index to serialize");
+        segments.write(self.index_segment, data)?;
+        Ok(())
+    }
+
+    /// Keys of all stored values, or `None` if the index isn't loaded yet.
+    pub fn keys(&mut self, segments: &mut SegmentManager) -> Option<Vec<String>> {
+        self.load_index(segments)
+            .map(|index| index.blobs.keys().cloned().collect())
+    }
+
+    /// Number of data segments not holding any value.
+    pub fn free_segments(&mut self, segments: &mut SegmentManager) -> Option<usize> {
+        let data_segments = self.data_segments.clone();
+        self.load_index(segments)
+            .map(|index| free_segments(&data_segments, index).len())
+    }
+
+    /// Reads the value stored under `key`.
+    pub fn get(&mut self, segments: &mut SegmentManager, key: &str) -> BlobRead {
+        let chunk_ids = match self.load_index(segments) {
+            Some(index) => match index.blobs.get(key) {
+                Some(ids) => ids.clone(),
+                None => {
+                    self.partial_reads.remove(key);
+                    return BlobRead::Missing;
+                }
+            },
+            None => return BlobRead::Pending,
+        };
+
+        let chunks = self.partial_reads.entry(key.to_owned()).or_default();
+        chunks.resize(chunk_ids.len(), None);
+        for (chunk, &id) in chunks.iter_mut().zip(&chunk_ids) {
+            if chunk.is_none() {
+                *chunk = segments.read(id);
+            }
+        }
+
+        if chunks.iter().all(Option::is_some) {
+            let chunks = self.partial_reads.remove(key).unwrap_or_default();
+            BlobRead::Ready(chunks.into_iter().flatten().collect())
+        } else {
+            BlobRead::Pending
+        }
+    }
+
+    /// Stores `value` under `key`, replacing any previous value.
+    ///
+    /// The segments are written when the manager is next flushed.
+    pub fn set(
+        &mut self,
+        segments: &mut SegmentManager,
+        key: &str,
+        value: &str,
+    ) -> Result<(), BlobStoreError> {
+        let data_segments = self.data_segments.clone();
+        let index = self
+            .load_index(segments)
+            .ok_or(BlobStoreError::IndexPending)?;
+
+        let chunks = split_chunks(value, MAX_SEGMENT_SIZE);
+        let mut free = free_segments(&data_segments, index);
+        // the segments holding the old value can be reused
+        if let Some(old) = index.blobs.get(key) {
+            free.extend(old);
+        }
+        if chunks.len() > free.len() {
+            return Err(BlobStoreError::OutOfSegments {
+                needed: chunks.len(),
+                free: free.len(),
+            });
+        }
+
+        let ids: Vec<u32> = free.into_iter().take(chunks.len()).collect();
+        for (&id, chunk) in ids.iter().zip(chunks) {
+            segments.write(id, chunk.to_owned())?;
+        }
+        index.blobs.insert(key.to_owned(), ids);
+        self.partial_reads.remove(key);
+
+        self.save_index(segments)
+    }
+
+    /// Removes the value stored under `key`, freeing its segments.
+    ///
+    /// Returns whether a value was stored.
+    pub fn remove(
+        &mut self,
+        segments: &mut SegmentManager,
+        key: &str,
+    ) -> Result<bool, BlobStoreError> {
+        let index = self
+            .load_index(segments)
+            .ok_or(BlobStoreError::IndexPending)?;
+        if index.blobs.remove(key).is_none() {
+            return Ok(false);
+        }
+        self.partial_reads.remove(key);
+        self.save_index(segments)?;
+        Ok(true)
+    }
+}
+
+/// Data segments not used by any value in `index`.
+fn free_segments(data_segments: &BTreeSet<u32>, index: &BlobIndex) -> BTreeSet<u32> {
+    let mut free = data_segments.clone();
+    for ids in index.blobs.values() {
+        for id in ids {
+            free.remove(id);
+        }
+    }
+    free
+}
+
+/// Splits `value` into pieces of at most `max_len` bytes, on character
+/// boundaries. An empty value is one empty piece.
+fn split_chunks(mut value: &str, max_len: usize) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    loop {
+        if value.len() <= max_len {
+            chunks.push(value);
+            return chunks;
+        }
+        let mut end = max_len;
+        while !value.is_char_boundary(end) {
+            end -= 1;
+        }
+        let (chunk, rest) = value.split_at(end);
+        chunks.push(chunk);
+        value = rest;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use super::{free_segments, split_chunks, BlobIndex};
+
+    #[test]
+    fn free_segments_excludes_used() {
+        let mut index = BlobIndex::default();
+        index.blobs.insert("a".to_owned(), vec![1, 3]);
+        index.blobs.insert("b".to_owned(), vec![4]);
+        // segments outside the pool don't matter
+        index.blobs.insert("c".to_owned(), vec![90]);
+
+        let pool: BTreeSet<u32> = (1..=5).collect();
+        assert_eq!(
+            free_segments(&pool, &index),
+            vec![2, 5].into_iter().collect()
+        );
+        assert_eq!(free_segments(&pool, &BlobIndex::default()), pool);
+    }
+
+    #[test]
+    fn split_chunks_ascii() {
+        assert_eq!(split_chunks("", 3), vec![""]);
+        assert_eq!(split_chunks("ab", 3), vec!["ab"]);
+        // exact multiples don't leave an empty piece at the end
+        assert_eq!(split_chunks("abc", 3), vec!["abc"]);
+        assert_eq!(split_chunks("abcdef", 3), vec!["abc", "def"]);
+        assert_eq!(split_chunks("abcdefg", 3), vec!["abc", "def", "g"]);
+    }
+
+    #[test]
+    fn split_chunks_multibyte() {
+        // 'é' is 2 bytes and '€' is 3, so most splits have to back off to
+        // the start of a character
+        assert_eq!(split_chunks("éé", 3), vec!["é", "é"]);
+        assert_eq!(split_chunks("€€", 4), vec!["€", "€"]);
+        assert_eq!(split_chunks("aé€b", 3), vec!["aé", "€", "b"]);
+
+        let value = "a€éb€€ééc";
+        for max_len in 3..=value.len() {
+            let chunks = split_chunks(value, max_len);
+            assert!(chunks.iter().all(|c| c.len() <= max_len && !c.is_empty()));
+            assert_eq!(chunks.concat(), value);
+        }
+    }
+}
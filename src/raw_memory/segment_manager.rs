@@ -0,0 +1,309 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    error, fmt,
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::{get_active_segments, get_segment, set_active_segments, set_segment};
+
+/// Maximum number of segments which can be active at once.
+pub const MAX_ACTIVE_SEGMENTS: usize = 10;
+
+/// Maximum length of the data in one segment.
+pub const MAX_SEGMENT_SIZE: usize = 100 * 1024;
+
+/// Highest valid segment id; ids start at 0.
+pub const MAX_SEGMENT_ID: u32 = 99;
+
+/// Error from [`SegmentManager::pin`], [`SegmentManager::write`],
+/// [`SegmentManager::get`] or [`SegmentManager::set`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SegmentError {
+    /// The id was above [`MAX_SEGMENT_ID`].
+    InvalidId(u32),
+    /// The data was longer than [`MAX_SEGMENT_SIZE`].
+    TooLarge { id: u32, size: usize },
+    /// [`MAX_ACTIVE_SEGMENTS`] segments were already pinned.
+    TooManyPinned(u32),
+    /// The segment's data couldn't be deserialized as JSON of the requested
+    /// type.
+    Deserialize { id: u32, message: String },
+    /// The value couldn't be serialized as JSON.
+    Serialize { id: u32, message: String },
+}
+
+impl fmt::Display for SegmentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SegmentError::InvalidId(id) => write!(
+                f,
+                "segment id {} is out of range, the highest id is {}",
+                id, MAX_SEGMENT_ID
+            ),
+            SegmentError::TooLarge { id, size } => write!(
+                f,
+                "data for segment {} is {} bytes, over the {} byte limit",
+                id, size, MAX_SEGMENT_SIZE
+            ),
+            SegmentError::TooManyPinned(id) => write!(
+                f,
+                "can't pin segment {}, {} segments are already pinned",
+                id, MAX_ACTIVE_SEGMENTS
+            ),
+            SegmentError::Deserialize { id, message } => {
+                write!(f, "failed to deserialize segment {}: {}", id, message)
+            }
+            SegmentError::Serialize { id, message } => {
+                write!(
+                    f,
+                    "failed to serialize data for segment {}: {}",
+                    id, message
+                )
+            }
+        }
+    }
+}
+
+impl error::Error for SegmentError {}
+
+/// Keeps track of which segments are active, and batches segment requests
+/// and writes so the active segment limits are never exceeded.
+///
+/// Segments requested with `setActiveSegments` only become readable on the
+/// next tick, so reading a segment which isn't active queues it to be
+/// requested, and the read should be retried next tick. Writes are cached
+/// until [`SegmentManager::flush`], which should be called once at the end of
+/// every tick.
+///
+/// The manager must be kept between ticks, for instance in a `thread_local!`.
+///
+/// ```no_run
+/// use std::collections::BTreeMap;
+///
+/// use screeps::raw_memory::SegmentManager;
+///
+/// let mut segments = SegmentManager::new();
+/// segments.pin(0).unwrap();
+///
+/// let mut counts: BTreeMap<String, u32> = segments.get(0).unwrap().unwrap_or_default();
+/// *counts.entry("ticks".to_owned()).or_insert(0) += 1;
+/// segments.set(0, &counts).unwrap();
+///
+/// segments.flush();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SegmentManager {
+    pinned: BTreeSet<u32>,
+    queued: BTreeSet<u32>,
+    requested: BTreeSet<u32>,
+    writes: BTreeMap<u32, String>,
+}
+
+impl SegmentManager {
+    pub fn new() -> SegmentManager {
+        SegmentManager::default()
+    }
+
+    /// Keeps a segment active every tick. Pinned segments count against the
+    /// [`MAX_ACTIVE_SEGMENTS`] limit first, before any queued reads, so at
+    /// most that many segments can be pinned.
+    pub fn pin(&mut self, id: u32) -> Result<(), SegmentError> {
+        if id > MAX_SEGMENT_ID {
+            return Err(SegmentError::InvalidId(id));
+        }
+        if !self.pinned.contains(&id) && self.pinned.len() >= MAX_ACTIVE_SEGMENTS {
+            return Err(SegmentError::TooManyPinned(id));
+        }
+        self.pinned.insert(id);
+        Ok(())
+    }
+
+    pub fn unpin(&mut self, id: u32) {
+        self.pinned.remove(&id);
+    }
+
+    /// Whether the segment can be read this tick.
+    pub fn is_available(&self, id: u32) -> bool {
+        self.writes.contains_key(&id) || get_active_segments().contains(&id)
+    }
+
+    /// Segments which were passed to `setActiveSegments` by the last
+    /// [`SegmentManager::flush`], and so should be readable this tick.
+    pub fn requested(&self) -> impl Iterator<Item = u32> + '_ {
+        self.requested.iter().cloned()
+    }
+
+    /// Segments waiting to be requested.
+    pub fn queued(&self) -> impl Iterator<Item = u32> + '_ {
+        self.queued.iter().cloned()
+    }
+
+    /// Reads a segment, including writes which haven't been flushed yet.
+    ///
+    /// If the segment isn't active this tick, returns `None` and queues the
+    /// segment to be requested.
+    pub fn read(&mut self, id: u32) -> Option<String> {
+        if let Some(data) = self.writes.get(&id) {
+            return Some(data.clone());
+        }
+        let data = get_segment(id);
+        if data.is_none() {
+            self.request(id);
+        }
+        data
+    }
+
+    /// Queues a segment to be requested with the next flush, making it
+    /// readable next tick.
+    pub fn request(&mut self, id: u32) {
+        if id <= MAX_SEGMENT_ID {
+            self.queued.insert(id);
+        }
+    }
+
+    /// Caches data to be written to a segment by the next flush, replacing any
+    /// earlier cached write to it.
+    pub fn write(&mut self, id: u32, data: String) -> Result<(), SegmentError> {
+        if id > MAX_SEGMENT_ID {
+            return Err(SegmentError::InvalidId(id));
+        }
+        if data.len() > MAX_SEGMENT_SIZE {
+            return Err(SegmentError::TooLarge {
+                id,
+                size: data.len(),
+            });
+        }
+        self.writes.insert(id, data);
+        Ok(())
+    }
+
+    /// Reads a segment and deserializes it from JSON.
+    ///
+    /// Returns `Ok(None)` if the segment is empty, or if it isn't active this
+    /// tick, in which case it's queued like with [`SegmentManager::read`].
+    pub fn get<T: DeserializeOwned>(&mut self, id: u32) -> Result<Option<T>, SegmentError> {
+        if id > MAX_SEGMENT_ID {
+            return Err(SegmentError::InvalidId(id));
+        }
+        match self.read(id) {
+            Some(data) if !data.is_empty() => {
+                serde_json::from_str(&data)
+                    .map(Some)
+                    .map_err(|e| SegmentError::Deserialize {
+                        id,
+                        message: e.to_string(),
+                    })
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Serializes a value as JSON and caches it to be written to a segment,
+    /// like [`SegmentManager::write`].
+    pub fn set<T: Serialize + ?Sized>(&mut self, id: u32, value: &T) -> Result<(), SegmentError> {
+        let data = serde_json::to_string(value).map_err(|e| SegmentError::Serialize {
+            id,
+            message: e.to_string(),
+        })?;
+        self.write(id, data)
+    }
+
+    /// Whether a write to the segment is waiting for a flush.
+    pub fn has_pending_write(&self, id: u32) -> bool {
+        self.writes.contains_key(&id)
+    }
+
+    /// Writes cached data to segments, and requests segments for next tick.
+    ///
+    /// At most [`MAX_ACTIVE_SEGMENTS`] segments can be loaded at once, so
+    /// writes to segments which aren't active are only made while there's
+    /// room, with the rest kept for the next flush. Pinned segments are then
+    /// requested, followed by as many queued segments as fit.
+    pub fn flush(&mut self) {
+        let mut loaded: BTreeSet<u32> = get_active_segments().into_iter().collect();
+
+        let writes = std::mem::take(&mut self.writes);
+        for (id, data) in writes {
+            if loaded.contains(&id) || loaded.len() < MAX_ACTIVE_SEGMENTS {
+                set_segment(id, &data);
+                loaded.insert(id);
+            } else {
+                self.writes.insert(id, data);
+            }
+        }
+
+        let next = next_requests(&self.pinned, &mut self.queued);
+        let ids: Vec<u32> = next.iter().cloned().collect();
+        set_active_segments(&ids);
+        self.requested = next;
+    }
+}
+
+/// Chooses the segments to request: all `pinned` segments, then as many
+/// `queued` ones as fit, lowest ids first. Chosen segments are removed from
+/// `queued`.
+fn next_requests(pinned: &BTreeSet<u32>, queued: &mut BTreeSet<u32>) -> BTreeSet<u32> {
+    let mut next = pinned.clone();
+    let room = MAX_ACTIVE_SEGMENTS.saturating_sub(next.len());
+    next.extend(
+        queued
+            .iter()
+            .cloned()
+            .filter(|id| !pinned.contains(id))
+            .take(room)
+            .collect::<Vec<u32>>(),
+    );
+    queued.retain(|id| !next.contains(id));
+    next
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use super::{next_requests, SegmentError, SegmentManager, MAX_ACTIVE_SEGMENTS};
+
+    #[test]
+    fn pin_checks_ids_and_count() {
+        let mut segments = SegmentManager::new();
+        assert_eq!(segments.pin(100), Err(SegmentError::InvalidId(100)));
+        for id in 0..MAX_ACTIVE_SEGMENTS as u32 {
+            assert_eq!(segments.pin(id * 11), Ok(()));
+        }
+        // pinning an already pinned segment again is fine
+        assert_eq!(segments.pin(0), Ok(()));
+        assert_eq!(segments.pin(1), Err(SegmentError::TooManyPinned(1)));
+
+        segments.unpin(0);
+        assert_eq!(segments.pin(1), Ok(()));
+    }
+
+    #[test]
+    fn requests_pinned_then_queued() {
+        assert!(next_requests(&BTreeSet::new(), &mut BTreeSet::new()).is_empty());
+
+        let pinned: BTreeSet<u32> = [5, 50].iter().cloned().collect();
+        let mut queued: BTreeSet<u32> = (0..20).collect();
+
+        let next = next_requests(&pinned, &mut queued);
+        let expected: BTreeSet<u32> = vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 50].into_iter().collect();
+        assert_eq!(next, expected);
+        assert_eq!(queued, (9..20).collect());
+
+        let next = next_requests(&pinned, &mut queued);
+        assert_eq!(next.len(), MAX_ACTIVE_SEGMENTS);
+        assert_eq!(queued, (17..20).collect());
+
+        let next = next_requests(&pinned, &mut queued);
+        assert_eq!(next, vec![5, 17, 18, 19, 50].into_iter().collect());
+        assert!(queued.is_empty());
+
+        // queued segments which are pinned are requested only once, and
+        // dropped from the queue
+        let mut queued: BTreeSet<u32> = vec![5, 7].into_iter().collect();
+        let next = next_requests(&pinned, &mut queued);
+        assert_eq!(next, vec![5, 7, 50].into_iter().collect());
+        assert!(queued.is_empty());
+    }
+}
@@ -0,0 +1,250 @@
+//! Choosing which structures to repair first.
+//!
+//! [`RepairPriority::queue`] scores every structure in a room which needs
+//! repair and sorts them most urgent first, so towers and workers can agree
+//! on targets. Walls and ramparts are only repaired up to a target which
+//! grows with the room's controller level, rather than to their millions of
+//! maximum hits.
+//!
+//! ```no_run
+//! use screeps::{game, repair::RepairPriority, RoomName};
+//!
+//! let room = game::rooms::get(RoomName::new("W1N1").unwrap()).unwrap();
+//!
+//! let queue = RepairPriority::new().defense_hits(50_000).queue(&room);
+//! if let Some(target) = queue.first() {
+//!     // repair `target.structure`
+//! }
+//! ```
+use std::cmp::Ordering;
+
+use crate::{
+    constants::{
+        find, StructureType, CONTAINER_DECAY, CONTAINER_DECAY_TIME, CONTAINER_DECAY_TIME_OWNED,
+        RAMPART_DECAY_AMOUNT, RAMPART_DECAY_TIME, ROAD_DECAY_AMOUNT, ROAD_DECAY_TIME,
+    },
+    objects::{OwnedStructureProperties, Room, Structure, StructureProperties},
+};
+
+/// Default hits walls and ramparts are repaired to at each controller level.
+pub fn default_defense_hits(rcl: u32) -> u32 {
+    match rcl {
+        0 | 1 => 0,
+        2 => 10_000,
+        3 => 50_000,
+        4 => 100_000,
+        5 => 300_000,
+        6 => 1_000_000,
+        7 => 3_000_000,
+        _ => 10_000_000,
+    }
+}
+
+/// Ticks until a structure which loses `amount` hits every `time` ticks is
+/// destroyed by decay, given its hits and its next decay in `ticks_to_decay`.
+fn ticks_until_decayed(hits: u32, ticks_to_decay: u32, amount: u32, time: u32) -> u32 {
+    let decays_left = (f64::from(hits) / f64::from(amount)).ceil() as u32;
+    ticks_to_decay + decays_left.saturating_sub(1) * time
+}
+
+/// A structure which needs repair, and how urgently.
+#[derive(Clone)]
+pub struct RepairTarget {
+    pub structure: Structure,
+    pub hits: u32,
+    /// Hits the structure should be repaired to.
+    pub target_hits: u32,
+    /// Higher is more urgent: the fraction of `target_hits` missing, plus `1`
+    /// if decay would destroy the structure within
+    /// [`RepairPriority::urgent_ticks`].
+    pub score: f64,
+}
+
+/// Settings for scoring structures for repair.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RepairPriority {
+    defense_hits: Option<u32>,
+    urgent_ticks: u32,
+}
+
+impl Default for RepairPriority {
+    fn default() -> Self {
+        RepairPriority {
+            defense_hits: None,
+            urgent_ticks: 1000,
+        }
+    }
+}
+
+impl RepairPriority {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the hits walls and ramparts are repaired to - default is
+    /// [`default_defense_hits`] for the room's controller level.
+    pub fn defense_hits(mut self, hits: u32) -> Self {
+        self.defense_hits = Some(hits);
+        self
+    }
+
+    /// Sets how soon decay must be due to destroy a road, container or
+    /// rampart for it to be repaired before anything else - default `1000`.
+    pub fn urgent_ticks(mut self, ticks: u32) -> Self {
+        self.urgent_ticks = ticks;
+        self
+    }
+
+    /// Whether a structure decay will destroy in `ticks_left` ticks should
+    /// be repaired before anything else.
+    fn is_urgent(&self, ticks_left: u32) -> bool {
+        ticks_left <= self.urgent_ticks
+    }
+
+    /// Scores one structure, or returns `None` if it doesn't need repair or
+    /// can't be repaired.
+    ///
+    /// `rcl` and `owned_room` describe the structure's room, and are used
+    /// for defense targets and container decay rates. Road decay is assumed
+    /// to be for plain terrain, which makes roads on swamps and walls look
+    /// less urgent than they are.
+    pub fn score(&self, structure: &Structure, rcl: u32, owned_room: bool) -> Option<RepairTarget> {
+        // novice area walls and other indestructible structures
+        let attackable = structure.as_attackable()?;
+        let hits = attackable.hits();
+        let hits_max = attackable.hits_max();
+        if hits_max == 0 {
+            return None;
+        }
+
+        let structure_type = structure.structure_type();
+        let target_hits = match structure_type {
+            StructureType::Wall | StructureType::Rampart => self
+                .defense_hits
+                .unwrap_or_else(|| default_defense_hits(rcl))
+                .min(hits_max),
+            _ => hits_max,
+        };
+        if hits >= target_hits {
+            return None;
+        }
+
+        let decay = match structure_type {
+            StructureType::Road => Some((ROAD_DECAY_AMOUNT, ROAD_DECAY_TIME)),
+            StructureType::Rampart => Some((RAMPART_DECAY_AMOUNT, RAMPART_DECAY_TIME)),
+            StructureType::Container if owned_room => {
+                Some((CONTAINER_DECAY, CONTAINER_DECAY_TIME_OWNED))
+            }
+            StructureType::Container => Some((CONTAINER_DECAY, CONTAINER_DECAY_TIME)),
+            _ => None,
+        };
+        let urgent = match (decay, structure.as_can_decay()) {
+            (Some((amount, time)), Some(decaying)) => self.is_urgent(ticks_until_decayed(
+                hits,
+                decaying.ticks_to_decay(),
+                amount,
+                time,
+            )),
+            _ => false,
+        };
+
+        let missing = 1.0 - f64::from(hits) / f64::from(target_hits);
+        Some(RepairTarget {
+            structure: structure.clone(),
+            hits,
+            target_hits,
+            score: missing + if urgent { 1.0 } else { 0.0 },
+        })
+    }
+
+    /// Scores each structure in `room` which is yours or unowned and needs
+    /// repair, most urgent first.
+    pub fn queue(&self, room: &Room) -> Vec<RepairTarget> {
+        let controller = room.controller();
+        let owned_room = controller.as_ref().map(|c| c.my()).unwrap_or(false);
+        let rcl = match &controller {
+            Some(c) if owned_room => c.level(),
+            _ => 0,
+        };
+
+        let mut queue: Vec<RepairTarget> = room
+            .find(find::STRUCTURES)
+            .iter()
+            .filter(|structure| match structure.as_owned() {
+                Some(owned) => owned.my(),
+                None => true,
+            })
+            .filter_map(|structure| self.score(structure, rcl, owned_room))
+            .collect();
+        queue.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal));
+        queue
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{default_defense_hits, ticks_until_decayed, RepairPriority};
+    use crate::constants::{
+        CONTAINER_DECAY, CONTAINER_DECAY_TIME_OWNED, RAMPART_DECAY_AMOUNT, RAMPART_DECAY_TIME,
+        ROAD_DECAY_AMOUNT, ROAD_DECAY_TIME,
+    };
+
+    #[test]
+    fn decay_to_destruction() {
+        let road = |hits, ticks_to_decay| {
+            ticks_until_decayed(hits, ticks_to_decay, ROAD_DECAY_AMOUNT, ROAD_DECAY_TIME)
+        };
+        // 100 hits lost every 1000 ticks
+        assert_eq!(road(5000, 300), 300 + 49 * 1000);
+        assert_eq!(road(101, 300), 1300);
+        assert_eq!(road(100, 300), 300);
+        assert_eq!(road(1, 1), 1);
+
+        // 300 hits lost every 100 ticks
+        let rampart = |hits, ticks_to_decay| {
+            ticks_until_decayed(
+                hits,
+                ticks_to_decay,
+                RAMPART_DECAY_AMOUNT,
+                RAMPART_DECAY_TIME,
+            )
+        };
+        assert_eq!(rampart(1, 50), 50);
+        assert_eq!(rampart(300_000, 100), 100 * 1000);
+
+        // containers in owned rooms lose 5000 hits every 500 ticks
+        assert_eq!(
+            ticks_until_decayed(250_000, 20, CONTAINER_DECAY, CONTAINER_DECAY_TIME_OWNED),
+            20 + 49 * 500
+        );
+    }
+
+    #[test]
+    fn urgent_cutoff() {
+        let ticks_left = |hits, ticks_to_decay| {
+            ticks_until_decayed(hits, ticks_to_decay, ROAD_DECAY_AMOUNT, ROAD_DECAY_TIME)
+        };
+        let default = RepairPriority::new();
+        assert!(default.is_urgent(ticks_left(100, 1000)));
+        assert!(!default.is_urgent(ticks_left(100, 1001)));
+        assert!(!default.is_urgent(ticks_left(101, 1)));
+
+        let sooner = RepairPriority::new().urgent_ticks(10);
+        assert!(sooner.is_urgent(ticks_left(100, 10)));
+        assert!(!sooner.is_urgent(ticks_left(100, 11)));
+        assert!(RepairPriority::new().urgent_ticks(0).is_urgent(0));
+    }
+
+    #[test]
+    fn defense_hits_by_level() {
+        assert_eq!(default_defense_hits(0), 0);
+        assert_eq!(default_defense_hits(1), 0);
+        assert_eq!(default_defense_hits(2), 10_000);
+        assert_eq!(default_defense_hits(4), 100_000);
+        assert_eq!(default_defense_hits(8), 10_000_000);
+        assert_eq!(default_defense_hits(9), 10_000_000);
+        for rcl in 1..=8 {
+            assert!(default_defense_hits(rcl) >= default_defense_hits(rcl - 1));
+        }
+    }
+}
@@ -0,0 +1,344 @@
+//! Deciding when to activate safe mode.
+//!
+//! [`SafeModeStatus`] reads a controller's safe mode fields and works out
+//! whether `StructureController.activateSafeMode` would succeed, without
+//! calling it. [`check`] combines that with a threat heuristic, such as
+//! [`default_threat`], into one [`SafeModeDecision`] per tick.
+//!
+//! ```no_run
+//! use screeps::{game, safe_mode, RoomName};
+//!
+//! let room = game::rooms::get(RoomName::new("W1N1").unwrap()).unwrap();
+//! let controller = room.controller().unwrap();
+//!
+//! if safe_mode::check(&controller, safe_mode::default_threat).should_activate() {
+//!     controller.activate_safe_mode();
+//! }
+//! ```
+use crate::{
+    constants::{
+        controller_downgrade, find, Part, ReturnCode, StructureType,
+        CONTROLLER_DOWNGRADE_SAFEMODE_THRESHOLD,
+    },
+    game,
+    objects::{
+        HasPosition, OwnedStructureProperties, Room, RoomObjectProperties, StructureController,
+        StructureProperties,
+    },
+};
+
+/// A controller's safe mode state, as of the current tick.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SafeModeStatus {
+    /// Whether the controller is yours.
+    pub my: bool,
+    pub level: u32,
+    /// Ticks of safe mode left, if it's active in this room.
+    pub active: Option<u32>,
+    /// Safe mode activations stored in the controller.
+    pub available: u32,
+    pub cooldown: Option<u32>,
+    pub upgrade_blocked: Option<u32>,
+    pub ticks_to_downgrade: u32,
+    /// Whether safe mode is active in any of your rooms, including this one.
+    pub active_anywhere: bool,
+}
+
+impl SafeModeStatus {
+    pub fn scan(controller: &StructureController) -> SafeModeStatus {
+        let active = controller.safe_mode();
+        let active_anywhere = active.is_some()
+            || game::rooms::values().iter().any(|room| {
+                room.controller()
+                    .map(|c| c.my() && c.safe_mode().is_some())
+                    .unwrap_or(false)
+            });
+
+        SafeModeStatus {
+            my: controller.my(),
+            level: controller.level(),
+            active,
+            available: controller.safe_mode_available(),
+            cooldown: controller.safe_mode_cooldown(),
+            upgrade_blocked: controller.upgrade_blocked(),
+            ticks_to_downgrade: controller.ticks_to_downgrade(),
+            active_anywhere,
+        }
+    }
+
+    /// Whether the downgrade timer is too low to activate safe mode, as
+    /// described in [`CONTROLLER_DOWNGRADE_SAFEMODE_THRESHOLD`].
+    pub fn downgrade_too_low(&self) -> bool {
+        let threshold = controller_downgrade(self.level)
+            .map(|max| (max / 2).saturating_sub(CONTROLLER_DOWNGRADE_SAFEMODE_THRESHOLD))
+            .unwrap_or(0);
+        self.ticks_to_downgrade < threshold
+    }
+
+    /// The error `activateSafeMode` would return this tick, checked in the
+    /// same order as the game does, or `None` if it would succeed.
+    pub fn blocked_by(&self) -> Option<ReturnCode> {
+        if !self.my {
+            Some(ReturnCode::NotOwner)
+        } else if self.available == 0 {
+            Some(ReturnCode::NotEnough)
+        } else if self.cooldown.is_some()
+            || self.upgrade_blocked.map(|ticks| ticks > 0).unwrap_or(false)
+            || self.downgrade_too_low()
+        {
+            Some(ReturnCode::Tired)
+        } else if self.active_anywhere {
+            Some(ReturnCode::Busy)
+        } else {
+            None
+        }
+    }
+
+    /// Whether `activateSafeMode` would succeed this tick.
+    pub fn can_activate(&self) -> bool {
+        self.blocked_by().is_none()
+    }
+
+    /// Decides whether to activate safe mode, given whether the room is
+    /// `threatened`.
+    pub fn decide(&self, threatened: bool) -> SafeModeDecision {
+        if let Some(ticks) = self.active {
+            SafeModeDecision::AlreadyActive(ticks)
+        } else if !threatened {
+            SafeModeDecision::NoThreat
+        } else if let Some(code) = self.blocked_by() {
+            SafeModeDecision::Unavailable(code)
+        } else {
+            SafeModeDecision::Activate
+        }
+    }
+}
+
+/// What to do about safe mode this tick, from [`SafeModeStatus::decide`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SafeModeDecision {
+    /// The room is threatened and safe mode can be activated.
+    Activate,
+    /// Safe mode is already active in this room, with this many ticks left.
+    AlreadyActive(u32),
+    /// Safe mode isn't needed.
+    NoThreat,
+    /// The room is threatened, but activating safe mode would fail with
+    /// this error.
+    Unavailable(ReturnCode),
+}
+
+impl SafeModeDecision {
+    pub fn should_activate(&self) -> bool {
+        *self == SafeModeDecision::Activate
+    }
+}
+
+/// Decides whether to activate safe mode in the controller's room, calling
+/// `is_threatened` only when safe mode isn't already active.
+///
+/// Rooms which aren't visible are never threatened.
+pub fn check<F>(controller: &StructureController, is_threatened: F) -> SafeModeDecision
+where
+    F: FnOnce(&Room) -> bool,
+{
+    let status = SafeModeStatus::scan(controller);
+    if status.active.is_some() {
+        return status.decide(false);
+    }
+    let threatened = controller
+        .room()
+        .map(|room| is_threatened(&room))
+        .unwrap_or(false);
+    status.decide(threatened)
+}
+
+/// Range from spawns, towers, storage, terminals and the controller within
+/// which [`default_threat`] considers hostile creeps a threat.
+const THREAT_RANGE: u32 = 3;
+
+/// A threat heuristic for [`check`]: whether any hostile creep able to
+/// attack, dismantle or attack the controller is within range 3 of one of
+/// your spawns, towers, storage or terminal, or of the controller.
+pub fn default_threat(room: &Room) -> bool {
+    let hostiles: Vec<_> = room
+        .find(find::HOSTILE_CREEPS)
+        .into_iter()
+        .filter(|creep| {
+            [Part::Attack, Part::RangedAttack, Part::Work, Part::Claim]
+                .iter()
+                .any(|&part| creep.get_active_bodyparts(part) > 0)
+        })
+        .map(|creep| creep.pos())
+        .collect();
+    if hostiles.is_empty() {
+        return false;
+    }
+
+    let mut protected: Vec<_> = room
+        .find(find::MY_STRUCTURES)
+        .into_iter()
+        .filter(|structure| {
+            matches!(
+                structure.structure_type(),
+                StructureType::Spawn
+                    | StructureType::Tower
+                    | StructureType::Storage
+                    | StructureType::Terminal
+            )
+        })
+        .map(|structure| structure.pos())
+        .collect();
+    protected.extend(room.controller().map(|controller| controller.pos()));
+
+    hostiles.iter().any(|hostile| {
+        protected
+            .iter()
+            .any(|pos| hostile.get_range_to(pos) <= THREAT_RANGE)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SafeModeDecision, SafeModeStatus};
+    use crate::constants::ReturnCode;
+
+    /// A level 8 controller which can activate safe mode.
+    fn ready() -> SafeModeStatus {
+        SafeModeStatus {
+            my: true,
+            level: 8,
+            active: None,
+            available: 1,
+            cooldown: None,
+            upgrade_blocked: None,
+            ticks_to_downgrade: 200_000,
+            active_anywhere: false,
+        }
+    }
+
+    #[test]
+    fn blocked_by_each_error() {
+        assert_eq!(ready().blocked_by(), None);
+        assert!(ready().can_activate());
+
+        let cases = [
+            (
+                SafeModeStatus {
+                    my: false,
+                    ..ready()
+                },
+                ReturnCode::NotOwner,
+            ),
+            (
+                SafeModeStatus {
+                    available: 0,
+                    ..ready()
+                },
+                ReturnCode::NotEnough,
+            ),
+            (
+                SafeModeStatus {
+                    cooldown: Some(300),
+                    ..ready()
+                },
+                ReturnCode::Tired,
+            ),
+            (
+                SafeModeStatus {
+                    upgrade_blocked: Some(20),
+                    ..ready()
+                },
+                ReturnCode::Tired,
+            ),
+            (
+                SafeModeStatus {
+                    ticks_to_downgrade: 1000,
+                    ..ready()
+                },
+                ReturnCode::Tired,
+            ),
+            (
+                SafeModeStatus {
+                    active_anywhere: true,
+                    ..ready()
+                },
+                ReturnCode::Busy,
+            ),
+        ];
+        for (status, code) in cases.iter() {
+            assert_eq!(status.blocked_by(), Some(*code), "{:?}", status);
+            assert!(!status.can_activate());
+        }
+
+        // an upgrade block which has run out doesn't count
+        let unblocked = SafeModeStatus {
+            upgrade_blocked: Some(0),
+            ..ready()
+        };
+        assert_eq!(unblocked.blocked_by(), None);
+
+        // errors are checked in the game's order
+        let everything = SafeModeStatus {
+            my: false,
+            available: 0,
+            cooldown: Some(300),
+            active_anywhere: true,
+            ..ready()
+        };
+        assert_eq!(everything.blocked_by(), Some(ReturnCode::NotOwner));
+        let mine = SafeModeStatus {
+            my: true,
+            ..everything
+        };
+        assert_eq!(mine.blocked_by(), Some(ReturnCode::NotEnough));
+        let available = SafeModeStatus {
+            available: 2,
+            ..mine
+        };
+        assert_eq!(available.blocked_by(), Some(ReturnCode::Tired));
+    }
+
+    #[test]
+    fn downgrade_threshold() {
+        // half of level 8's 200,000 downgrade ticks, less 5000
+        let at = |level, ticks_to_downgrade| SafeModeStatus {
+            level,
+            ticks_to_downgrade,
+            ..ready()
+        };
+        assert!(!at(8, 95_000).downgrade_too_low());
+        assert!(at(8, 94_999).downgrade_too_low());
+        assert!(!at(4, 15_000).downgrade_too_low());
+        assert!(at(4, 14_999).downgrade_too_low());
+        // level 2 has only 10,000 downgrade ticks, so the threshold is 0
+        assert!(!at(2, 0).downgrade_too_low());
+        assert!(!at(0, 0).downgrade_too_low());
+    }
+
+    #[test]
+    fn decisions() {
+        assert_eq!(ready().decide(true), SafeModeDecision::Activate);
+        assert!(ready().decide(true).should_activate());
+        assert_eq!(ready().decide(false), SafeModeDecision::NoThreat);
+
+        let active = SafeModeStatus {
+            active: Some(1234),
+            active_anywhere: true,
+            ..ready()
+        };
+        assert_eq!(active.decide(true), SafeModeDecision::AlreadyActive(1234));
+        assert_eq!(active.decide(false), SafeModeDecision::AlreadyActive(1234));
+
+        let cooling = SafeModeStatus {
+            cooldown: Some(300),
+            ..ready()
+        };
+        assert_eq!(
+            cooling.decide(true),
+            SafeModeDecision::Unavailable(ReturnCode::Tired)
+        );
+        assert!(!cooling.decide(true).should_activate());
+        assert_eq!(cooling.decide(false), SafeModeDecision::NoThreat);
+    }
+}
@@ -0,0 +1,205 @@
+//! A [`log`] backend writing to the Screeps console.
+//!
+//! The simplest setup is a single call at the start of the first tick:
+//!
+//! ```no_run
+//! screeps::screeps_logging::init(log::LevelFilter::Info).unwrap();
+//! ```
+//!
+//! [`ScreepsLogger`] allows turning off colors, or changing how error
+//! messages are sent with [`game::notify`].
+//!
+//! [`set_panic_hook`] similarly writes panics to the console, rather than
+//! leaving only the opaque error from the WebAssembly trap.
+//!
+//! [`game::notify`]: crate::game::notify
+use std::{cell::RefCell, collections::HashMap, panic};
+
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+
+use crate::{console, game};
+
+thread_local! {
+    /// The tick each error message was last sent as a notification.
+    static LAST_NOTIFIED: RefCell<HashMap<String, u32>> = RefCell::new(HashMap::new());
+}
+
+/// Logger writing each record to the console as `[LEVEL] target: message`.
+///
+/// Records at [`Level::Error`] are also sent with [`game::notify`], unless
+/// the same message was already sent in the last `notify_throttle` ticks.
+///
+/// [`game::notify`]: crate::game::notify
+#[derive(Clone, Debug)]
+pub struct ScreepsLogger {
+    max_level: LevelFilter,
+    colored: bool,
+    notify_errors: bool,
+    notify_throttle: u32,
+    notify_group_interval: u32,
+}
+
+impl Default for ScreepsLogger {
+    fn default() -> ScreepsLogger {
+        ScreepsLogger {
+            max_level: LevelFilter::Info,
+            colored: true,
+            notify_errors: true,
+            notify_throttle: 100,
+            notify_group_interval: 0,
+        }
+    }
+}
+
+impl ScreepsLogger {
+    pub fn new() -> ScreepsLogger {
+        ScreepsLogger::default()
+    }
+
+    /// Most verbose level which is written. Default: `Info`
+    pub fn max_level(mut self, val: LevelFilter) -> ScreepsLogger {
+        self.max_level = val;
+        self
+    }
+
+    /// Whether to color each line by level, using HTML `<font>` tags.
+    /// Default: true
+    pub fn colored(mut self, val: bool) -> ScreepsLogger {
+        self.colored = val;
+        self
+    }
+
+    /// Whether to send error records with `Game.notify`. Default: true
+    pub fn notify_errors(mut self, val: bool) -> ScreepsLogger {
+        self.notify_errors = val;
+        self
+    }
+
+    /// Minimum number of ticks before an identical error message is sent
+    /// again. Default: 100
+    pub fn notify_throttle(mut self, ticks: u32) -> ScreepsLogger {
+        self.notify_throttle = ticks;
+        self
+    }
+
+    /// The `groupInterval` passed to `Game.notify`, in minutes. Default: 0
+    pub fn notify_group_interval(mut self, minutes: u32) -> ScreepsLogger {
+        self.notify_group_interval = minutes;
+        self
+    }
+
+    /// Installs this as the global logger.
+    ///
+    /// Fails if a logger was already installed, for instance on a previous
+    /// tick in the same global.
+    pub fn init(self) -> Result<(), SetLoggerError> {
+        let max_level = self.max_level;
+        log::set_logger(Box::leak(Box::new(self)))?;
+        log::set_max_level(max_level);
+        Ok(())
+    }
+
+    fn format(&self, record: &Record<'_>) -> String {
+        let line = format!(
+            "[{}] {}: {}",
+            record.level(),
+            record.target(),
+            record.args()
+        );
+        if self.colored {
+            format!(
+                "<font color=\"{}\">{}</font>",
+                level_color(record.level()),
+                line
+            )
+        } else {
+            line
+        }
+    }
+
+    fn notify(&self, message: String) {
+        let time = game::time();
+        let throttle = self.notify_throttle;
+        let send = LAST_NOTIFIED.with(|last_notified| {
+            let mut last_notified = last_notified.borrow_mut();
+            last_notified.retain(|_, &mut tick| time.saturating_sub(tick) < throttle);
+            if last_notified.contains_key(&message) {
+                return None;
+            }
+            last_notified.insert(message.clone(), time);
+            Some(message)
+        });
+        if let Some(message) = send {
+            game::notify(&message, Some(self.notify_group_interval));
+        }
+    }
+}
+
+impl Log for ScreepsLogger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.level() <= self.max_level
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        console::log(&self.format(record));
+        if self.notify_errors && record.level() == Level::Error {
+            self.notify(format!("{}: {}", record.target(), record.args()));
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+pub(crate) fn level_color(level: Level) -> &'static str {
+    match level {
+        Level::Error => "#ff5555",
+        Level::Warn => "#ffaa33",
+        Level::Info => "#ffffff",
+        Level::Debug => "#77aaff",
+        Level::Trace => "#999999",
+    }
+}
+
+/// Installs a [`ScreepsLogger`] with default settings writing records up to
+/// `max_level`.
+///
+/// Fails if a logger was already installed.
+pub fn init(max_level: LevelFilter) -> Result<(), SetLoggerError> {
+    ScreepsLogger::new().max_level(max_level).init()
+}
+
+/// Replaces the panic hook with one writing the panic message, its location
+/// and the JavaScript stack trace to the console in the error color, and
+/// sending the message and location with [`game::notify`].
+///
+/// [`game::notify`]: crate::game::notify
+pub fn set_panic_hook() {
+    panic::set_hook(Box::new(|info| {
+        let payload = info.payload();
+        let message = match payload.downcast_ref::<&str>() {
+            Some(s) => *s,
+            None => match payload.downcast_ref::<String>() {
+                Some(s) => s.as_str(),
+                None => "Box<Any>",
+            },
+        };
+        let summary = match info.location() {
+            Some(location) => format!("panicked at '{}', {}", message, location),
+            None => format!("panicked at '{}'", message),
+        };
+
+        js! { @(no_return)
+            const stack = new Error().stack;
+            console.log(
+                "<font color=\"" + @{level_color(Level::Error)} + "\">"
+                    + @{&summary}
+                    + (stack ? "\n" + stack : "")
+                    + "</font>"
+            );
+        }
+        game::notify(&summary, None);
+    }));
+}
@@ -0,0 +1,245 @@
+//! Exporting per-tick metrics through a memory segment.
+//!
+//! [`TickStats::collect`] gathers CPU, GCL, creep and room numbers for the
+//! current tick, and [`TickStats::write_to_segment`] stores them as compact
+//! JSON for external tools, such as a Grafana agent polling the segment
+//! through the server's HTTP API, to read.
+//!
+//! ```no_run
+//! use screeps::{raw_memory::SegmentManager, stats::TickStats};
+//!
+//! let mut segments = SegmentManager::new();
+//!
+//! // at the end of the tick
+//! TickStats::collect()
+//!     .counter("remote_mines", 3.0)
+//!     .write_to_segment(&mut segments, 98)
+//!     .unwrap();
+//! segments.flush();
+//! ```
+//!
+//! The JSON uses short keys to keep the segment small:
+//!
+//! ```text
+//! {
+//!   "t": 12345,                          // tick
+//!   "cpu": {"u": 12.3, "b": 9000, "l": 20},  // used, bucket, limit
+//!   "gcl": {"l": 3, "p": 1234.5, "pt": 5000.0},  // level, progress, total
+//!   "c": 25,                             // creeps
+//!   "r": {"W1N1": {"e": 300, "ec": 550, "l": 2, "p": 1000, "pt": 45000}},
+//!   "n": {"remote_mines": 3.0}           // custom counters
+//! }
+//! ```
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game,
+    local::RoomName,
+    objects::OwnedStructureProperties,
+    raw_memory::{SegmentError, SegmentManager},
+};
+
+/// CPU usage at the time stats were collected.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CpuStats {
+    #[serde(rename = "u")]
+    pub used: f64,
+    #[serde(rename = "b")]
+    pub bucket: u32,
+    #[serde(rename = "l")]
+    pub limit: u32,
+}
+
+/// Global control level progress.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct GclStats {
+    #[serde(rename = "l")]
+    pub level: u32,
+    #[serde(rename = "p")]
+    pub progress: f64,
+    #[serde(rename = "pt")]
+    pub progress_total: f64,
+}
+
+/// Energy and controller progress of one owned room.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RoomStats {
+    #[serde(rename = "e")]
+    pub energy_available: u32,
+    #[serde(rename = "ec")]
+    pub energy_capacity_available: u32,
+    #[serde(rename = "l")]
+    pub controller_level: u32,
+    /// Controller progress, or `None` at level 8.
+    #[serde(rename = "p", default, skip_serializing_if = "Option::is_none")]
+    pub controller_progress: Option<u32>,
+    #[serde(rename = "pt", default, skip_serializing_if = "Option::is_none")]
+    pub controller_progress_total: Option<u32>,
+}
+
+/// Metrics for one tick, in the format written by
+/// [`TickStats::write_to_segment`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TickStats {
+    #[serde(rename = "t")]
+    pub tick: u32,
+    pub cpu: CpuStats,
+    pub gcl: GclStats,
+    /// Number of creeps owned.
+    #[serde(rename = "c")]
+    pub creeps: u32,
+    /// Stats for each room with a controller owned by you.
+    #[serde(rename = "r")]
+    pub rooms: BTreeMap<RoomName, RoomStats>,
+    /// Any other numbers to export, by name.
+    #[serde(rename = "n", default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub counters: BTreeMap<String, f64>,
+}
+
+impl TickStats {
+    /// Collects this tick's stats.
+    ///
+    /// CPU used is measured when this is called, so it's best called at the
+    /// end of the tick.
+    pub fn collect() -> TickStats {
+        let rooms = game::rooms::values()
+            .into_iter()
+            .filter_map(|room| {
+                let controller = room.controller().filter(|c| c.my())?;
+                let stats = RoomStats {
+                    energy_available: room.energy_available(),
+                    energy_capacity_available: room.energy_capacity_available(),
+                    controller_level: controller.level(),
+                    controller_progress: controller.progress(),
+                    controller_progress_total: controller.progress_total(),
+                };
+                Some((room.name(), stats))
+            })
+            .collect();
+
+        TickStats {
+            tick: game::time(),
+            cpu: CpuStats {
+                used: game::cpu::get_used(),
+                bucket: game::cpu::bucket(),
+                limit: game::cpu::limit(),
+            },
+            gcl: GclStats {
+                level: game::gcl::level(),
+                progress: game::gcl::progress(),
+                progress_total: game::gcl::progress_total(),
+            },
+            creeps: game::creeps::keys().len() as u32,
+            rooms,
+            counters: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a custom counter, replacing any counter with the same name.
+    pub fn counter(mut self, name: &str, value: f64) -> Self {
+        self.counters.insert(name.to_owned(), value);
+        self
+    }
+
+    /// Serializes these stats to be stored in memory segment `id` by the
+    /// next [`SegmentManager::flush`], replacing its contents.
+    pub fn write_to_segment(
+        &self,
+        segments: &mut SegmentManager,
+        id: u32,
+    ) -> Result<(), SegmentError> {
+        segments.set(id, self)
+    }
+
+    /// Reads stats previously written to memory segment `id`, like
+    /// [`SegmentManager::get`].
+    pub fn read_from_segment(
+        segments: &mut SegmentManager,
+        id: u32,
+    ) -> Result<Option<TickStats>, SegmentError> {
+        segments.get(id)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use super::{CpuStats, GclStats, RoomStats, TickStats};
+    use crate::local::RoomName;
+
+    #[test]
+    fn serializes_as_documented() {
+        let mut rooms = BTreeMap::new();
+        rooms.insert(
+            RoomName::new("W1N1").unwrap(),
+            RoomStats {
+                energy_available: 300,
+                energy_capacity_available: 550,
+                controller_level: 2,
+                controller_progress: Some(1000),
+                controller_progress_total: Some(45000),
+            },
+        );
+        let stats = TickStats {
+            tick: 12345,
+            cpu: CpuStats {
+                used: 12.3,
+                bucket: 9000,
+                limit: 20,
+            },
+            gcl: GclStats {
+                level: 3,
+                progress: 1234.5,
+                progress_total: 5000.0,
+            },
+            creeps: 25,
+            rooms,
+            counters: BTreeMap::new(),
+        }
+        .counter("remote_mines", 3.0);
+
+        let json = serde_json::to_string(&stats).unwrap();
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"t":12345,"cpu":{"u":12.3,"b":9000,"l":20},"#,
+                r#""gcl":{"l":3,"p":1234.5,"pt":5000.0},"c":25,"#,
+                r#""r":{"W1N1":{"e":300,"ec":550,"l":2,"p":1000,"pt":45000}},"#,
+                r#""n":{"remote_mines":3.0}}"#
+            )
+        );
+        assert_eq!(serde_json::from_str::<TickStats>(&json).unwrap(), stats);
+    }
+
+    #[test]
+    fn skips_empty_fields() {
+        let mut rooms = BTreeMap::new();
+        rooms.insert(
+            RoomName::new("E2S3").unwrap(),
+            RoomStats {
+                energy_available: 12900,
+                energy_capacity_available: 12900,
+                controller_level: 8,
+                controller_progress: None,
+                controller_progress_total: None,
+            },
+        );
+        let stats = TickStats {
+            rooms,
+            ..TickStats::default()
+        };
+
+        let json = serde_json::to_string(&stats).unwrap();
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"t":0,"cpu":{"u":0.0,"b":0,"l":0},"gcl":{"l":0,"p":0.0,"pt":0.0},"#,
+                r#""c":0,"r":{"E2S3":{"e":12900,"ec":12900,"l":8}}}"#
+            )
+        );
+        assert_eq!(serde_json::from_str::<TickStats>(&json).unwrap(), stats);
+    }
+}